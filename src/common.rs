@@ -84,3 +84,46 @@ where
         (raw >= self.0).then_some(raw)
     }
 }
+
+/// Processor, which passes the sequences through unchanged
+#[derive(Default, Copy, Clone, Debug)]
+pub struct NoProcessor;
+
+/// Preprocessing applied to both sequences before they are compared
+///
+/// This is implemented for [`NoProcessor`], [`DefaultProcessor`] and any function
+/// `Fn(Iter) -> Vec<Iter::Item>`.
+///
+/// [`DefaultProcessor`]: crate::utils::DefaultProcessor
+pub trait Processor<Iter>
+where
+    Iter: Iterator,
+{
+    type Output: DoubleEndedIterator<Item = Iter::Item> + Clone;
+
+    fn process(&self, s: Iter) -> Self::Output;
+}
+
+impl<Iter> Processor<Iter> for NoProcessor
+where
+    Iter: DoubleEndedIterator + Clone,
+{
+    type Output = Iter;
+
+    fn process(&self, s: Iter) -> Self::Output {
+        s
+    }
+}
+
+impl<Iter, F> Processor<Iter> for F
+where
+    Iter: Iterator,
+    Iter::Item: Clone,
+    F: Fn(Iter) -> Vec<Iter::Item>,
+{
+    type Output = std::vec::IntoIter<Iter::Item>;
+
+    fn process(&self, s: Iter) -> Self::Output {
+        self(s).into_iter()
+    }
+}
@@ -4,3 +4,6 @@ pub mod growing_hashmap;
 pub mod intrinsics;
 pub mod matrix;
 pub mod pattern_match_vector;
+pub mod splitted_sentence;
+#[cfg(test)]
+pub mod testing;
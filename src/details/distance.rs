@@ -1,6 +1,7 @@
 use crate::details::common::norm_sim_to_norm_dist;
 use crate::HashableChar;
 
+#[allow(dead_code)]
 pub trait MetricUsize2 {
     fn maximum(&self, len1: usize, len2: usize) -> usize;
 
@@ -21,8 +22,8 @@ pub trait MetricUsize2 {
     {
         let maximum = self.maximum(len1, len2);
 
-        let cutoff_similarity = score_cutoff.map(|x| if maximum >= x { maximum - x } else { 0 });
-        let hint_similarity = score_hint.map(|x| if maximum >= x { maximum - x } else { 0 });
+        let cutoff_similarity = score_cutoff.map(|x| maximum.saturating_sub(x));
+        let hint_similarity = score_hint.map(|x| maximum.saturating_sub(x));
 
         let sim = self._similarity(s1, len1, s2, len2, cutoff_similarity, hint_similarity)?;
         let dist = maximum - sim;
@@ -171,8 +172,8 @@ pub trait MetricUsize {
     {
         let maximum = self.maximum(len1, len2);
 
-        let cutoff_similarity = score_cutoff.map(|x| if maximum >= x { maximum - x } else { 0 });
-        let hint_similarity = score_hint.map(|x| if maximum >= x { maximum - x } else { 0 });
+        let cutoff_similarity = score_cutoff.map(|x| maximum.saturating_sub(x));
+        let hint_similarity = score_hint.map(|x| maximum.saturating_sub(x));
 
         let sim = self._similarity(s1, len1, s2, len2, cutoff_similarity, hint_similarity);
         maximum - sim
@@ -207,7 +208,8 @@ pub trait MetricUsize {
         let cutoff_distance = score_cutoff.map(|x| maximum - x);
         let hint_distance = score_hint.map(|x| maximum - x);
         let dist = self._distance(s1, len1, s2, len2, cutoff_distance, hint_distance);
-        maximum - dist
+        // distances above the cutoff may be reported as any larger value
+        maximum.saturating_sub(dist)
     }
 
     fn _normalized_distance<Iter1, Iter2>(
@@ -13,6 +13,7 @@ struct GrowingHashmapMapElem<ValueType> {
 /// - elements can't be removed
 /// - only allocates memory on first write access.
 ///   This improves performance for hashmaps that are never written to
+#[derive(Clone)]
 pub struct GrowingHashmap<ValueType> {
     used: i32,
     fill: i32,
@@ -156,6 +157,7 @@ where
     }
 }
 
+#[derive(Clone)]
 pub struct HybridGrowingHashmap<ValueType> {
     // todo in theory we have a fixed keytype here and so we wouldn't need both
     // an unsigned and signed map. In Practice this probably doesn't matter all that much
@@ -1,5 +1,5 @@
 pub const fn ceil_div_usize(a: usize, divisor: usize) -> usize {
-    a / divisor + (a % divisor != 0) as usize
+    a.div_ceil(divisor)
 }
 
 /// shift right without undefined behavior for shifts > bit width
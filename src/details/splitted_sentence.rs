@@ -0,0 +1,207 @@
+use crate::{Hash, HashableChar};
+use std::cmp::Ordering;
+
+/// checks whether an element is a whitespace character.
+///
+/// This uses the same definition of whitespace as Python's `str.split`
+pub fn is_space<CharT>(ch: &CharT) -> bool
+where
+    CharT: HashableChar,
+{
+    let code_point = match ch.hash_char() {
+        Hash::UNSIGNED(val) => val,
+        Hash::SIGNED(val) => {
+            if val < 0 {
+                return false;
+            }
+            val as u64
+        }
+    };
+
+    matches!(
+        code_point,
+        0x0009..=0x000D
+            | 0x001C..=0x001F
+            | 0x0020
+            | 0x0085
+            | 0x00A0
+            | 0x1680
+            | 0x2000..=0x200A
+            | 0x2028
+            | 0x2029
+            | 0x202F
+            | 0x205F
+            | 0x3000
+    )
+}
+
+/// element of a sequence of tokens joined by a single whitespace
+#[derive(Clone, Copy, Debug)]
+pub enum TokenElem<CharT> {
+    Char(CharT),
+    Separator,
+}
+
+impl<CharT1, CharT2> PartialEq<TokenElem<CharT2>> for TokenElem<CharT1>
+where
+    CharT1: PartialEq<CharT2>,
+{
+    #[inline]
+    fn eq(&self, other: &TokenElem<CharT2>) -> bool {
+        match (self, other) {
+            (TokenElem::Char(a), TokenElem::Char(b)) => a == b,
+            (TokenElem::Separator, TokenElem::Separator) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<CharT> HashableChar for TokenElem<CharT>
+where
+    CharT: HashableChar,
+{
+    #[inline]
+    fn hash_char(&self) -> Hash {
+        match self {
+            TokenElem::Char(ch) => ch.hash_char(),
+            // whitespace never ends up inside a token, so this can't collide
+            TokenElem::Separator => Hash::UNSIGNED(u64::from(b' ')),
+        }
+    }
+}
+
+fn hash_key<CharT>(ch: &CharT) -> i128
+where
+    CharT: HashableChar,
+{
+    match ch.hash_char() {
+        Hash::UNSIGNED(val) => i128::from(val),
+        Hash::SIGNED(val) => i128::from(val),
+    }
+}
+
+fn cmp_tokens<CharT>(a: &[CharT], b: &[CharT]) -> Ordering
+where
+    CharT: HashableChar,
+{
+    a.iter().map(hash_key).cmp(b.iter().map(hash_key))
+}
+
+fn tokens_equal<CharT1, CharT2>(a: &[CharT1], b: &[CharT2]) -> bool
+where
+    CharT1: PartialEq<CharT2>,
+{
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x == y)
+}
+
+/// whitespace separated words of a sequence
+#[derive(Clone, Debug)]
+pub struct SplittedSentence<CharT> {
+    tokens: Vec<Vec<CharT>>,
+}
+
+impl<CharT> SplittedSentence<CharT>
+where
+    CharT: HashableChar + Copy,
+{
+    /// splits the sequence on whitespace and sorts the resulting words
+    pub fn new_sorted<Iter>(s: Iter) -> Self
+    where
+        Iter: IntoIterator<Item = CharT>,
+    {
+        let mut tokens = Vec::new();
+        let mut token = Vec::new();
+        for ch in s {
+            if is_space(&ch) {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            } else {
+                token.push(ch);
+            }
+        }
+        if !token.is_empty() {
+            tokens.push(token);
+        }
+
+        tokens.sort_by(|a, b| cmp_tokens(a, b));
+        Self { tokens }
+    }
+
+    /// removes duplicated words. This requires the words to be sorted
+    pub fn dedupe(&mut self) {
+        self.tokens
+            .dedup_by(|a, b| cmp_tokens(a, b) == Ordering::Equal);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// length of the sequence created by [`SplittedSentence::join`]
+    pub fn length(&self) -> usize {
+        let chars: usize = self.tokens.iter().map(Vec::len).sum();
+        chars + self.tokens.len().saturating_sub(1)
+    }
+
+    /// joins the words separated by a single whitespace
+    pub fn join(&self) -> Vec<TokenElem<CharT>> {
+        let mut joined = Vec::with_capacity(self.length());
+        for (i, token) in self.tokens.iter().enumerate() {
+            if i != 0 {
+                joined.push(TokenElem::Separator);
+            }
+            joined.extend(token.iter().map(|&ch| TokenElem::Char(ch)));
+        }
+        joined
+    }
+}
+
+pub struct SetDecomposition<CharT1, CharT2> {
+    pub difference_ab: SplittedSentence<CharT1>,
+    pub difference_ba: SplittedSentence<CharT2>,
+    pub intersection: SplittedSentence<CharT1>,
+}
+
+/// splits two sets of deduplicated words into the words only found in `a`,
+/// the words only found in `b` and the words found in both of them
+pub fn set_decomposition<CharT1, CharT2>(
+    a: &SplittedSentence<CharT1>,
+    b: &SplittedSentence<CharT2>,
+) -> SetDecomposition<CharT1, CharT2>
+where
+    CharT1: PartialEq<CharT2> + Clone,
+    CharT2: Clone,
+{
+    let mut difference_ab = Vec::new();
+    let mut difference_ba = b.tokens.clone();
+    let mut intersection = Vec::new();
+
+    for token in &a.tokens {
+        if let Some(pos) = difference_ba
+            .iter()
+            .position(|other| tokens_equal(token, other))
+        {
+            difference_ba.remove(pos);
+            intersection.push(token.clone());
+        } else {
+            difference_ab.push(token.clone());
+        }
+    }
+
+    SetDecomposition {
+        difference_ab: SplittedSentence {
+            tokens: difference_ab,
+        },
+        difference_ba: SplittedSentence {
+            tokens: difference_ba,
+        },
+        intersection: SplittedSentence {
+            tokens: intersection,
+        },
+    }
+}
@@ -0,0 +1,43 @@
+//! Helpers to generate deterministic pseudo random test data
+
+use std::ops::RangeInclusive;
+
+/// Linear congruential generator, so tests are reproducible without extra dependencies
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// pseudo random number in the range `0..bound`
+    pub fn next(&mut self, bound: usize) -> usize {
+        self.state = self
+            .state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        ((self.state >> 33) % bound as u64) as usize
+    }
+
+    /// pseudo random letter out of the first `alphabet_size` lowercase letters
+    pub fn letter(&mut self, alphabet_size: usize) -> u8 {
+        b'a' + self.next(alphabet_size) as u8
+    }
+
+    /// sequence of `len` letters out of the first `alphabet_size` lowercase letters
+    pub fn string(&mut self, len: usize, alphabet_size: usize) -> Vec<u8> {
+        (0..len).map(|_| self.letter(alphabet_size)).collect()
+    }
+
+    /// `count` words with a length in `lengths` made of the letters `a` to `d`
+    pub fn words(&mut self, count: usize, lengths: RangeInclusive<usize>) -> Vec<String> {
+        (0..count)
+            .map(|_| {
+                let len = lengths.start() + self.next(lengths.end() - lengths.start() + 1);
+                self.string(len, 4).into_iter().map(char::from).collect()
+            })
+            .collect()
+    }
+}
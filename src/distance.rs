@@ -1,4 +1,6 @@
+pub mod affine_gap;
 pub mod damerau_levenshtein;
+mod editops;
 pub mod hamming;
 pub mod indel;
 pub mod jaro;
@@ -9,5 +11,462 @@ pub mod osa;
 pub mod postfix;
 pub mod prefix;
 
+pub use editops::{
+    EditType, Editop, Editops, EditopsError, MatchingBlock, Opcode, OpcodeType, Opcodes,
+};
+
+use crate::HashableChar;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+/// Interface shared by the metrics of all modules
+///
+/// Every module provides a marker type implementing this trait (e.g. [`levenshtein::Levenshtein`]),
+/// which allows writing code that is generic over the metric. The raw score is either a distance,
+/// where lower scores are better, or a similarity, where higher scores are better. Normalized
+/// scores are always in the range `[0.0, 1.0]`.
+///
+/// The marker types use the default configuration of their module, e.g. the uniform
+/// [`WeightTable`](levenshtein::WeightTable) for Levenshtein. The scoring functions return
+/// `None` when the score is worse than `score_cutoff`. The `BatchComparator` of each module
+/// implements the counterpart [`BatchMetric`].
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::{indel, levenshtein, Metric};
+///
+/// fn best<'a, M: Metric>(metric: &M, query: &str, choices: &[&'a str]) -> Option<&'a str> {
+///     let mut best: Option<(&str, M::Output)> = None;
+///     for &choice in choices {
+///         let cutoff = best.map(|(_, score)| score);
+///         if let Some(score) = metric.distance(query.chars(), choice.chars(), cutoff) {
+///             if best.map_or(true, |(_, best_score)| M::is_better(score, best_score)) {
+///                 best = Some((choice, score));
+///             }
+///         }
+///     }
+///     best.map(|(choice, _)| choice)
+/// }
+///
+/// let choices = ["kitchen", "sitting", "mitten"];
+/// assert_eq!(Some("mitten"), best(&levenshtein::Levenshtein, "kitten", &choices));
+/// assert_eq!(Some("mitten"), best(&indel::Indel, "kitten", &choices));
+/// ```
+pub trait Metric {
+    /// type of the raw score
+    type Output: Copy + PartialOrd + Debug;
+
+    /// `true` if the raw score is a similarity and `false` if it is a distance
+    const HIGHER_IS_BETTER: bool;
+
+    /// best possible raw score
+    const OPTIMAL_SCORE: Self::Output;
+
+    /// worst possible raw score
+    const WORST_SCORE: Self::Output;
+
+    /// compares two raw scores, ordering the better score first
+    fn compare(a: Self::Output, b: Self::Output) -> Ordering {
+        let ord = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+        if Self::HIGHER_IS_BETTER {
+            ord.reverse()
+        } else {
+            ord
+        }
+    }
+
+    /// `true` if the raw score `a` is strictly better than `b`
+    fn is_better(a: Self::Output, b: Self::Output) -> bool {
+        Self::compare(a, b) == Ordering::Less
+    }
+
+    /// Distance between `s1` and `s2` or `None` if it is larger than `score_cutoff`
+    fn distance<Iter1, Iter2>(
+        &self,
+        s1: Iter1,
+        s2: Iter2,
+        score_cutoff: Option<Self::Output>,
+    ) -> Option<Self::Output>
+    where
+        Iter1: IntoIterator,
+        Iter1::IntoIter: DoubleEndedIterator + Clone,
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy;
+
+    /// Similarity between `s1` and `s2` or `None` if it is smaller than `score_cutoff`
+    fn similarity<Iter1, Iter2>(
+        &self,
+        s1: Iter1,
+        s2: Iter2,
+        score_cutoff: Option<Self::Output>,
+    ) -> Option<Self::Output>
+    where
+        Iter1: IntoIterator,
+        Iter1::IntoIter: DoubleEndedIterator + Clone,
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy;
+
+    /// Normalized distance between `s1` and `s2` or `None` if it is larger than `score_cutoff`
+    fn normalized_distance<Iter1, Iter2>(
+        &self,
+        s1: Iter1,
+        s2: Iter2,
+        score_cutoff: Option<f64>,
+    ) -> Option<f64>
+    where
+        Iter1: IntoIterator,
+        Iter1::IntoIter: DoubleEndedIterator + Clone,
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy;
+
+    /// Normalized similarity between `s1` and `s2` or `None` if it is smaller than `score_cutoff`
+    fn normalized_similarity<Iter1, Iter2>(
+        &self,
+        s1: Iter1,
+        s2: Iter2,
+        score_cutoff: Option<f64>,
+    ) -> Option<f64>
+    where
+        Iter1: IntoIterator,
+        Iter1::IntoIter: DoubleEndedIterator + Clone,
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy;
+}
+
+/// Implements [`Metric`] for the marker type of a module
+///
+/// The scores are calculated by `$comparator`, which has to implement either
+/// `MetricUsize` or `Metricf64`. The implementing trait has to be in scope
+/// where the macro is used.
+macro_rules! impl_metric {
+    (
+        $marker:ty,
+        $output:ty,
+        higher_is_better: $higher_is_better:expr,
+        optimal_score: $optimal_score:expr,
+        worst_score: $worst_score:expr,
+        comparator: $comparator:expr $(,)?
+    ) => {
+        impl $crate::distance::Metric for $marker {
+            type Output = $output;
+            const HIGHER_IS_BETTER: bool = $higher_is_better;
+            const OPTIMAL_SCORE: $output = $optimal_score;
+            const WORST_SCORE: $output = $worst_score;
+
+            fn distance<Iter1, Iter2>(
+                &self,
+                s1: Iter1,
+                s2: Iter2,
+                score_cutoff: Option<$output>,
+            ) -> Option<$output>
+            where
+                Iter1: IntoIterator,
+                Iter1::IntoIter: DoubleEndedIterator + Clone,
+                Iter2: IntoIterator,
+                Iter2::IntoIter: DoubleEndedIterator + Clone,
+                Iter1::Item: PartialEq<Iter2::Item> + $crate::HashableChar + Copy,
+                Iter2::Item: PartialEq<Iter1::Item> + $crate::HashableChar + Copy,
+            {
+                let (s1, s2) = (s1.into_iter(), s2.into_iter());
+                let dist = $comparator._distance(
+                    s1.clone(),
+                    s1.count(),
+                    s2.clone(),
+                    s2.count(),
+                    score_cutoff,
+                    None,
+                );
+                match score_cutoff {
+                    Some(cutoff) if dist > cutoff => None,
+                    _ => Some(dist),
+                }
+            }
+
+            fn similarity<Iter1, Iter2>(
+                &self,
+                s1: Iter1,
+                s2: Iter2,
+                score_cutoff: Option<$output>,
+            ) -> Option<$output>
+            where
+                Iter1: IntoIterator,
+                Iter1::IntoIter: DoubleEndedIterator + Clone,
+                Iter2: IntoIterator,
+                Iter2::IntoIter: DoubleEndedIterator + Clone,
+                Iter1::Item: PartialEq<Iter2::Item> + $crate::HashableChar + Copy,
+                Iter2::Item: PartialEq<Iter1::Item> + $crate::HashableChar + Copy,
+            {
+                let (s1, s2) = (s1.into_iter(), s2.into_iter());
+                let sim = $comparator._similarity(
+                    s1.clone(),
+                    s1.count(),
+                    s2.clone(),
+                    s2.count(),
+                    score_cutoff,
+                    None,
+                );
+                match score_cutoff {
+                    Some(cutoff) if sim < cutoff => None,
+                    _ => Some(sim),
+                }
+            }
+
+            fn normalized_distance<Iter1, Iter2>(
+                &self,
+                s1: Iter1,
+                s2: Iter2,
+                score_cutoff: Option<f64>,
+            ) -> Option<f64>
+            where
+                Iter1: IntoIterator,
+                Iter1::IntoIter: DoubleEndedIterator + Clone,
+                Iter2: IntoIterator,
+                Iter2::IntoIter: DoubleEndedIterator + Clone,
+                Iter1::Item: PartialEq<Iter2::Item> + $crate::HashableChar + Copy,
+                Iter2::Item: PartialEq<Iter1::Item> + $crate::HashableChar + Copy,
+            {
+                let (s1, s2) = (s1.into_iter(), s2.into_iter());
+                let norm_dist = $comparator._normalized_distance(
+                    s1.clone(),
+                    s1.count(),
+                    s2.clone(),
+                    s2.count(),
+                    score_cutoff,
+                    None,
+                );
+                match score_cutoff {
+                    Some(cutoff) if norm_dist > cutoff => None,
+                    _ => Some(norm_dist),
+                }
+            }
+
+            fn normalized_similarity<Iter1, Iter2>(
+                &self,
+                s1: Iter1,
+                s2: Iter2,
+                score_cutoff: Option<f64>,
+            ) -> Option<f64>
+            where
+                Iter1: IntoIterator,
+                Iter1::IntoIter: DoubleEndedIterator + Clone,
+                Iter2: IntoIterator,
+                Iter2::IntoIter: DoubleEndedIterator + Clone,
+                Iter1::Item: PartialEq<Iter2::Item> + $crate::HashableChar + Copy,
+                Iter2::Item: PartialEq<Iter1::Item> + $crate::HashableChar + Copy,
+            {
+                let (s1, s2) = (s1.into_iter(), s2.into_iter());
+                let norm_sim = $comparator._normalized_similarity(
+                    s1.clone(),
+                    s1.count(),
+                    s2.clone(),
+                    s2.count(),
+                    score_cutoff,
+                    None,
+                );
+                match score_cutoff {
+                    Some(cutoff) if norm_sim < cutoff => None,
+                    _ => Some(norm_sim),
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use impl_metric;
+
+/// [`Metric`] counterpart for comparators caching the first sequence
+///
+/// The `BatchComparator` of each module (and the batch comparators in [`fuzz`](crate::fuzz))
+/// implement this trait, so generic code can compare many sequences with the same query
+/// without repeating its preprocessing. `Elem2` is the element type of the compared
+/// sequences. The raw scores are described like the scores of the module's marker type,
+/// e.g. [`levenshtein::Levenshtein`] for [`levenshtein::BatchComparator`].
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::{levenshtein, BatchMetric};
+/// use rapidfuzz::fuzz;
+///
+/// fn best<'a, B: BatchMetric<char>>(scorer: &B, choices: &[&'a str]) -> Option<&'a str> {
+///     let mut best: Option<(&str, B::Output)> = None;
+///     for &choice in choices {
+///         let cutoff = best.map(|(_, score)| score);
+///         let score = if B::HIGHER_IS_BETTER {
+///             scorer.similarity(choice.chars(), cutoff)
+///         } else {
+///             scorer.distance(choice.chars(), cutoff)
+///         };
+///         if let Some(score) = score {
+///             if best.map_or(true, |(_, best_score)| B::is_better(score, best_score)) {
+///                 best = Some((choice, score));
+///             }
+///         }
+///     }
+///     best.map(|(choice, _)| choice)
+/// }
+///
+/// let choices = ["kitchen", "sitting", "mitten"];
+/// let scorer = levenshtein::BatchComparator::new("kitten".chars());
+/// assert_eq!(Some("mitten"), best(&scorer, &choices));
+/// let scorer = fuzz::RatioBatchComparator::new("kitten".chars());
+/// assert_eq!(Some("mitten"), best(&scorer, &choices));
+/// ```
+pub trait BatchMetric<Elem2> {
+    /// type of the raw score
+    type Output: Copy + PartialOrd + Debug;
+
+    /// `true` if the raw score is a similarity and `false` if it is a distance
+    const HIGHER_IS_BETTER: bool;
+
+    /// best possible raw score
+    const OPTIMAL_SCORE: Self::Output;
+
+    /// worst possible raw score
+    const WORST_SCORE: Self::Output;
+
+    /// compares two raw scores, ordering the better score first
+    fn compare(a: Self::Output, b: Self::Output) -> Ordering {
+        let ord = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+        if Self::HIGHER_IS_BETTER {
+            ord.reverse()
+        } else {
+            ord
+        }
+    }
+
+    /// `true` if the raw score `a` is strictly better than `b`
+    fn is_better(a: Self::Output, b: Self::Output) -> bool {
+        Self::compare(a, b) == Ordering::Less
+    }
+
+    /// Distance to `s2` or `None` if it is larger than `score_cutoff`
+    fn distance<Iter2>(
+        &self,
+        s2: Iter2,
+        score_cutoff: Option<Self::Output>,
+    ) -> Option<Self::Output>
+    where
+        Iter2: IntoIterator<Item = Elem2>,
+        Iter2::IntoIter: DoubleEndedIterator + Clone;
+
+    /// Similarity to `s2` or `None` if it is smaller than `score_cutoff`
+    fn similarity<Iter2>(
+        &self,
+        s2: Iter2,
+        score_cutoff: Option<Self::Output>,
+    ) -> Option<Self::Output>
+    where
+        Iter2: IntoIterator<Item = Elem2>,
+        Iter2::IntoIter: DoubleEndedIterator + Clone;
+
+    /// Normalized distance to `s2` or `None` if it is larger than `score_cutoff`
+    fn normalized_distance<Iter2>(&self, s2: Iter2, score_cutoff: Option<f64>) -> Option<f64>
+    where
+        Iter2: IntoIterator<Item = Elem2>,
+        Iter2::IntoIter: DoubleEndedIterator + Clone;
+
+    /// Normalized similarity to `s2` or `None` if it is smaller than `score_cutoff`
+    fn normalized_similarity<Iter2>(&self, s2: Iter2, score_cutoff: Option<f64>) -> Option<f64>
+    where
+        Iter2: IntoIterator<Item = Elem2>,
+        Iter2::IntoIter: DoubleEndedIterator + Clone;
+}
+
+/// Implements [`BatchMetric`] for the `BatchComparator` of a module
+///
+/// The scores are calculated by the `*_with_args` methods of the comparator using
+/// `$args` and are described by the [`Metric`] implementation of `$marker`.
+macro_rules! impl_batch_metric {
+    ($comparator:ident, $marker:ty, args: $args:expr $(,)?) => {
+        impl<Elem1, Elem2> $crate::distance::BatchMetric<Elem2> for $comparator<Elem1>
+        where
+            Elem1: PartialEq<Elem2> + $crate::HashableChar + Copy,
+            Elem2: PartialEq<Elem1> + $crate::HashableChar + Copy,
+        {
+            type Output = <$marker as $crate::distance::Metric>::Output;
+            const HIGHER_IS_BETTER: bool = <$marker as $crate::distance::Metric>::HIGHER_IS_BETTER;
+            const OPTIMAL_SCORE: Self::Output =
+                <$marker as $crate::distance::Metric>::OPTIMAL_SCORE;
+            const WORST_SCORE: Self::Output = <$marker as $crate::distance::Metric>::WORST_SCORE;
+
+            fn distance<Iter2>(
+                &self,
+                s2: Iter2,
+                score_cutoff: Option<Self::Output>,
+            ) -> Option<Self::Output>
+            where
+                Iter2: IntoIterator<Item = Elem2>,
+                Iter2::IntoIter: DoubleEndedIterator + Clone,
+            {
+                match score_cutoff {
+                    Some(cutoff) => self.distance_with_args(s2, &$args.score_cutoff(cutoff)),
+                    None => Some(self.distance_with_args(s2, &$args)),
+                }
+            }
+
+            fn similarity<Iter2>(
+                &self,
+                s2: Iter2,
+                score_cutoff: Option<Self::Output>,
+            ) -> Option<Self::Output>
+            where
+                Iter2: IntoIterator<Item = Elem2>,
+                Iter2::IntoIter: DoubleEndedIterator + Clone,
+            {
+                match score_cutoff {
+                    Some(cutoff) => self.similarity_with_args(s2, &$args.score_cutoff(cutoff)),
+                    None => Some(self.similarity_with_args(s2, &$args)),
+                }
+            }
+
+            fn normalized_distance<Iter2>(
+                &self,
+                s2: Iter2,
+                score_cutoff: Option<f64>,
+            ) -> Option<f64>
+            where
+                Iter2: IntoIterator<Item = Elem2>,
+                Iter2::IntoIter: DoubleEndedIterator + Clone,
+            {
+                match score_cutoff {
+                    Some(cutoff) => {
+                        self.normalized_distance_with_args(s2, &$args.score_cutoff(cutoff))
+                    }
+                    None => Some(self.normalized_distance_with_args(s2, &$args)),
+                }
+            }
+
+            fn normalized_similarity<Iter2>(
+                &self,
+                s2: Iter2,
+                score_cutoff: Option<f64>,
+            ) -> Option<f64>
+            where
+                Iter2: IntoIterator<Item = Elem2>,
+                Iter2::IntoIter: DoubleEndedIterator + Clone,
+            {
+                match score_cutoff {
+                    Some(cutoff) => {
+                        self.normalized_similarity_with_args(s2, &$args.score_cutoff(cutoff))
+                    }
+                    None => Some(self.normalized_similarity_with_args(s2, &$args)),
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use impl_batch_metric;
+
 #[cfg(test)]
 pub(crate) mod example;
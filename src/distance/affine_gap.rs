@@ -0,0 +1,853 @@
+//! Affine gap distance
+//!
+//! The affine gap distance measures the minimum cost of transforming one sequence into
+//! another using `mismatches` and `gaps`. A gap is a run of consecutive insertions or
+//! deletions. Opening a gap costs `gap_open_cost`, while every further element of the same
+//! gap only costs `gap_extension_cost`. So a gap of length `k` costs
+//! `gap_open_cost + (k - 1) * gap_extension_cost`.
+//!
+//! This is useful when whole words are dropped, e.g. when comparing addresses. With the
+//! linear gap costs of the [`Levenshtein`] distance a single long deletion is penalized
+//! more than a couple of scattered edits.
+//!
+//! ```
+//! use rapidfuzz::distance::{affine_gap, levenshtein};
+//!
+//! // one gap of 7 elements
+//! assert_eq!(
+//!     9,
+//!     affine_gap::distance("12 Main Street London".chars(), "12 Main London".chars())
+//! );
+//! // three separate typos
+//! assert_eq!(
+//!     11,
+//!     affine_gap::distance("12 Main Street London".chars(), "12 Mian Stret Lodnon".chars())
+//! );
+//!
+//! assert_eq!(
+//!     7,
+//!     levenshtein::distance("12 Main Street London".chars(), "12 Main London".chars())
+//! );
+//! assert_eq!(
+//!     5,
+//!     levenshtein::distance("12 Main Street London".chars(), "12 Mian Stret Lodnon".chars())
+//! );
+//! ```
+//!
+//! [`Levenshtein`]: ../levenshtein/index.html
+//!
+//! # Performance
+//!
+//! The implementation has a runtime complexity of `O(N*M)` and a memory usage of `O(M)`.
+//! It's based on the paper `An improved algorithm for matching biological sequences`
+//! from Osamu Gotoh and exits early once every cell of a row exceeds the `score_cutoff`.
+//!
+
+use crate::common::{
+    DistanceCutoff, NoProcessor, NoScoreCutoff, Processor, SimilarityCutoff, WithScoreCutoff,
+};
+use crate::details::distance::MetricUsize;
+use crate::distance::{impl_batch_metric, impl_metric};
+use crate::HashableChar;
+use std::cmp::min;
+
+#[must_use]
+#[derive(Copy, Clone, Debug)]
+pub struct Args<ResultType, CutoffType, ProcessorType = NoProcessor> {
+    score_cutoff: CutoffType,
+    score_hint: Option<ResultType>,
+    weights: WeightTable,
+    processor: ProcessorType,
+}
+
+impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
+    fn default() -> Args<ResultType, NoScoreCutoff> {
+        Args {
+            score_cutoff: NoScoreCutoff,
+            score_hint: None,
+            weights: WeightTable::default(),
+            processor: NoProcessor,
+        }
+    }
+}
+
+impl<ResultType, CutoffType, ProcessorType> Args<ResultType, CutoffType, ProcessorType> {
+    pub fn score_hint(mut self, score_hint: ResultType) -> Self {
+        self.score_hint = Some(score_hint);
+        self
+    }
+
+    pub fn score_cutoff(
+        self,
+        score_cutoff: ResultType,
+    ) -> Args<ResultType, WithScoreCutoff<ResultType>, ProcessorType> {
+        Args {
+            score_hint: self.score_hint,
+            score_cutoff: WithScoreCutoff(score_cutoff),
+            weights: self.weights,
+            processor: self.processor,
+        }
+    }
+
+    pub fn weights(mut self, weights: &WeightTable) -> Self {
+        self.weights = *weights;
+        self
+    }
+
+    /// preprocessing applied to both sequences before comparing them
+    pub fn processor<P>(self, processor: P) -> Args<ResultType, CutoffType, P> {
+        Args {
+            score_cutoff: self.score_cutoff,
+            score_hint: self.score_hint,
+            weights: self.weights,
+            processor,
+        }
+    }
+}
+
+/// Weight table to specify the costs of edit operations in the affine gap distance
+///
+/// The weights are expected to satisfy `gap_extension_cost <= gap_open_cost`. Otherwise
+/// it is cheaper to split a gap into multiple gaps of length 1, which is taken into account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WeightTable {
+    /// cost of the first element of a gap
+    pub gap_open_cost: usize,
+    /// cost of every further element of a gap
+    pub gap_extension_cost: usize,
+    /// cost of aligning two different elements
+    pub mismatch_cost: usize,
+}
+
+impl Default for WeightTable {
+    /// creates a weight table with a gap opening cost of 3, a gap extension cost of 1
+    /// and a mismatch cost of 2
+    fn default() -> Self {
+        Self {
+            gap_open_cost: 3,
+            gap_extension_cost: 1,
+            mismatch_cost: 2,
+        }
+    }
+}
+
+impl WeightTable {
+    /// cost of a single gap of length `len`
+    fn gap_cost(&self, len: usize) -> usize {
+        if len == 0 {
+            0
+        } else {
+            self.gap_open_cost + (len - 1) * self.gap_extension_cost
+        }
+    }
+
+    /// cost of `len` consecutive insertions or deletions. They might be split into
+    /// multiple gaps of length 1, if this is cheaper
+    fn gaps_cost(&self, len: usize) -> usize {
+        min(self.gap_cost(len), len * self.gap_open_cost)
+    }
+
+    /// calculates the maximum possible distance based on string lengths
+    fn maximum(&self, len1: usize, len2: usize) -> usize {
+        min(
+            self.gaps_cost(len1) + self.gaps_cost(len2),
+            min(len1, len2) * self.mismatch_cost + self.gaps_cost(len1.abs_diff(len2)),
+        )
+    }
+
+    /// calculates the minimum possible distance based on string lengths
+    fn min_distance(&self, len1: usize, len2: usize) -> usize {
+        self.gaps_cost(len1.abs_diff(len2))
+    }
+}
+
+/// based on the paper
+/// "An improved algorithm for matching biological sequences"
+/// from Osamu Gotoh
+///
+/// Besides the distance of each cell, the distance of the best alignment ending
+/// with a gap is stored, so gaps can be extended.
+fn gotoh<Iter1, Iter2>(
+    s1: Iter1,
+    s2: Iter2,
+    len2: usize,
+    weights: &WeightTable,
+    score_cutoff: usize,
+) -> usize
+where
+    Iter1: Iterator,
+    Iter2: Iterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item>,
+{
+    // distance of the previous row and of alignments of it ending with a deletion
+    let mut dist: Vec<usize> = (0..=len2).map(|j| weights.gaps_cost(j)).collect();
+    let mut deletion = vec![usize::MAX; len2 + 1];
+
+    for (i, ch1) in s1.enumerate().map(|(i, ch1)| (i + 1, ch1)) {
+        let mut diag = dist[0];
+        dist[0] = weights.gaps_cost(i);
+        deletion[0] = dist[0];
+        // distance of alignments of the current row ending with an insertion
+        let mut insertion = usize::MAX;
+        let mut row_min = dist[0];
+
+        for (j, ch2) in s2.clone().enumerate().map(|(j, ch2)| (j + 1, ch2)) {
+            deletion[j] = min(
+                dist[j].saturating_add(weights.gap_open_cost),
+                deletion[j].saturating_add(weights.gap_extension_cost),
+            );
+            insertion = min(
+                dist[j - 1].saturating_add(weights.gap_open_cost),
+                insertion.saturating_add(weights.gap_extension_cost),
+            );
+            let mismatch_cost = if ch1 == ch2 { 0 } else { weights.mismatch_cost };
+            let temp = min(diag + mismatch_cost, min(deletion[j], insertion));
+
+            diag = dist[j];
+            dist[j] = temp;
+            row_min = min(row_min, temp);
+        }
+
+        // costs are never negative, so the distance can't drop below the row minimum
+        if row_min > score_cutoff {
+            return score_cutoff + 1;
+        }
+    }
+
+    dist[len2]
+}
+
+fn distance_impl<Iter1, Iter2>(
+    s1: Iter1,
+    len1: usize,
+    s2: Iter2,
+    len2: usize,
+    weights: &WeightTable,
+    score_cutoff: usize,
+) -> usize
+where
+    Iter1: Iterator,
+    Iter2: Iterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item>,
+{
+    if score_cutoff < weights.min_distance(len1, len2) {
+        return usize::MAX;
+    }
+
+    gotoh(s1, s2, len2, weights, score_cutoff)
+}
+
+struct IndividualComparator {
+    weights: WeightTable,
+}
+
+impl MetricUsize for IndividualComparator {
+    fn maximum(&self, len1: usize, len2: usize) -> usize {
+        self.weights.maximum(len1, len2)
+    }
+
+    fn _distance<Iter1, Iter2>(
+        &self,
+        s1: Iter1,
+        len1: usize,
+        s2: Iter2,
+        len2: usize,
+        score_cutoff: Option<usize>,
+        _score_hint: Option<usize>,
+    ) -> usize
+    where
+        Iter1: DoubleEndedIterator + Clone,
+        Iter2: DoubleEndedIterator + Clone,
+        Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    {
+        distance_impl(
+            s1,
+            len1,
+            s2,
+            len2,
+            &self.weights,
+            score_cutoff.unwrap_or(usize::MAX),
+        )
+    }
+}
+
+/// Affine gap distance
+///
+/// Calculates the minimum cost of aligning both sequences, where a gap of length `k`
+/// costs `gap_open_cost + (k - 1) * gap_extension_cost`.
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::affine_gap;
+///
+/// assert_eq!(5, affine_gap::distance("kitten".chars(), "kit".chars()));
+/// ```
+pub fn distance<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> usize
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    distance_with_args(s1, s2, &Args::default())
+}
+
+pub fn distance_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
+    s1: Iter1,
+    s2: Iter2,
+    args: &Args<usize, CutoffType, ProcessorType>,
+) -> CutoffType::Output
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    CutoffType: DistanceCutoff<usize>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+{
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
+    args.score_cutoff.score(
+        IndividualComparator {
+            weights: args.weights,
+        }
+        ._distance(
+            s1_iter.clone(),
+            s1_iter.count(),
+            s2_iter.clone(),
+            s2_iter.count(),
+            args.score_cutoff.cutoff(),
+            args.score_hint,
+        ),
+    )
+}
+
+/// Affine gap similarity in the range [0, max]
+///
+/// This is calculated as `maximum - `[`distance`]. Where maximum is defined as
+/// ```notrust
+/// maximum = min(
+///     gaps_cost(len1) + gaps_cost(len2),
+///     min(len1, len2) * mismatch_cost + gaps_cost(abs(len1 - len2))
+/// )
+/// gaps_cost(k) = min(gap_open_cost + (k - 1) * gap_extension_cost, k * gap_open_cost)
+/// ```
+///
+pub fn similarity<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> usize
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    similarity_with_args(s1, s2, &Args::default())
+}
+
+pub fn similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
+    s1: Iter1,
+    s2: Iter2,
+    args: &Args<usize, CutoffType, ProcessorType>,
+) -> CutoffType::Output
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    CutoffType: SimilarityCutoff<usize>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+{
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
+    args.score_cutoff.score(
+        IndividualComparator {
+            weights: args.weights,
+        }
+        ._similarity(
+            s1_iter.clone(),
+            s1_iter.count(),
+            s2_iter.clone(),
+            s2_iter.count(),
+            args.score_cutoff.cutoff(),
+            args.score_hint,
+        ),
+    )
+}
+
+/// Normalized affine gap distance in the range [1.0, 0.0]
+///
+/// This is calculated as [`distance`]` / maximum`.
+///
+pub fn normalized_distance<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    normalized_distance_with_args(s1, s2, &Args::default())
+}
+
+pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
+    s1: Iter1,
+    s2: Iter2,
+    args: &Args<f64, CutoffType, ProcessorType>,
+) -> CutoffType::Output
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    CutoffType: DistanceCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+{
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
+    args.score_cutoff.score(
+        IndividualComparator {
+            weights: args.weights,
+        }
+        ._normalized_distance(
+            s1_iter.clone(),
+            s1_iter.count(),
+            s2_iter.clone(),
+            s2_iter.count(),
+            args.score_cutoff.cutoff(),
+            args.score_hint,
+        ),
+    )
+}
+
+/// Normalized affine gap similarity in the range [0.0, 1.0]
+///
+/// This is calculated as `1.0 - `[`normalized_distance`].
+///
+pub fn normalized_similarity<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    normalized_similarity_with_args(s1, s2, &Args::default())
+}
+
+pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
+    s1: Iter1,
+    s2: Iter2,
+    args: &Args<f64, CutoffType, ProcessorType>,
+) -> CutoffType::Output
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    CutoffType: SimilarityCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+{
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
+    args.score_cutoff.score(
+        IndividualComparator {
+            weights: args.weights,
+        }
+        ._normalized_similarity(
+            s1_iter.clone(),
+            s1_iter.count(),
+            s2_iter.clone(),
+            s2_iter.count(),
+            args.score_cutoff.cutoff(),
+            args.score_hint,
+        ),
+    )
+}
+
+/// Marker type describing the affine gap distance, see [`Metric`](crate::distance::Metric)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct AffineGap;
+
+impl_metric!(
+    AffineGap,
+    usize,
+    higher_is_better: false,
+    optimal_score: 0,
+    worst_score: usize::MAX,
+    comparator: IndividualComparator {
+        weights: WeightTable::default(),
+    },
+);
+
+struct BatchComparatorImpl<'a> {
+    weights: &'a WeightTable,
+}
+
+impl MetricUsize for BatchComparatorImpl<'_> {
+    fn maximum(&self, len1: usize, len2: usize) -> usize {
+        self.weights.maximum(len1, len2)
+    }
+
+    fn _distance<Iter1, Iter2>(
+        &self,
+        s1: Iter1,
+        len1: usize,
+        s2: Iter2,
+        len2: usize,
+        score_cutoff: Option<usize>,
+        _score_hint: Option<usize>,
+    ) -> usize
+    where
+        Iter1: DoubleEndedIterator + Clone,
+        Iter2: DoubleEndedIterator + Clone,
+        Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    {
+        let score_cutoff = score_cutoff.unwrap_or(usize::MAX);
+        if score_cutoff < self.weights.min_distance(len1, len2) {
+            return usize::MAX;
+        }
+
+        // the distance is symmetric, so the cached `s1` is used as columns, which
+        // are iterated once per row
+        gotoh(s2, s1, len1, self.weights, score_cutoff)
+    }
+}
+
+/// `One x Many` comparisons using the affine gap distance
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::affine_gap;
+///
+/// let scorer = affine_gap::BatchComparator::new("kitten".chars());
+/// assert_eq!(5, scorer.distance("kit".chars()));
+/// ```
+#[derive(Clone)]
+pub struct BatchComparator<Elem1> {
+    s1: Vec<Elem1>,
+}
+
+impl<Elem1> BatchComparator<Elem1>
+where
+    Elem1: HashableChar + Clone,
+{
+    pub fn new<Iter1>(s1: Iter1) -> Self
+    where
+        Iter1: IntoIterator<Item = Elem1>,
+    {
+        Self {
+            s1: s1.into_iter().collect(),
+        }
+    }
+
+    /// Normalized distance calculated similar to [`normalized_distance`]
+    pub fn normalized_distance<Iter2>(&self, s2: Iter2) -> f64
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.normalized_distance_with_args(s2, &Args::default())
+    }
+
+    pub fn normalized_distance_with_args<Iter2, CutoffType>(
+        &self,
+        s2: Iter2,
+        args: &Args<f64, CutoffType>,
+    ) -> CutoffType::Output
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+        CutoffType: DistanceCutoff<f64>,
+    {
+        let s2_iter = s2.into_iter();
+        args.score_cutoff.score(
+            BatchComparatorImpl {
+                weights: &args.weights,
+            }
+            ._normalized_distance(
+                self.s1.iter().copied(),
+                self.s1.len(),
+                s2_iter.clone(),
+                s2_iter.count(),
+                args.score_cutoff.cutoff(),
+                args.score_hint,
+            ),
+        )
+    }
+
+    /// Normalized similarity calculated similar to [`normalized_similarity`]
+    pub fn normalized_similarity<Iter2>(&self, s2: Iter2) -> f64
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.normalized_similarity_with_args(s2, &Args::default())
+    }
+
+    pub fn normalized_similarity_with_args<Iter2, CutoffType>(
+        &self,
+        s2: Iter2,
+        args: &Args<f64, CutoffType>,
+    ) -> CutoffType::Output
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+        CutoffType: SimilarityCutoff<f64>,
+    {
+        let s2_iter = s2.into_iter();
+        args.score_cutoff.score(
+            BatchComparatorImpl {
+                weights: &args.weights,
+            }
+            ._normalized_similarity(
+                self.s1.iter().copied(),
+                self.s1.len(),
+                s2_iter.clone(),
+                s2_iter.count(),
+                args.score_cutoff.cutoff(),
+                args.score_hint,
+            ),
+        )
+    }
+
+    /// Distance calculated similar to [`distance`]
+    pub fn distance<Iter2>(&self, s2: Iter2) -> usize
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.distance_with_args(s2, &Args::default())
+    }
+
+    pub fn distance_with_args<Iter2, CutoffType>(
+        &self,
+        s2: Iter2,
+        args: &Args<usize, CutoffType>,
+    ) -> CutoffType::Output
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+        CutoffType: DistanceCutoff<usize>,
+    {
+        let s2_iter = s2.into_iter();
+        args.score_cutoff.score(
+            BatchComparatorImpl {
+                weights: &args.weights,
+            }
+            ._distance(
+                self.s1.iter().copied(),
+                self.s1.len(),
+                s2_iter.clone(),
+                s2_iter.count(),
+                args.score_cutoff.cutoff(),
+                args.score_hint,
+            ),
+        )
+    }
+
+    /// Similarity calculated similar to [`similarity`]
+    pub fn similarity<Iter2>(&self, s2: Iter2) -> usize
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.similarity_with_args(s2, &Args::default())
+    }
+
+    pub fn similarity_with_args<Iter2, CutoffType>(
+        &self,
+        s2: Iter2,
+        args: &Args<usize, CutoffType>,
+    ) -> CutoffType::Output
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+        CutoffType: SimilarityCutoff<usize>,
+    {
+        let s2_iter = s2.into_iter();
+        args.score_cutoff.score(
+            BatchComparatorImpl {
+                weights: &args.weights,
+            }
+            ._similarity(
+                self.s1.iter().copied(),
+                self.s1.len(),
+                s2_iter.clone(),
+                s2_iter.count(),
+                args.score_cutoff.cutoff(),
+                args.score_hint,
+            ),
+        )
+    }
+}
+
+impl_batch_metric!(BatchComparator, AffineGap, args: Args::default());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::details::testing::Lcg;
+
+    /// minimum cost of all alignments of `s1` and `s2`. `gap` is the kind of gap
+    /// the alignment currently ends with, which can be extended or followed by a new gap
+    fn brute_force(s1: &[u8], s2: &[u8], weights: &WeightTable, gap: Option<bool>) -> usize {
+        let open = |deletion: bool| {
+            if gap == Some(deletion) {
+                min(weights.gap_extension_cost, weights.gap_open_cost)
+            } else {
+                weights.gap_open_cost
+            }
+        };
+
+        let mut best = usize::MAX;
+        if let (Some((ch1, rest1)), Some((ch2, rest2))) = (s1.split_first(), s2.split_first()) {
+            let mismatch_cost = if ch1 == ch2 { 0 } else { weights.mismatch_cost };
+            best = min(
+                best,
+                mismatch_cost + brute_force(rest1, rest2, weights, None),
+            );
+        }
+        if let Some((_, rest1)) = s1.split_first() {
+            best = min(
+                best,
+                open(true) + brute_force(rest1, s2, weights, Some(true)),
+            );
+        }
+        if let Some((_, rest2)) = s2.split_first() {
+            best = min(
+                best,
+                open(false) + brute_force(s1, rest2, weights, Some(false)),
+            );
+        }
+        if s1.is_empty() && s2.is_empty() {
+            best = 0;
+        }
+        best
+    }
+
+    #[test]
+    fn test_brute_force() {
+        let mut rng = Lcg::new(9);
+
+        let weight_tables = [
+            WeightTable::default(),
+            WeightTable {
+                gap_open_cost: 1,
+                gap_extension_cost: 1,
+                mismatch_cost: 1,
+            },
+            WeightTable {
+                gap_open_cost: 5,
+                gap_extension_cost: 0,
+                mismatch_cost: 3,
+            },
+            WeightTable {
+                gap_open_cost: 4,
+                gap_extension_cost: 2,
+                mismatch_cost: 1,
+            },
+            WeightTable {
+                gap_open_cost: 1,
+                gap_extension_cost: 5,
+                mismatch_cost: 2,
+            },
+        ];
+        for _ in 0..40 {
+            let s1 = rng.string(6, 3);
+            let s2 = rng.string(4, 3);
+            for weights in &weight_tables {
+                let expected = brute_force(&s1, &s2, weights, None);
+                let args = Args::default().weights(weights);
+                assert_eq!(expected, distance_with_args(s1.iter(), s2.iter(), &args));
+                assert_eq!(expected, distance_with_args(s2.iter(), s1.iter(), &args));
+
+                let scorer = BatchComparator::new(s1.iter().copied());
+                assert_eq!(
+                    expected,
+                    scorer.distance_with_args(s2.iter().copied(), &args)
+                );
+                assert_eq!(
+                    weights.maximum(s1.len(), s2.len()) - expected,
+                    similarity_with_args(s1.iter(), s2.iter(), &args)
+                );
+
+                for score_cutoff in 0..expected + 2 {
+                    let args = Args::default().weights(weights).score_cutoff(score_cutoff);
+                    let expected = (expected <= score_cutoff).then_some(expected);
+                    assert_eq!(expected, distance_with_args(s1.iter(), s2.iter(), &args));
+                    assert_eq!(
+                        expected,
+                        scorer.distance_with_args(s2.iter().copied(), &args)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_simple() {
+        assert_eq!(0, distance("".chars(), "".chars()));
+        assert_eq!(0, distance("abc".chars(), "abc".chars()));
+        assert_eq!(5, distance("abc".chars(), "".chars()));
+        assert_eq!(7, distance("abcdef".chars(), "a".chars()));
+        assert_eq!(2, distance("abc".chars(), "abd".chars()));
+        assert_eq!(6, distance("abc".chars(), "b".chars()));
+
+        // with expensive extensions gaps are split, including leading and trailing gaps
+        let args = Args::default().weights(&WeightTable {
+            gap_open_cost: 1,
+            gap_extension_cost: 5,
+            mismatch_cost: 2,
+        });
+        assert_eq!(3, distance_with_args("".chars(), "abc".chars(), &args));
+        assert_eq!(3, distance_with_args("x".chars(), "xabc".chars(), &args));
+        assert_eq!(3, distance_with_args("abc".chars(), "".chars(), &args));
+        assert_eq!(3, distance_with_args("abcx".chars(), "x".chars(), &args));
+        assert_eq!(0, similarity_with_args("abc".chars(), "".chars(), &args));
+
+        assert_eq!(8, similarity("abcdef".chars(), "abcd".chars()));
+        assert_eq!(0.0, normalized_distance("abc".chars(), "abc".chars()));
+        assert_eq!(1.0, normalized_distance("abc".chars(), "xyz".chars()));
+        assert_eq!(
+            None,
+            normalized_similarity_with_args(
+                "abc".chars(),
+                "xyz".chars(),
+                &Args::default().score_cutoff(0.5)
+            )
+        );
+    }
+}
@@ -43,6 +43,8 @@
 //! # Performance
 //!
 //! The implementation has a runtime complexity of `O(N*M)` and a memory usage of `O(N+M)`.
+//! With a `score_cutoff` `K` only a band of the matrix is calculated, which reduces the
+//! runtime to `O(N*K)`.
 //! It's based on the paper
 //! `Linear space string correction algorithm using the Damerau-Levenshtein distance`
 //! from Chunchun Zhao and Sartaj Sahni
@@ -50,19 +52,24 @@
 //! ![benchmark results](https://raw.githubusercontent.com/rapidfuzz/rapidfuzz-rs/main/rapidfuzz-benches/results/damerau_levenshtein.svg)
 //!
 
-use crate::common::{DistanceCutoff, NoScoreCutoff, SimilarityCutoff, WithScoreCutoff};
+use crate::common::{
+    DistanceCutoff, NoProcessor, NoScoreCutoff, Processor, SimilarityCutoff, WithScoreCutoff,
+};
 use crate::details::common::remove_common_affix;
 use crate::details::distance::MetricUsize;
 use crate::details::growing_hashmap::{GrowingHashmap, HybridGrowingHashmap};
+use crate::distance::{impl_batch_metric, impl_metric};
 use crate::HashableChar;
 use std::cmp::{max, min};
 use std::mem;
 
 #[must_use]
 #[derive(Copy, Clone, Debug)]
-pub struct Args<ResultType, CutoffType> {
+pub struct Args<ResultType, CutoffType, ProcessorType = NoProcessor> {
     pub(crate) score_cutoff: CutoffType,
     pub(crate) score_hint: Option<ResultType>,
+    pub(crate) weights: WeightTable,
+    pub(crate) processor: ProcessorType,
 }
 
 impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
@@ -70,11 +77,13 @@ impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
         Args {
             score_cutoff: NoScoreCutoff,
             score_hint: None,
+            weights: WeightTable::default(),
+            processor: NoProcessor,
         }
     }
 }
 
-impl<ResultType, CutoffType> Args<ResultType, CutoffType> {
+impl<ResultType, CutoffType, ProcessorType> Args<ResultType, CutoffType, ProcessorType> {
     pub fn score_hint(mut self, score_hint: ResultType) -> Self {
         self.score_hint = Some(score_hint);
         self
@@ -83,10 +92,95 @@ impl<ResultType, CutoffType> Args<ResultType, CutoffType> {
     pub fn score_cutoff(
         self,
         score_cutoff: ResultType,
-    ) -> Args<ResultType, WithScoreCutoff<ResultType>> {
+    ) -> Args<ResultType, WithScoreCutoff<ResultType>, ProcessorType> {
         Args {
             score_hint: self.score_hint,
             score_cutoff: WithScoreCutoff(score_cutoff),
+            weights: self.weights,
+            processor: self.processor,
+        }
+    }
+
+    pub fn weights(mut self, weights: &WeightTable) -> Self {
+        self.weights = *weights;
+        self
+    }
+
+    /// preprocessing applied to both sequences before comparing them
+    pub fn processor<P>(self, processor: P) -> Args<ResultType, CutoffType, P> {
+        Args {
+            score_cutoff: self.score_cutoff,
+            score_hint: self.score_hint,
+            weights: self.weights,
+            processor,
+        }
+    }
+}
+
+/// Weight table to specify the costs of edit operations in the Damerau-Levenshtein
+/// and OSA distance
+///
+/// For the Damerau-Levenshtein distance the weights are expected to satisfy
+/// `2 * transposition_cost >= insertion_cost + deletion_cost`. Otherwise it can be
+/// cheaper to transpose elements multiple times, which is not taken into account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WeightTable {
+    /// cost of insertions
+    pub insertion_cost: usize,
+    /// cost of deletions
+    pub deletion_cost: usize,
+    /// cost of substitutions
+    pub substitution_cost: usize,
+    /// cost of transpositions
+    pub transposition_cost: usize,
+}
+
+impl Default for WeightTable {
+    /// creates a uniform weight table where each operation has a cost of 1
+    fn default() -> Self {
+        Self {
+            insertion_cost: 1,
+            deletion_cost: 1,
+            substitution_cost: 1,
+            transposition_cost: 1,
+        }
+    }
+}
+
+impl WeightTable {
+    /// common cost of all operations if they all have the same cost
+    pub(crate) fn uniform_cost(&self) -> Option<usize> {
+        let cost = self.insertion_cost;
+        (self.deletion_cost == cost
+            && self.substitution_cost == cost
+            && self.transposition_cost == cost)
+            .then_some(cost)
+    }
+
+    /// calculates the maximum possible distance based on string lengths.
+    /// Transpositions are never required to reach it
+    pub(crate) fn maximum(&self, len1: usize, len2: usize) -> usize {
+        let max_dist = len1 * self.deletion_cost + len2 * self.insertion_cost;
+
+        if len1 >= len2 {
+            min(
+                max_dist,
+                len2 * self.substitution_cost + (len1 - len2) * self.deletion_cost,
+            )
+        } else {
+            min(
+                max_dist,
+                len1 * self.substitution_cost + (len2 - len1) * self.insertion_cost,
+            )
+        }
+    }
+
+    /// calculates the minimum possible distance based on string lengths
+    pub(crate) fn min_distance(&self, len1: usize, len2: usize) -> usize {
+        if len1 >= len2 {
+            (len1 - len2) * self.deletion_cost
+        } else {
+            (len2 - len1) * self.insertion_cost
         }
     }
 }
@@ -102,55 +196,120 @@ impl Default for RowId {
     }
 }
 
+/// sequence used as columns in [`distance_zhao`] with each distinct element
+/// mapped to a dense id, so the last occurrences can be stored in a `Vec`
+#[derive(Clone)]
+struct ColumnIds {
+    /// ids of the elements. Elements not part of the sequence map to -1
+    map: HybridGrowingHashmap<RowId>,
+    ids: Vec<usize>,
+    count: usize,
+}
+
+impl ColumnIds {
+    fn new<Iter>(s: Iter) -> Self
+    where
+        Iter: Iterator,
+        Iter::Item: HashableChar,
+    {
+        let mut map = HybridGrowingHashmap::<RowId> {
+            map_unsigned: GrowingHashmap::default(),
+            map_signed: GrowingHashmap::default(),
+            extended_ascii: [RowId::default(); 256],
+        };
+        let mut count = 0;
+        let ids = s
+            .map(|ch| {
+                let id = map.get_mut(ch);
+                if id.val < 0 {
+                    id.val = count as isize;
+                    count += 1;
+                }
+                id.val as usize
+            })
+            .collect();
+
+        Self { map, ids, count }
+    }
+}
+
 /// based on the paper
 /// "Linear space string correction algorithm using the Damerau-Levenshtein distance"
 /// from Chunchun Zhao and Sartaj Sahni
 ///
+/// `s2` are the ids of a subslice of `columns`. Only the diagonals which can still lead to
+/// a distance of at most `score_cutoff` are calculated. A distance above `score_cutoff`
+/// might be overestimated.
+///
 /// todo in c++ this is templated on an integer type which reduced
 /// memory usage depending on string lengths
-fn distance_zhao<Iter1, Iter2>(s1: Iter1, len1: usize, s2: Iter2, len2: usize) -> usize
+fn distance_zhao<Iter1>(
+    s1: Iter1,
+    len1: usize,
+    columns: &ColumnIds,
+    s2: &[usize],
+    score_cutoff: usize,
+) -> usize
 where
     Iter1: Iterator,
-    Iter2: Iterator + Clone,
-    Iter1::Item: PartialEq<Iter2::Item> + HashableChar,
-    Iter2::Item: PartialEq<Iter1::Item> + HashableChar,
+    Iter1::Item: HashableChar,
 {
+    let len2 = s2.len();
     let max_val = max(len1, len2) as isize + 1;
 
-    let mut last_row_id = HybridGrowingHashmap::<RowId> {
-        map_unsigned: GrowingHashmap::default(),
-        map_signed: GrowingHashmap::default(),
-        extended_ascii: [RowId::default(); 256],
-    };
+    // every alignment ending on diagonal `len1 - len2` that passes diagonal `d`
+    // costs at least `|d| + |len1 - len2 - d|`. A small margin keeps the cells
+    // used for transpositions next to the band intact
+    let center = len1 as isize - len2 as isize;
+    let slack = min(
+        score_cutoff.saturating_sub(center.unsigned_abs()) / 2 + 2,
+        len1 + len2,
+    ) as isize;
+    let band_min = min(0, center) - slack;
+    let band_max = max(0, center) + slack;
+
+    let mut last_row_id = vec![-1_isize; columns.count];
     let size = len2 + 2;
     let mut fr = vec![max_val; size];
+    // row in which the value in `fr` was stored
+    let mut fr_row = vec![-1_isize; size];
     let mut r1 = vec![max_val; size];
     let mut r: Vec<isize> = (max_val..=max_val).chain(0..(size - 1) as isize).collect();
 
     for (i, ch1) in s1.enumerate().map(|(i, ch1)| (i + 1, ch1)) {
         mem::swap(&mut r, &mut r1);
+        let first = max(1, i as isize - band_max) as usize;
+        let last = min(len2 as isize, i as isize - band_min) as usize;
+        let id1 = columns.map.get(ch1).val;
+
         let mut last_col_id: isize = -1;
-        let mut last_i2l1 = r[1];
-        r[1] = i as isize;
+        let mut last_i2l1 = r[first];
+        r[first] = if first == 1 { i as isize } else { max_val };
         let mut t = max_val;
 
-        for (j, ch2) in s2.clone().enumerate().map(|(j, ch2)| (j + 1, ch2)) {
-            let diag = r1[j] + isize::from(ch1 != ch2);
+        for j in first..=last {
+            let id2 = s2[j - 1];
+            let is_match = id1 == id2 as isize;
+            let diag = r1[j] + isize::from(!is_match);
             let left = r[j] + 1;
             let up = r1[j + 1] + 1;
             let mut temp = min(diag, min(left, up));
 
-            if ch1 == ch2 {
+            if is_match {
                 last_col_id = j as isize; // last occurence of s1_i
                 fr[j + 1] = r1[j - 1]; // save H_k-1,j-2
+                fr_row[j + 1] = i as isize;
                 t = last_i2l1; // save H_i-2,l-1
             } else {
-                let k = last_row_id.get(ch2).val;
+                let k = last_row_id[id2];
                 let l = last_col_id;
 
                 if j as isize - l == 1 {
-                    let transpose = fr[j + 1] + (i as isize - k);
-                    temp = min(temp, transpose);
+                    // the value is only valid if it was stored in the last occurrence of s2_j
+                    if fr_row[j + 1] == k {
+                        let transpose = fr[j + 1] + (i as isize - k);
+                        temp = min(temp, transpose);
+                    }
                 } else if i as isize - k == 1 {
                     let transpose = t + (j as isize - l);
                     temp = min(temp, transpose);
@@ -161,38 +320,176 @@ where
             r[j + 1] = temp;
         }
 
-        last_row_id.get_mut(ch1).val = i as isize;
+        // cells right of the band are read by the next two rows
+        for cell in r.iter_mut().skip(last + 2).take(2) {
+            *cell = max_val;
+        }
+
+        if id1 >= 0 {
+            last_row_id[id1 as usize] = i as isize;
+        }
     }
 
     r[len2 + 1] as usize
 }
 
+/// Zhao's algorithm generalized to arbitrary weights
+///
+/// With arbitrary weights a transposition can be cheaper than the alternatives even when
+/// elements are deleted and inserted between the transposed elements. So in addition to
+/// the last occurrence of each element of `s1` the row before it is stored, which
+/// requires `O(min(N, S)*M)` memory, where `S` is the number of distinct elements in `s1`.
+/// Returns `usize::MAX` as soon as the distance can no longer be within `score_cutoff`.
+fn generalized_distance_zhao<Iter1, Iter2>(
+    s1: Iter1,
+    len1: usize,
+    s2: Iter2,
+    len2: usize,
+    weights: &WeightTable,
+    score_cutoff: usize,
+) -> usize
+where
+    Iter1: Iterator,
+    Iter2: Iterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    // row of the last occurrence of each element and the index of the row before it in `rows`
+    let mut last_row_id = HybridGrowingHashmap::<RowId> {
+        map_unsigned: GrowingHashmap::default(),
+        map_signed: GrowingHashmap::default(),
+        extended_ascii: [RowId::default(); 256],
+    };
+    let mut row_slot = HybridGrowingHashmap::<RowId> {
+        map_unsigned: GrowingHashmap::default(),
+        map_signed: GrowingHashmap::default(),
+        extended_ascii: [RowId::default(); 256],
+    };
+    let mut rows: Vec<Vec<usize>> = Vec::new();
+
+    // lower bound for the distance of any alignment passing through row `i`
+    let row_lower_bound = |i: usize, row: &[usize]| {
+        row.iter()
+            .enumerate()
+            .map(|(j, &cell)| cell + weights.min_distance(len1 - i, len2 - j))
+            .min()
+            .unwrap_or(usize::MAX)
+    };
+    // lower bounds of the rows stored in `rows`
+    let mut row_lower_bounds: Vec<usize> = Vec::new();
+
+    let mut r1: Vec<usize> = Vec::with_capacity(len2 + 1);
+    let mut r: Vec<usize> = (0..=len2).map(|j| j * weights.insertion_cost).collect();
+    let mut prev_lower_bound = row_lower_bound(0, &r);
+
+    for (i, ch1) in s1.enumerate().map(|(i, ch1)| (i + 1, ch1)) {
+        mem::swap(&mut r, &mut r1);
+        r.clear();
+        r.push(i * weights.deletion_cost);
+        let mut last_col_id = 0;
+
+        for (j, ch2) in s2.clone().enumerate().map(|(j, ch2)| (j + 1, ch2)) {
+            let diag = r1[j - 1]
+                + if ch1 == ch2 {
+                    0
+                } else {
+                    weights.substitution_cost
+                };
+            let left = r[j - 1] + weights.insertion_cost;
+            let up = r1[j] + weights.deletion_cost;
+            let mut temp = min(diag, min(left, up));
+
+            let k = last_row_id.get(ch2).val;
+            if k > 0 && last_col_id > 0 {
+                let k = k as usize;
+                let slot = row_slot.get(ch2).val as usize;
+                let transpose = rows[slot][last_col_id - 1]
+                    + (i - k - 1) * weights.deletion_cost
+                    + (j - last_col_id - 1) * weights.insertion_cost
+                    + weights.transposition_cost;
+                temp = min(temp, transpose);
+            }
+
+            if ch1 == ch2 {
+                last_col_id = j;
+            }
+            r.push(temp);
+        }
+
+        // store the row in front of the occurrence of `ch1`
+        let slot = row_slot.get_mut(ch1);
+        if slot.val < 0 {
+            slot.val = rows.len() as isize;
+            rows.push(r1.clone());
+            row_lower_bounds.push(prev_lower_bound);
+        } else {
+            rows[slot.val as usize].clone_from(&r1);
+            row_lower_bounds[slot.val as usize] = prev_lower_bound;
+        }
+        last_row_id.get_mut(ch1).val = i as isize;
+
+        // the following rows are calculated from the current row and the stored rows,
+        // so every remaining alignment passes through one of them
+        let lower_bound = row_lower_bound(i, &r);
+        let stored_lower_bound = row_lower_bounds.iter().copied().min().unwrap_or(usize::MAX);
+        if min(lower_bound, stored_lower_bound) > score_cutoff {
+            return usize::MAX;
+        }
+        prev_lower_bound = lower_bound;
+    }
+
+    r[len2]
+}
+
 fn distance_impl<Iter1, Iter2>(
     s1: Iter1,
     len1: usize,
     s2: Iter2,
     len2: usize,
+    weights: &WeightTable,
     score_cutoff: usize,
 ) -> usize
 where
     Iter1: DoubleEndedIterator + Clone,
     Iter2: DoubleEndedIterator + Clone,
-    Iter1::Item: PartialEq<Iter2::Item> + HashableChar,
-    Iter2::Item: PartialEq<Iter1::Item> + HashableChar,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
 {
-    if score_cutoff < len1.abs_diff(len2) {
+    if score_cutoff < weights.min_distance(len1, len2) {
         return usize::MAX;
     }
 
     let affix = remove_common_affix(s1, len1, s2, len2);
-    distance_zhao(affix.s1, affix.len1, affix.s2, affix.len2)
+    match weights.uniform_cost() {
+        Some(0) => 0,
+        Some(cost) => {
+            let columns = ColumnIds::new(affix.s2);
+            distance_zhao(
+                affix.s1,
+                affix.len1,
+                &columns,
+                &columns.ids,
+                score_cutoff / cost,
+            ) * cost
+        }
+        None => generalized_distance_zhao(
+            affix.s1,
+            affix.len1,
+            affix.s2,
+            affix.len2,
+            weights,
+            score_cutoff,
+        ),
+    }
 }
 
-struct IndividualComparator;
+struct IndividualComparator {
+    weights: WeightTable,
+}
 
 impl MetricUsize for IndividualComparator {
     fn maximum(&self, len1: usize, len2: usize) -> usize {
-        max(len1, len2)
+        self.weights.maximum(len1, len2)
     }
 
     fn _distance<Iter1, Iter2>(
@@ -210,7 +507,14 @@ impl MetricUsize for IndividualComparator {
         Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
         Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     {
-        distance_impl(s1, len1, s2, len2, score_cutoff.unwrap_or(usize::MAX))
+        distance_impl(
+            s1,
+            len1,
+            s2,
+            len2,
+            &self.weights,
+            score_cutoff.unwrap_or(usize::MAX),
+        )
     }
 }
 
@@ -237,10 +541,10 @@ where
     distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn distance_with_args<Iter1, Iter2, CutoffType>(
+pub fn distance_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<usize, CutoffType>,
+    args: &Args<usize, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -250,17 +554,23 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<usize>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
-    args.score_cutoff.score(IndividualComparator {}._distance(
-        s1_iter.clone(),
-        s1_iter.count(),
-        s2_iter.clone(),
-        s2_iter.count(),
-        args.score_cutoff.cutoff(),
-        args.score_hint,
-    ))
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
+    args.score_cutoff.score(
+        IndividualComparator {
+            weights: args.weights,
+        }
+        ._distance(
+            s1_iter.clone(),
+            s1_iter.count(),
+            s2_iter.clone(),
+            s2_iter.count(),
+            args.score_cutoff.cutoff(),
+            args.score_hint,
+        ),
+    )
 }
 
 /// Damerau-Levenshtein similarity in the range [0, max]
@@ -279,10 +589,10 @@ where
     similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn similarity_with_args<Iter1, Iter2, CutoffType>(
+pub fn similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<usize, CutoffType>,
+    args: &Args<usize, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -292,17 +602,23 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<usize>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
-    args.score_cutoff.score(IndividualComparator {}._similarity(
-        s1_iter.clone(),
-        s1_iter.count(),
-        s2_iter.clone(),
-        s2_iter.count(),
-        args.score_cutoff.cutoff(),
-        args.score_hint,
-    ))
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
+    args.score_cutoff.score(
+        IndividualComparator {
+            weights: args.weights,
+        }
+        ._similarity(
+            s1_iter.clone(),
+            s1_iter.count(),
+            s2_iter.clone(),
+            s2_iter.count(),
+            args.score_cutoff.cutoff(),
+            args.score_hint,
+        ),
+    )
 }
 
 /// Normalized Damerau-Levenshtein distance in the range [1.0, 0.0]
@@ -321,10 +637,10 @@ where
     normalized_distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType>(
+pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -334,18 +650,23 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
-    args.score_cutoff
-        .score(IndividualComparator {}._normalized_distance(
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
+    args.score_cutoff.score(
+        IndividualComparator {
+            weights: args.weights,
+        }
+        ._normalized_distance(
             s1_iter.clone(),
             s1_iter.count(),
             s2_iter.clone(),
             s2_iter.count(),
             args.score_cutoff.cutoff(),
             args.score_hint,
-        ))
+        ),
+    )
 }
 
 /// Normalized Damerau-Levenshtein similarity in the range [0.0, 1.0]
@@ -364,10 +685,10 @@ where
     normalized_similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType>(
+pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -377,20 +698,40 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
-    args.score_cutoff
-        .score(IndividualComparator {}._normalized_similarity(
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
+    args.score_cutoff.score(
+        IndividualComparator {
+            weights: args.weights,
+        }
+        ._normalized_similarity(
             s1_iter.clone(),
             s1_iter.count(),
             s2_iter.clone(),
             s2_iter.count(),
             args.score_cutoff.cutoff(),
             args.score_hint,
-        ))
+        ),
+    )
 }
 
+/// Marker type describing the Damerau-Levenshtein distance, see [`Metric`](crate::distance::Metric)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DamerauLevenshtein;
+
+impl_metric!(
+    DamerauLevenshtein,
+    usize,
+    higher_is_better: false,
+    optimal_score: 0,
+    worst_score: usize::MAX,
+    comparator: IndividualComparator {
+        weights: WeightTable::default(),
+    },
+);
+
 /// `One x Many` comparisons using the Damerau-Levenshtein distance
 ///
 /// # Examples
@@ -404,6 +745,55 @@ where
 #[derive(Clone)]
 pub struct BatchComparator<Elem1> {
     s1: Vec<Elem1>,
+    columns: ColumnIds,
+}
+
+struct BatchComparatorImpl<'a, Elem1> {
+    cache: &'a BatchComparator<Elem1>,
+    weights: &'a WeightTable,
+}
+
+impl<CharT> MetricUsize for BatchComparatorImpl<'_, CharT> {
+    fn maximum(&self, len1: usize, len2: usize) -> usize {
+        self.weights.maximum(len1, len2)
+    }
+
+    fn _distance<Iter1, Iter2>(
+        &self,
+        s1: Iter1,
+        len1: usize,
+        s2: Iter2,
+        len2: usize,
+        score_cutoff: Option<usize>,
+        _score_hint: Option<usize>,
+    ) -> usize
+    where
+        Iter1: DoubleEndedIterator + Clone,
+        Iter2: DoubleEndedIterator + Clone,
+        Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    {
+        let score_cutoff = score_cutoff.unwrap_or(usize::MAX);
+        let Some(cost) = self.weights.uniform_cost().filter(|&cost| cost != 0) else {
+            return distance_impl(s1, len1, s2, len2, self.weights, score_cutoff);
+        };
+
+        if score_cutoff < self.weights.min_distance(len1, len2) {
+            return usize::MAX;
+        }
+
+        // with uniform weights the distance is symmetric, so the cached `s1` is used
+        // as columns and `s2` as rows
+        let affix = remove_common_affix(s1, len1, s2, len2);
+        let columns = &self.cache.columns;
+        distance_zhao(
+            affix.s2,
+            affix.len2,
+            columns,
+            &columns.ids[affix.prefix_len..len1 - affix.suffix_len],
+            score_cutoff / cost,
+        ) * cost
+    }
 }
 
 impl<Elem1> BatchComparator<Elem1>
@@ -414,9 +804,9 @@ where
     where
         Iter1: IntoIterator<Item = Elem1>,
     {
-        Self {
-            s1: s1.into_iter().collect(),
-        }
+        let s1: Vec<Elem1> = s1.into_iter().collect();
+        let columns = ColumnIds::new(s1.iter().cloned());
+        Self { s1, columns }
     }
 
     /// Normalized distance calculated similar to [`normalized_distance`]
@@ -442,7 +832,21 @@ where
         Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
         CutoffType: DistanceCutoff<f64>,
     {
-        normalized_distance_with_args(self.s1.iter().copied(), s2, args)
+        let s2_iter = s2.into_iter();
+        args.score_cutoff.score(
+            BatchComparatorImpl {
+                cache: self,
+                weights: &args.weights,
+            }
+            ._normalized_distance(
+                self.s1.iter().copied(),
+                self.s1.len(),
+                s2_iter.clone(),
+                s2_iter.count(),
+                args.score_cutoff.cutoff(),
+                args.score_hint,
+            ),
+        )
     }
 
     /// Normalized similarity calculated similar to [`normalized_similarity`]
@@ -468,7 +872,21 @@ where
         Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
         CutoffType: SimilarityCutoff<f64>,
     {
-        normalized_similarity_with_args(self.s1.iter().copied(), s2, args)
+        let s2_iter = s2.into_iter();
+        args.score_cutoff.score(
+            BatchComparatorImpl {
+                cache: self,
+                weights: &args.weights,
+            }
+            ._normalized_similarity(
+                self.s1.iter().copied(),
+                self.s1.len(),
+                s2_iter.clone(),
+                s2_iter.count(),
+                args.score_cutoff.cutoff(),
+                args.score_hint,
+            ),
+        )
     }
 
     /// Distance calculated similar to [`distance`]
@@ -494,7 +912,21 @@ where
         Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
         CutoffType: DistanceCutoff<usize>,
     {
-        distance_with_args(self.s1.iter().copied(), s2, args)
+        let s2_iter = s2.into_iter();
+        args.score_cutoff.score(
+            BatchComparatorImpl {
+                cache: self,
+                weights: &args.weights,
+            }
+            ._distance(
+                self.s1.iter().copied(),
+                self.s1.len(),
+                s2_iter.clone(),
+                s2_iter.count(),
+                args.score_cutoff.cutoff(),
+                args.score_hint,
+            ),
+        )
     }
 
     /// Similarity calculated similar to [`similarity`]
@@ -520,13 +952,30 @@ where
         Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
         CutoffType: SimilarityCutoff<usize>,
     {
-        similarity_with_args(self.s1.iter().copied(), s2, args)
+        let s2_iter = s2.into_iter();
+        args.score_cutoff.score(
+            BatchComparatorImpl {
+                cache: self,
+                weights: &args.weights,
+            }
+            ._similarity(
+                self.s1.iter().copied(),
+                self.s1.len(),
+                s2_iter.clone(),
+                s2_iter.count(),
+                args.score_cutoff.cutoff(),
+                args.score_hint,
+            ),
+        )
     }
 }
 
+impl_batch_metric!(BatchComparator, DamerauLevenshtein, args: Args::default());
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::details::testing::Lcg;
 
     static EMPTY: &str = "";
     static TEST: &str = "aaaa";
@@ -702,4 +1151,186 @@ mod tests {
             _test_distance("ИвaнкoIvan".chars(), "Петрунко".chars(), &Args::default())
         );
     }
+
+    /// Lowrance-Wagner algorithm using the full matrix
+    fn lowrance_wagner(s1: &[u8], s2: &[u8], weights: &WeightTable) -> usize {
+        let mut d = vec![vec![0; s2.len() + 1]; s1.len() + 1];
+        for i in 0..=s1.len() {
+            for j in 0..=s2.len() {
+                if i == 0 || j == 0 {
+                    d[i][j] = i * weights.deletion_cost + j * weights.insertion_cost;
+                    continue;
+                }
+                let sub = if s1[i - 1] == s2[j - 1] {
+                    0
+                } else {
+                    weights.substitution_cost
+                };
+                let mut dist = (d[i - 1][j - 1] + sub)
+                    .min(d[i][j - 1] + weights.insertion_cost)
+                    .min(d[i - 1][j] + weights.deletion_cost);
+
+                let k = (1..i).rev().find(|&k| s1[k - 1] == s2[j - 1]);
+                let l = (1..j).rev().find(|&l| s2[l - 1] == s1[i - 1]);
+                if let (Some(k), Some(l)) = (k, l) {
+                    dist = dist.min(
+                        d[k - 1][l - 1]
+                            + (i - k - 1) * weights.deletion_cost
+                            + (j - l - 1) * weights.insertion_cost
+                            + weights.transposition_cost,
+                    );
+                }
+                d[i][j] = dist;
+            }
+        }
+        d[s1.len()][s2.len()]
+    }
+
+    #[test]
+    fn test_weights() {
+        let mut rng = Lcg::new(5);
+
+        let weight_tables = [
+            WeightTable {
+                insertion_cost: 1,
+                deletion_cost: 1,
+                substitution_cost: 10,
+                transposition_cost: 1,
+            },
+            WeightTable {
+                insertion_cost: 2,
+                deletion_cost: 3,
+                substitution_cost: 4,
+                transposition_cost: 3,
+            },
+            WeightTable {
+                insertion_cost: 2,
+                deletion_cost: 2,
+                substitution_cost: 2,
+                transposition_cost: 1,
+            },
+        ];
+        for _ in 0..50 {
+            let s1 = rng.string(8, 4);
+            let s2 = rng.string(10, 4);
+            assert_eq!(
+                distance(s1.iter(), s2.iter()),
+                generalized_distance_zhao(
+                    s1.iter(),
+                    s1.len(),
+                    s2.iter(),
+                    s2.len(),
+                    &WeightTable::default(),
+                    usize::MAX
+                )
+            );
+
+            for weights in &weight_tables {
+                let args = Args::default().weights(weights);
+                let expected = lowrance_wagner(&s1, &s2, weights);
+                assert_eq!(expected, distance_with_args(s1.iter(), s2.iter(), &args));
+                assert_eq!(
+                    expected,
+                    BatchComparator::new(s1.iter().copied())
+                        .distance_with_args(s2.iter().copied(), &args)
+                );
+                assert_eq!(
+                    weights.maximum(s1.len(), s2.len()) - expected,
+                    similarity_with_args(s1.iter(), s2.iter(), &args)
+                );
+            }
+        }
+
+        // elements are deleted and inserted between the transposed elements
+        let args = Args::default().weights(&weight_tables[0]);
+        assert_eq!(3, distance_with_args("xay".chars(), "ybx".chars(), &args));
+
+        let uniform = WeightTable {
+            insertion_cost: 3,
+            deletion_cost: 3,
+            substitution_cost: 3,
+            transposition_cost: 3,
+        };
+        let args = Args::default().weights(&uniform);
+        assert_eq!(6, distance_with_args("CA".chars(), "ABC".chars(), &args));
+        assert_delta!(
+            Some(2.0 / 3.0),
+            Some(normalized_distance_with_args(
+                "CA".chars(),
+                "ABC".chars(),
+                &Args::default().weights(&uniform)
+            )),
+            0.0001
+        );
+    }
+
+    #[test]
+    fn test_score_cutoff() {
+        let mut rng = Lcg::new(17);
+
+        let weight_tables = [
+            WeightTable {
+                insertion_cost: 1,
+                deletion_cost: 1,
+                substitution_cost: 10,
+                transposition_cost: 1,
+            },
+            WeightTable {
+                insertion_cost: 2,
+                deletion_cost: 3,
+                substitution_cost: 4,
+                transposition_cost: 3,
+            },
+            WeightTable {
+                insertion_cost: 2,
+                deletion_cost: 2,
+                substitution_cost: 2,
+                transposition_cost: 2,
+            },
+        ];
+        for _ in 0..200 {
+            let len1 = rng.next(90);
+            let s1 = rng.string(len1, 3);
+            // similar strings created by editing s1
+            let mut s2 = s1.clone();
+            for _ in 0..rng.next(12) {
+                let pos = rng.next(s2.len() + 1);
+                match rng.next(4) {
+                    0 => s2.insert(pos, rng.letter(3)),
+                    1 if pos < s2.len() => {
+                        s2.remove(pos);
+                    }
+                    2 if pos + 1 < s2.len() => s2.swap(pos, pos + 1),
+                    _ if pos < s2.len() => s2[pos] = rng.letter(3),
+                    _ => {}
+                }
+            }
+
+            let expected = lowrance_wagner(&s1, &s2, &WeightTable::default());
+            let scorer = BatchComparator::new(s1.iter().copied());
+            for score_cutoff in 0..expected + 3 {
+                let args = Args::default().score_cutoff(score_cutoff);
+                let expected = (expected <= score_cutoff).then_some(expected);
+                assert_eq!(expected, distance_with_args(&s1, &s2, &args));
+                assert_eq!(expected, distance_with_args(&s2, &s1, &args));
+                assert_eq!(
+                    expected,
+                    scorer.distance_with_args(s2.iter().copied(), &args)
+                );
+            }
+
+            for weights in &weight_tables {
+                let expected = lowrance_wagner(&s1, &s2, weights);
+                for score_cutoff in [0, expected / 2, expected, expected + 1] {
+                    let args = Args::default().weights(weights).score_cutoff(score_cutoff);
+                    let expected = (expected <= score_cutoff).then_some(expected);
+                    assert_eq!(expected, distance_with_args(&s1, &s2, &args));
+                    assert_eq!(
+                        expected,
+                        scorer.distance_with_args(s2.iter().copied(), &args)
+                    );
+                }
+            }
+        }
+    }
 }
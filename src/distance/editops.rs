@@ -0,0 +1,713 @@
+use crate::HashableChar;
+use std::cmp::min;
+use std::error;
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Deref, RangeBounds};
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum EditopsError {
+    NotASubsequence,
+}
+
+impl Display for EditopsError {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        let text = match self {
+            Self::NotASubsequence => "Subsequence is not a subsequence of the edit operations",
+        };
+
+        write!(fmt, "{text}")
+    }
+}
+
+impl error::Error for EditopsError {}
+
+/// Kind of an edit operation in [`Editops`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EditType {
+    Replace,
+    Insert,
+    Delete,
+}
+
+/// Single edit operation required to transform the source into the destination sequence
+///
+/// - `Replace`: replace `src[src_pos]` with `dest[dest_pos]`
+/// - `Insert`: insert `dest[dest_pos]` at `src_pos`
+/// - `Delete`: delete `src[src_pos]`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Editop {
+    pub tag: EditType,
+    pub src_pos: usize,
+    pub dest_pos: usize,
+}
+
+/// List of edit operations required to transform the source into the destination sequence
+///
+/// The operations are sorted by their position and matching elements are not
+/// included.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Editops {
+    ops: Vec<Editop>,
+    src_len: usize,
+    dest_len: usize,
+}
+
+impl Editops {
+    pub(crate) fn new(ops: Vec<Editop>, src_len: usize, dest_len: usize) -> Self {
+        Self {
+            ops,
+            src_len,
+            dest_len,
+        }
+    }
+
+    /// length of the source sequence
+    pub fn src_len(&self) -> usize {
+        self.src_len
+    }
+
+    /// length of the destination sequence
+    pub fn dest_len(&self) -> usize {
+        self.dest_len
+    }
+
+    /// Applies the edit operations to `s1`, which creates `s2`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sequences are shorter than the positions of the edit operations
+    ///
+    /// # Example
+    /// ```
+    /// use rapidfuzz::distance::levenshtein;
+    ///
+    /// let ops = levenshtein::editops("qabxcd".chars(), "abycdf".chars());
+    /// let res: String = ops.apply("qabxcd".chars(), "abycdf".chars()).into_iter().collect();
+    /// assert_eq!("abycdf", res);
+    /// ```
+    pub fn apply<Iter1, Iter2, CharT>(&self, s1: Iter1, s2: Iter2) -> Vec<CharT>
+    where
+        Iter1: IntoIterator<Item = CharT>,
+        Iter2: IntoIterator<Item = CharT>,
+        CharT: HashableChar + Copy,
+    {
+        let s1: Vec<CharT> = s1.into_iter().collect();
+        let s2: Vec<CharT> = s2.into_iter().collect();
+
+        let mut res = Vec::with_capacity(s2.len());
+        let mut src_pos = 0;
+        for op in &self.ops {
+            // matches between last and current editop
+            res.extend_from_slice(&s1[src_pos..op.src_pos]);
+            src_pos = op.src_pos;
+
+            match op.tag {
+                EditType::Replace => {
+                    res.push(s2[op.dest_pos]);
+                    src_pos += 1;
+                }
+                EditType::Insert => res.push(s2[op.dest_pos]),
+                EditType::Delete => src_pos += 1,
+            }
+        }
+
+        // matches after the last editop
+        res.extend_from_slice(&s1[src_pos..]);
+        res
+    }
+
+    /// Returns the edit operations required to transform the destination
+    /// into the source sequence
+    pub fn inverse(&self) -> Editops {
+        let ops = self
+            .ops
+            .iter()
+            .map(|op| Editop {
+                tag: match op.tag {
+                    EditType::Replace => EditType::Replace,
+                    EditType::Insert => EditType::Delete,
+                    EditType::Delete => EditType::Insert,
+                },
+                src_pos: op.dest_pos,
+                dest_pos: op.src_pos,
+            })
+            .collect();
+
+        Editops::new(ops, self.dest_len, self.src_len)
+    }
+
+    /// Returns the edit operations in `range`. The source and destination lengths
+    /// are kept, so the result can still be applied to the same sequences.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds
+    pub fn slice<R>(&self, range: R) -> Editops
+    where
+        R: RangeBounds<usize>,
+    {
+        let ops = self.ops[(range.start_bound().cloned(), range.end_bound().cloned())].to_vec();
+        Editops::new(ops, self.src_len, self.dest_len)
+    }
+
+    /// Removes the edit operations in `subsequence` from the edit operations.
+    ///
+    /// The result transforms the sequence created by applying `subsequence` to the
+    /// source into the destination sequence. So the positions of the remaining edit
+    /// operations are adjusted for the removed insertions and deletions.
+    ///
+    /// # Example
+    /// ```
+    /// use rapidfuzz::distance::levenshtein;
+    ///
+    /// let ops = levenshtein::editops("qabxcd".chars(), "abycdf".chars());
+    /// let remaining = ops.remove_subsequence(&ops.slice(..1)).unwrap();
+    /// let res: String = remaining.apply("abxcd".chars(), "abycdf".chars()).into_iter().collect();
+    /// assert_eq!("abycdf", res);
+    /// ```
+    pub fn remove_subsequence(&self, subsequence: &Editops) -> Result<Editops, EditopsError> {
+        if subsequence.len() > self.len() {
+            return Err(EditopsError::NotASubsequence);
+        }
+
+        let mut ops = Vec::with_capacity(self.len() - subsequence.len());
+        // offset to correct the positions for removed edit operations
+        let mut offset = 0_isize;
+        let mut remaining = self.ops.iter();
+
+        let mut push = |op: &Editop, offset: isize| {
+            ops.push(Editop {
+                src_pos: op.src_pos.wrapping_add_signed(offset),
+                ..*op
+            });
+        };
+
+        for sop in &subsequence.ops {
+            loop {
+                match remaining.next() {
+                    Some(op) if op == sop => break,
+                    Some(op) => push(op, offset),
+                    // element of subsequence not part of the sequence
+                    None => return Err(EditopsError::NotASubsequence),
+                }
+            }
+
+            match sop.tag {
+                EditType::Insert => offset += 1,
+                EditType::Delete => offset -= 1,
+                EditType::Replace => {}
+            }
+        }
+
+        // add remaining elements
+        for op in remaining {
+            push(op, offset);
+        }
+
+        Ok(Editops::new(
+            ops,
+            self.src_len.wrapping_add_signed(offset),
+            self.dest_len,
+        ))
+    }
+
+    /// Converts the edit operations into blocks of [`Opcodes`], which include
+    /// the matching parts of the sequences as well.
+    pub fn as_opcodes(&self) -> Opcodes {
+        let mut blocks = Vec::new();
+        let mut src_pos = 0;
+        let mut dest_pos = 0;
+
+        let mut i = 0;
+        while i < self.ops.len() {
+            if src_pos < self.ops[i].src_pos || dest_pos < self.ops[i].dest_pos {
+                blocks.push(Opcode {
+                    tag: OpcodeType::Equal,
+                    src_start: src_pos,
+                    src_end: self.ops[i].src_pos,
+                    dest_start: dest_pos,
+                    dest_end: self.ops[i].dest_pos,
+                });
+                src_pos = self.ops[i].src_pos;
+                dest_pos = self.ops[i].dest_pos;
+            }
+
+            let src_start = src_pos;
+            let dest_start = dest_pos;
+            let tag = self.ops[i].tag;
+
+            while i < self.ops.len()
+                && self.ops[i].tag == tag
+                && src_pos == self.ops[i].src_pos
+                && dest_pos == self.ops[i].dest_pos
+            {
+                match tag {
+                    EditType::Replace => {
+                        src_pos += 1;
+                        dest_pos += 1;
+                    }
+                    EditType::Insert => dest_pos += 1,
+                    EditType::Delete => src_pos += 1,
+                }
+                i += 1;
+            }
+
+            blocks.push(Opcode {
+                tag: tag.into(),
+                src_start,
+                src_end: src_pos,
+                dest_start,
+                dest_end: dest_pos,
+            });
+        }
+
+        if src_pos < self.src_len || dest_pos < self.dest_len {
+            blocks.push(Opcode {
+                tag: OpcodeType::Equal,
+                src_start: src_pos,
+                src_end: self.src_len,
+                dest_start: dest_pos,
+                dest_end: self.dest_len,
+            });
+        }
+
+        Opcodes::new(blocks, self.src_len, self.dest_len)
+    }
+
+    /// Converts the edit operations into a list of [`MatchingBlock`]s describing the
+    /// matching parts of the sequences. The list always ends with the dummy block
+    /// `MatchingBlock { src_start: src_len, dest_start: dest_len, size: 0 }`.
+    pub fn as_matching_blocks(&self) -> Vec<MatchingBlock> {
+        let mut blocks = Vec::new();
+        let mut src_pos = 0;
+        let mut dest_pos = 0;
+
+        for op in &self.ops {
+            if src_pos < op.src_pos || dest_pos < op.dest_pos {
+                let size = min(op.src_pos - src_pos, op.dest_pos - dest_pos);
+                if size > 0 {
+                    blocks.push(MatchingBlock {
+                        src_start: src_pos,
+                        dest_start: dest_pos,
+                        size,
+                    });
+                }
+                src_pos = op.src_pos;
+                dest_pos = op.dest_pos;
+            }
+
+            match op.tag {
+                EditType::Replace => {
+                    src_pos += 1;
+                    dest_pos += 1;
+                }
+                EditType::Insert => dest_pos += 1,
+                EditType::Delete => src_pos += 1,
+            }
+        }
+
+        if src_pos < self.src_len || dest_pos < self.dest_len {
+            let size = min(self.src_len - src_pos, self.dest_len - dest_pos);
+            if size > 0 {
+                blocks.push(MatchingBlock {
+                    src_start: src_pos,
+                    dest_start: dest_pos,
+                    size,
+                });
+            }
+        }
+
+        blocks.push(MatchingBlock {
+            src_start: self.src_len,
+            dest_start: self.dest_len,
+            size: 0,
+        });
+        blocks
+    }
+}
+
+impl Deref for Editops {
+    type Target = [Editop];
+
+    fn deref(&self) -> &Self::Target {
+        &self.ops
+    }
+}
+
+impl IntoIterator for Editops {
+    type Item = Editop;
+    type IntoIter = std::vec::IntoIter<Editop>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ops.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Editops {
+    type Item = &'a Editop;
+    type IntoIter = std::slice::Iter<'a, Editop>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ops.iter()
+    }
+}
+
+/// Block of matching elements, so `src[src_start..src_start + size] == dest[dest_start..dest_start + size]`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MatchingBlock {
+    pub src_start: usize,
+    pub dest_start: usize,
+    pub size: usize,
+}
+
+/// Kind of a block in [`Opcodes`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OpcodeType {
+    Equal,
+    Replace,
+    Insert,
+    Delete,
+}
+
+impl From<EditType> for OpcodeType {
+    fn from(tag: EditType) -> Self {
+        match tag {
+            EditType::Replace => OpcodeType::Replace,
+            EditType::Insert => OpcodeType::Insert,
+            EditType::Delete => OpcodeType::Delete,
+        }
+    }
+}
+
+/// Block of operations required to transform the source into the destination sequence
+///
+/// Similar to the opcodes returned by Python's `difflib.SequenceMatcher.get_opcodes`:
+/// - `Equal`: `src[src_start..src_end] == dest[dest_start..dest_end]`
+/// - `Replace`: `src[src_start..src_end]` should be replaced by `dest[dest_start..dest_end]`
+/// - `Insert`: `dest[dest_start..dest_end]` should be inserted at `src[src_start..src_start]`
+/// - `Delete`: `src[src_start..src_end]` should be deleted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Opcode {
+    pub tag: OpcodeType,
+    pub src_start: usize,
+    pub src_end: usize,
+    pub dest_start: usize,
+    pub dest_end: usize,
+}
+
+/// List of [`Opcode`] blocks covering the whole source and destination sequence
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Opcodes {
+    ops: Vec<Opcode>,
+    src_len: usize,
+    dest_len: usize,
+}
+
+impl Opcodes {
+    pub(crate) fn new(ops: Vec<Opcode>, src_len: usize, dest_len: usize) -> Self {
+        Self {
+            ops,
+            src_len,
+            dest_len,
+        }
+    }
+
+    /// length of the source sequence
+    pub fn src_len(&self) -> usize {
+        self.src_len
+    }
+
+    /// length of the destination sequence
+    pub fn dest_len(&self) -> usize {
+        self.dest_len
+    }
+
+    /// Applies the opcodes to `s1`, which creates `s2`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sequences are shorter than the ranges of the opcodes
+    pub fn apply<Iter1, Iter2, CharT>(&self, s1: Iter1, s2: Iter2) -> Vec<CharT>
+    where
+        Iter1: IntoIterator<Item = CharT>,
+        Iter2: IntoIterator<Item = CharT>,
+        CharT: HashableChar + Copy,
+    {
+        let s1: Vec<CharT> = s1.into_iter().collect();
+        let s2: Vec<CharT> = s2.into_iter().collect();
+
+        let mut res = Vec::with_capacity(s2.len());
+        for op in &self.ops {
+            match op.tag {
+                OpcodeType::Equal => res.extend_from_slice(&s1[op.src_start..op.src_end]),
+                OpcodeType::Replace | OpcodeType::Insert => {
+                    res.extend_from_slice(&s2[op.dest_start..op.dest_end]);
+                }
+                OpcodeType::Delete => {}
+            }
+        }
+        res
+    }
+
+    /// Returns the opcodes required to transform the destination
+    /// into the source sequence
+    pub fn inverse(&self) -> Opcodes {
+        let ops = self
+            .ops
+            .iter()
+            .map(|op| Opcode {
+                tag: match op.tag {
+                    OpcodeType::Insert => OpcodeType::Delete,
+                    OpcodeType::Delete => OpcodeType::Insert,
+                    tag => tag,
+                },
+                src_start: op.dest_start,
+                src_end: op.dest_end,
+                dest_start: op.src_start,
+                dest_end: op.src_end,
+            })
+            .collect();
+
+        Opcodes::new(ops, self.dest_len, self.src_len)
+    }
+
+    /// Converts the opcodes into single [`Editops`]
+    pub fn as_editops(&self) -> Editops {
+        let mut ops = Vec::new();
+        for op in &self.ops {
+            match op.tag {
+                OpcodeType::Equal => {}
+                OpcodeType::Replace => {
+                    ops.extend(
+                        (op.src_start..op.src_end)
+                            .zip(op.dest_start..op.dest_end)
+                            .map(|(src_pos, dest_pos)| Editop {
+                                tag: EditType::Replace,
+                                src_pos,
+                                dest_pos,
+                            }),
+                    );
+                }
+                OpcodeType::Insert => {
+                    ops.extend((op.dest_start..op.dest_end).map(|dest_pos| Editop {
+                        tag: EditType::Insert,
+                        src_pos: op.src_start,
+                        dest_pos,
+                    }));
+                }
+                OpcodeType::Delete => {
+                    ops.extend((op.src_start..op.src_end).map(|src_pos| Editop {
+                        tag: EditType::Delete,
+                        src_pos,
+                        dest_pos: op.dest_start,
+                    }));
+                }
+            }
+        }
+
+        Editops::new(ops, self.src_len, self.dest_len)
+    }
+
+    /// Converts the opcodes into a list of [`MatchingBlock`]s, similar to
+    /// [`Editops::as_matching_blocks`]
+    pub fn as_matching_blocks(&self) -> Vec<MatchingBlock> {
+        let mut blocks: Vec<MatchingBlock> = self
+            .ops
+            .iter()
+            .filter(|op| op.tag == OpcodeType::Equal && op.src_end > op.src_start)
+            .map(|op| MatchingBlock {
+                src_start: op.src_start,
+                dest_start: op.dest_start,
+                size: op.src_end - op.src_start,
+            })
+            .collect();
+
+        blocks.push(MatchingBlock {
+            src_start: self.src_len,
+            dest_start: self.dest_len,
+            size: 0,
+        });
+        blocks
+    }
+}
+
+impl Deref for Opcodes {
+    type Target = [Opcode];
+
+    fn deref(&self) -> &Self::Target {
+        &self.ops
+    }
+}
+
+impl IntoIterator for Opcodes {
+    type Item = Opcode;
+    type IntoIter = std::vec::IntoIter<Opcode>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ops.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Opcodes {
+    type Item = &'a Opcode;
+    type IntoIter = std::slice::Iter<'a, Opcode>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ops.iter()
+    }
+}
+
+impl From<Editops> for Opcodes {
+    fn from(editops: Editops) -> Self {
+        editops.as_opcodes()
+    }
+}
+
+impl From<Opcodes> for Editops {
+    fn from(opcodes: Opcodes) -> Self {
+        opcodes.as_editops()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::levenshtein;
+
+    #[test]
+    fn test_as_opcodes() {
+        // "spam" -> "park"
+        let editops = Editops::new(
+            vec![
+                Editop {
+                    tag: EditType::Delete,
+                    src_pos: 0,
+                    dest_pos: 0,
+                },
+                Editop {
+                    tag: EditType::Replace,
+                    src_pos: 3,
+                    dest_pos: 2,
+                },
+                Editop {
+                    tag: EditType::Insert,
+                    src_pos: 4,
+                    dest_pos: 3,
+                },
+            ],
+            4,
+            4,
+        );
+
+        let opcodes = editops.as_opcodes();
+        let expected = [
+            (OpcodeType::Delete, 0, 1, 0, 0),
+            (OpcodeType::Equal, 1, 3, 0, 2),
+            (OpcodeType::Replace, 3, 4, 2, 3),
+            (OpcodeType::Insert, 4, 4, 3, 4),
+        ];
+        assert_eq!(expected.len(), opcodes.len());
+        for (op, (tag, src_start, src_end, dest_start, dest_end)) in opcodes.iter().zip(expected) {
+            assert_eq!(
+                Opcode {
+                    tag,
+                    src_start,
+                    src_end,
+                    dest_start,
+                    dest_end
+                },
+                *op
+            );
+        }
+
+        assert_eq!(
+            vec![
+                MatchingBlock {
+                    src_start: 1,
+                    dest_start: 0,
+                    size: 2
+                },
+                MatchingBlock {
+                    src_start: 4,
+                    dest_start: 4,
+                    size: 0
+                },
+            ],
+            editops.as_matching_blocks()
+        );
+
+        assert!(Editops::new(Vec::new(), 0, 0).as_opcodes().is_empty());
+        assert_eq!(
+            &[Opcode {
+                tag: OpcodeType::Equal,
+                src_start: 0,
+                src_end: 3,
+                dest_start: 0,
+                dest_end: 3
+            }],
+            &*Editops::new(Vec::new(), 3, 3).as_opcodes()
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let ops = levenshtein::editops("spam".chars(), "park".chars());
+        let apply = |ops: &Editops, s1: &str, s2: &str| -> String {
+            ops.apply(s1.chars(), s2.chars()).into_iter().collect()
+        };
+
+        assert_eq!("park", apply(&ops, "spam", "park"));
+        assert_eq!("spam", apply(&ops.inverse(), "park", "spam"));
+
+        let opcodes = ops.as_opcodes();
+        let apply_opcodes = |ops: &Opcodes, s1: &str, s2: &str| -> String {
+            ops.apply(s1.chars(), s2.chars()).into_iter().collect()
+        };
+        assert_eq!("park", apply_opcodes(&opcodes, "spam", "park"));
+        assert_eq!("spam", apply_opcodes(&opcodes.inverse(), "park", "spam"));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let ops = levenshtein::editops("qabxcd".chars(), "abycdf".chars());
+        let opcodes = ops.as_opcodes();
+        assert_eq!(ops, opcodes.as_editops());
+        assert_eq!(ops, Editops::from(Opcodes::from(ops.clone())));
+        assert_eq!(ops.as_matching_blocks(), opcodes.as_matching_blocks());
+        assert_eq!(ops.inverse().as_opcodes(), opcodes.inverse());
+        assert_eq!(ops, ops.inverse().inverse());
+    }
+
+    #[test]
+    fn test_remove_subsequence() {
+        let ops = levenshtein::editops("qabxcd".chars(), "abycdf".chars());
+        assert_eq!(3, ops.len());
+        assert_eq!(ops, ops.slice(..));
+        assert_eq!(&ops[1..], &*ops.slice(1..));
+
+        let subsequence = ops.slice(..1);
+        let remaining = ops.remove_subsequence(&subsequence).unwrap();
+        assert_eq!(2, remaining.len());
+        assert_eq!(5, remaining.src_len());
+        let intermediate: String = subsequence
+            .apply("qabxcd".chars(), "abycdf".chars())
+            .into_iter()
+            .collect();
+        assert_eq!("abxcd", intermediate);
+        let res: String = remaining
+            .apply(intermediate.chars(), "abycdf".chars())
+            .into_iter()
+            .collect();
+        assert_eq!("abycdf", res);
+
+        assert!(ops.remove_subsequence(&ops).unwrap().is_empty());
+        assert_eq!(
+            Err(EditopsError::NotASubsequence),
+            ops.slice(..1).remove_subsequence(&ops)
+        );
+        assert_eq!(
+            Err(EditopsError::NotASubsequence),
+            ops.remove_subsequence(&ops.inverse())
+        );
+    }
+}
@@ -9,8 +9,11 @@
 //! of unequal length should be padded or return an error.
 //!
 
-use crate::common::{DistanceCutoff, NoScoreCutoff, SimilarityCutoff, WithScoreCutoff};
+use crate::common::{
+    DistanceCutoff, NoProcessor, NoScoreCutoff, Processor, SimilarityCutoff, WithScoreCutoff,
+};
 use crate::details::distance::MetricUsize;
+use crate::distance::{impl_batch_metric, impl_metric};
 use crate::HashableChar;
 
 use std::error;
@@ -23,10 +26,11 @@ pub struct NoPadding;
 
 #[must_use]
 #[derive(Copy, Clone, Debug)]
-pub struct Args<ResultType, CutoffType, PaddingType> {
+pub struct Args<ResultType, CutoffType, PaddingType, ProcessorType = NoProcessor> {
     score_cutoff: CutoffType,
     score_hint: Option<ResultType>,
     pad: PaddingType,
+    processor: ProcessorType,
 }
 
 impl<ResultType> Default for Args<ResultType, NoScoreCutoff, NoPadding> {
@@ -34,6 +38,7 @@ impl<ResultType> Default for Args<ResultType, NoScoreCutoff, NoPadding> {
         Args {
             score_cutoff: NoScoreCutoff,
             score_hint: None,
+            processor: NoProcessor,
             pad: NoPadding,
         }
     }
@@ -89,7 +94,8 @@ where
     }
 }
 
-impl<ResultType, CutoffType, PaddingType> Args<ResultType, CutoffType, PaddingType>
+impl<ResultType, CutoffType, PaddingType, ProcessorType>
+    Args<ResultType, CutoffType, PaddingType, ProcessorType>
 where
     ResultType: Copy,
 {
@@ -101,19 +107,31 @@ where
     pub fn score_cutoff(
         self,
         score_cutoff: ResultType,
-    ) -> Args<ResultType, WithScoreCutoff<ResultType>, PaddingType> {
+    ) -> Args<ResultType, WithScoreCutoff<ResultType>, PaddingType, ProcessorType> {
         Args {
             score_hint: self.score_hint,
             score_cutoff: WithScoreCutoff(score_cutoff),
+            processor: self.processor,
             pad: self.pad,
         }
     }
 
-    pub fn pad(self, pad: bool) -> Args<ResultType, CutoffType, Padding> {
+    pub fn pad(self, pad: bool) -> Args<ResultType, CutoffType, Padding, ProcessorType> {
         Args {
             score_hint: self.score_hint,
             score_cutoff: self.score_cutoff,
             pad: Padding(pad),
+            processor: self.processor,
+        }
+    }
+
+    /// preprocessing applied to both sequences before comparing them
+    pub fn processor<P>(self, processor: P) -> Args<ResultType, CutoffType, PaddingType, P> {
+        Args {
+            score_hint: self.score_hint,
+            score_cutoff: self.score_cutoff,
+            pad: self.pad,
+            processor,
         }
     }
 }
@@ -209,10 +227,10 @@ where
     distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn distance_with_args<Iter1, Iter2, CutoffType, PaddingType>(
+pub fn distance_with_args<Iter1, Iter2, CutoffType, PaddingType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<usize, CutoffType, PaddingType>,
+    args: &Args<usize, CutoffType, PaddingType, ProcessorType>,
 ) -> PaddingType::Output
 where
     Iter1: IntoIterator,
@@ -223,9 +241,10 @@ where
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<usize>,
     PaddingType: PaddingTrait<CutoffType::Output>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     let len1 = s1_iter.clone().count();
     let len2 = s2_iter.clone().count();
 
@@ -260,10 +279,10 @@ where
     similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn similarity_with_args<Iter1, Iter2, CutoffType, PaddingType>(
+pub fn similarity_with_args<Iter1, Iter2, CutoffType, PaddingType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<usize, CutoffType, PaddingType>,
+    args: &Args<usize, CutoffType, PaddingType, ProcessorType>,
 ) -> PaddingType::Output
 where
     Iter1: IntoIterator,
@@ -274,9 +293,10 @@ where
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<usize>,
     PaddingType: PaddingTrait<CutoffType::Output>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     let len1 = s1_iter.clone().count();
     let len2 = s2_iter.clone().count();
 
@@ -311,10 +331,10 @@ where
     normalized_distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType, PaddingType>(
+pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType, PaddingType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType, PaddingType>,
+    args: &Args<f64, CutoffType, PaddingType, ProcessorType>,
 ) -> PaddingType::Output
 where
     Iter1: IntoIterator,
@@ -325,9 +345,10 @@ where
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<f64>,
     PaddingType: PaddingTrait<CutoffType::Output>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     let len1 = s1_iter.clone().count();
     let len2 = s2_iter.clone().count();
 
@@ -364,10 +385,10 @@ where
     normalized_similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType, PaddingType>(
+pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType, PaddingType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType, PaddingType>,
+    args: &Args<f64, CutoffType, PaddingType, ProcessorType>,
 ) -> PaddingType::Output
 where
     Iter1: IntoIterator,
@@ -378,9 +399,10 @@ where
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<f64>,
     PaddingType: PaddingTrait<CutoffType::Output>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     let len1 = s1_iter.clone().count();
     let len2 = s2_iter.clone().count();
 
@@ -401,6 +423,21 @@ where
     )
 }
 
+/// Marker type describing the Hamming distance, see [`Metric`](crate::distance::Metric)
+///
+/// Sequences of different length are padded, like with `Args::pad(true)`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Hamming;
+
+impl_metric!(
+    Hamming,
+    usize,
+    higher_is_better: false,
+    optimal_score: 0,
+    worst_score: usize::MAX,
+    comparator: IndividualComparator {},
+);
+
 /// `One x Many` comparisons using the Hamming distance
 ///
 /// # Examples
@@ -538,9 +575,12 @@ where
     }
 }
 
+impl_batch_metric!(BatchComparator, Hamming, args: Args::default().pad(true));
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::DefaultProcessor;
 
     fn assert_dist(dist: usize, str1: &str, str2: &str) {
         assert_eq!(Ok(dist), distance(str1.chars(), str2.chars()));
@@ -638,4 +678,27 @@ mod tests {
     fn names() {
         assert_dist(14, "Friedrich Nietzs", "Jean-Paul Sartre")
     }
+
+    #[test]
+    fn processor() {
+        assert_eq!(
+            Ok(0),
+            distance_with_args(
+                "Hamming!".chars(),
+                "hamming ".chars(),
+                &Args::default().processor(DefaultProcessor)
+            )
+        );
+        assert_eq!(
+            Some(4),
+            distance_with_args(
+                "Ham".chars(),
+                "hamming".chars(),
+                &Args::default()
+                    .processor(|s: std::str::Chars| s.skip(1).collect())
+                    .pad(true)
+                    .score_cutoff(5)
+            )
+        );
+    }
 }
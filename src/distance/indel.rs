@@ -17,17 +17,22 @@
 //! ![benchmark results](https://raw.githubusercontent.com/rapidfuzz/rapidfuzz-rs/main/rapidfuzz-benches/results/indel.svg)
 //!
 
-use crate::common::{DistanceCutoff, NoScoreCutoff, SimilarityCutoff, WithScoreCutoff};
+use crate::common::{
+    DistanceCutoff, NoProcessor, NoScoreCutoff, Processor, SimilarityCutoff, WithScoreCutoff,
+};
 use crate::details::distance::MetricUsize;
-use crate::details::pattern_match_vector::BlockPatternMatchVector;
+use crate::details::intrinsics::carrying_add;
+use crate::details::pattern_match_vector::{BitVectorInterface, BlockPatternMatchVector};
 use crate::distance::lcs_seq;
+use crate::distance::{impl_batch_metric, impl_metric, Editops};
 use crate::HashableChar;
 
 #[must_use]
 #[derive(Copy, Clone, Debug)]
-pub struct Args<ResultType, CutoffType> {
+pub struct Args<ResultType, CutoffType, ProcessorType = NoProcessor> {
     pub(crate) score_cutoff: CutoffType,
     pub(crate) score_hint: Option<ResultType>,
+    pub(crate) processor: ProcessorType,
 }
 
 impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
@@ -35,11 +40,12 @@ impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
         Args {
             score_cutoff: NoScoreCutoff,
             score_hint: None,
+            processor: NoProcessor,
         }
     }
 }
 
-impl<ResultType, CutoffType> Args<ResultType, CutoffType> {
+impl<ResultType, CutoffType, ProcessorType> Args<ResultType, CutoffType, ProcessorType> {
     pub fn score_hint(mut self, score_hint: ResultType) -> Self {
         self.score_hint = Some(score_hint);
         self
@@ -48,10 +54,20 @@ impl<ResultType, CutoffType> Args<ResultType, CutoffType> {
     pub fn score_cutoff(
         self,
         score_cutoff: ResultType,
-    ) -> Args<ResultType, WithScoreCutoff<ResultType>> {
+    ) -> Args<ResultType, WithScoreCutoff<ResultType>, ProcessorType> {
         Args {
             score_hint: self.score_hint,
             score_cutoff: WithScoreCutoff(score_cutoff),
+            processor: self.processor,
+        }
+    }
+
+    /// preprocessing applied to both sequences before comparing them
+    pub fn processor<P>(self, processor: P) -> Args<ResultType, CutoffType, P> {
+        Args {
+            score_cutoff: self.score_cutoff,
+            score_hint: self.score_hint,
+            processor,
         }
     }
 }
@@ -82,16 +98,8 @@ impl MetricUsize for IndividualComparator {
         let score_hint = score_hint_.unwrap_or(usize::MAX);
 
         let maximum = self.maximum(len1, len2);
-        let lcs_cutoff = if maximum / 2 >= score_cutoff {
-            maximum / 2 - score_cutoff
-        } else {
-            0
-        };
-        let lcs_hint = if maximum / 2 >= score_hint {
-            maximum / 2 - score_hint
-        } else {
-            0
-        };
+        let lcs_cutoff = (maximum / 2).saturating_sub(score_cutoff);
+        let lcs_hint = (maximum / 2).saturating_sub(score_hint);
         let lcs_sim = lcs_seq::IndividualComparator {}._similarity(
             s1,
             len1,
@@ -133,10 +141,10 @@ where
     distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn distance_with_args<Iter1, Iter2, CutoffType>(
+pub fn distance_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<usize, CutoffType>,
+    args: &Args<usize, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -146,9 +154,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<usize>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff.score(IndividualComparator {}._distance(
         s1_iter.clone(),
         s1_iter.count(),
@@ -174,10 +183,10 @@ where
     similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn similarity_with_args<Iter1, Iter2, CutoffType>(
+pub fn similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<usize, CutoffType>,
+    args: &Args<usize, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -187,9 +196,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<usize>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff.score(IndividualComparator {}._similarity(
         s1_iter.clone(),
         s1_iter.count(),
@@ -215,10 +225,10 @@ where
     normalized_distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType>(
+pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -228,9 +238,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff
         .score(IndividualComparator {}._normalized_distance(
             s1_iter.clone(),
@@ -257,10 +268,10 @@ where
     normalized_similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType>(
+pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -270,9 +281,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff
         .score(IndividualComparator {}._normalized_similarity(
             s1_iter.clone(),
@@ -299,16 +311,50 @@ where
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
 {
     let maximum = len1 + len2;
-    let lcs_cutoff = if maximum / 2 >= score_cutoff {
-        maximum / 2 - score_cutoff
-    } else {
-        0
-    };
+    let lcs_cutoff = (maximum / 2).saturating_sub(score_cutoff);
 
     let lcs_sim = lcs_seq::similarity_with_pm(pm, s1, len1, s2, len2, lcs_cutoff);
     maximum - 2 * lcs_sim
 }
 
+/// Returns the insertions and deletions required to transform `s1` into `s2`
+///
+/// This is equal to [`lcs_seq::editops`].
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::indel;
+///
+/// let ops = indel::editops("qabxcd".chars(), "abycdf".chars());
+/// assert_eq!(4, ops.len());
+/// assert_eq!(indel::distance("qabxcd".chars(), "abycdf".chars()), ops.len());
+/// ```
+///
+/// [`lcs_seq::editops`]: ../lcs_seq/fn.editops.html
+pub fn editops<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> Editops
+where
+    Iter1: IntoIterator,
+    Iter2: IntoIterator,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    lcs_seq::editops(s1, s2)
+}
+
+/// Marker type describing the Indel distance, see [`Metric`](crate::distance::Metric)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Indel;
+
+impl_metric!(
+    Indel,
+    usize,
+    higher_is_better: false,
+    optimal_score: 0,
+    worst_score: usize::MAX,
+    comparator: IndividualComparator {},
+);
+
 /// `One x Many` comparisons using the Indel distance
 ///
 /// # Examples
@@ -351,16 +397,8 @@ where
         let score_hint = score_hint_.unwrap_or(usize::MAX);
 
         let maximum = self.maximum(len1, len2);
-        let lcs_cutoff = if maximum / 2 >= score_cutoff {
-            maximum / 2 - score_cutoff
-        } else {
-            0
-        };
-        let lcs_hint = if maximum / 2 >= score_hint {
-            maximum / 2 - score_hint
-        } else {
-            0
-        };
+        let lcs_cutoff = (maximum / 2).saturating_sub(score_cutoff);
+        let lcs_hint = (maximum / 2).saturating_sub(score_hint);
         let lcs_sim = self
             .scorer
             ._similarity(s1, len1, s2, len2, Some(lcs_cutoff), Some(lcs_hint));
@@ -519,6 +557,166 @@ where
     }
 }
 
+impl_batch_metric!(BatchComparator, Indel, args: Args::default());
+
+/// bit-parallel state of a single candidate of an [`IncrementalComparator`]
+#[derive(Clone)]
+struct IncrementalCandidate {
+    pm: BlockPatternMatchVector,
+    len: usize,
+    /// zero bits mark the elements of the candidate contributing to the LCS
+    s: Vec<u64>,
+}
+
+impl IncrementalCandidate {
+    fn lcs(&self) -> usize {
+        self.s.iter().map(|x| (!x).count_ones() as usize).sum()
+    }
+}
+
+/// `Many x One` comparisons using the Indel distance with a query that grows
+/// one element at a time
+///
+/// The comparator keeps the column state of the bit-parallel LCS algorithm for every
+/// candidate, so appending an element to the query only requires a single step of
+/// `O([N/64])` for a candidate of length `N`.
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::indel;
+///
+/// let mut scorer = indel::IncrementalComparator::new(["lewenstein", "levenshtein"].map(str::chars));
+/// scorer.extend("leven".chars());
+/// assert_eq!((7, 6), (scorer.distance(0), scorer.distance(1)));
+/// ```
+#[derive(Clone)]
+pub struct IncrementalComparator {
+    candidates: Vec<IncrementalCandidate>,
+    query_len: usize,
+}
+
+impl IncrementalComparator {
+    /// creates a comparator for `candidates` with an empty query
+    pub fn new<Iter, Seq>(candidates: Iter) -> Self
+    where
+        Iter: IntoIterator<Item = Seq>,
+        Seq: IntoIterator,
+        Seq::Item: HashableChar + Copy,
+    {
+        let candidates = candidates
+            .into_iter()
+            .map(|seq| {
+                let seq: Vec<Seq::Item> = seq.into_iter().collect();
+                let mut pm = BlockPatternMatchVector::new(seq.len());
+                pm.insert(seq.iter().copied());
+                IncrementalCandidate {
+                    s: vec![!0_u64; pm.size()],
+                    pm,
+                    len: seq.len(),
+                }
+            })
+            .collect();
+
+        Self {
+            candidates,
+            query_len: 0,
+        }
+    }
+
+    /// number of candidates
+    pub fn len(&self) -> usize {
+        self.candidates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.candidates.is_empty()
+    }
+
+    /// number of elements appended to the query
+    pub fn query_len(&self) -> usize {
+        self.query_len
+    }
+
+    /// appends `ch` to the query and updates the state of every candidate
+    pub fn push<CharT>(&mut self, ch: CharT)
+    where
+        CharT: HashableChar + Copy,
+    {
+        self.query_len += 1;
+        for candidate in &mut self.candidates {
+            let mut carry = false;
+            for (word, s_ref) in candidate.s.iter_mut().enumerate() {
+                let u = *s_ref & candidate.pm.get(word, ch);
+                let (x, carry_) = carrying_add(*s_ref, u, carry);
+                carry = carry_;
+                *s_ref = x | (*s_ref - u);
+            }
+        }
+    }
+
+    /// resets the query to an empty sequence
+    pub fn clear(&mut self) {
+        self.query_len = 0;
+        for candidate in &mut self.candidates {
+            candidate.s.fill(!0_u64);
+        }
+    }
+
+    /// Indel distance between the query and the candidate at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn distance(&self, index: usize) -> usize {
+        let candidate = &self.candidates[index];
+        self.query_len + candidate.len - 2 * candidate.lcs()
+    }
+
+    /// Indel similarity between the query and the candidate at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn similarity(&self, index: usize) -> usize {
+        2 * self.candidates[index].lcs()
+    }
+
+    /// normalized Indel distance between the query and the candidate at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn normalized_distance(&self, index: usize) -> f64 {
+        let maximum = self.query_len + self.candidates[index].len;
+        if maximum == 0 {
+            0.0
+        } else {
+            self.distance(index) as f64 / maximum as f64
+        }
+    }
+
+    /// normalized Indel similarity between the query and the candidate at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn normalized_similarity(&self, index: usize) -> f64 {
+        1.0 - self.normalized_distance(index)
+    }
+}
+
+impl<CharT> Extend<CharT> for IncrementalComparator
+where
+    CharT: HashableChar + Copy,
+{
+    fn extend<Iter: IntoIterator<Item = CharT>>(&mut self, iter: Iter) {
+        for ch in iter {
+            self.push(ch);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -862,4 +1060,34 @@ mod tests {
             test_distance("ab".chars(), "ac".chars(), &Args::default())
         );
     }
+
+    #[test]
+    fn test_incremental_comparator() {
+        let candidates = ["", "a", "lewenstein", "levenshtein", &"xy".repeat(80)];
+        let mut scorer = IncrementalComparator::new(candidates.iter().map(|c| c.chars()));
+
+        let query: String = "levenshteinx"
+            .chars()
+            .chain("yx".repeat(70).chars())
+            .collect();
+        for (len, ch) in query.char_indices() {
+            for (index, candidate) in candidates.iter().enumerate() {
+                let query = &query[..len];
+                assert_eq!(
+                    distance(query.chars(), candidate.chars()),
+                    scorer.distance(index)
+                );
+                assert_delta!(
+                    Some(normalized_similarity(query.chars(), candidate.chars())),
+                    Some(scorer.normalized_similarity(index)),
+                    0.0001
+                );
+            }
+            scorer.push(ch);
+        }
+
+        scorer.clear();
+        assert_eq!(0, scorer.query_len());
+        assert_eq!(11, scorer.distance(3));
+    }
 }
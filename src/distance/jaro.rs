@@ -13,22 +13,26 @@
 //! ![benchmark results](https://raw.githubusercontent.com/rapidfuzz/rapidfuzz-rs/main/rapidfuzz-benches/results/jaro.svg)
 //!
 
-use crate::common::{DistanceCutoff, NoScoreCutoff, SimilarityCutoff, WithScoreCutoff};
+use crate::common::{
+    DistanceCutoff, NoProcessor, NoScoreCutoff, Processor, SimilarityCutoff, WithScoreCutoff,
+};
 use crate::details::common::find_common_prefix;
 use crate::details::distance::Metricf64;
 use crate::details::intrinsics::{bit_mask_lsb_u64, blsi_u64, ceil_div_usize};
 use crate::details::pattern_match_vector::{
     BitVectorInterface, BlockPatternMatchVector, PatternMatchVector,
 };
+use crate::distance::{impl_batch_metric, impl_metric};
 use crate::Hash;
 use crate::HashableChar;
 use std::cmp::min;
 
 #[must_use]
 #[derive(Copy, Clone, Debug)]
-pub struct Args<ResultType, CutoffType> {
+pub struct Args<ResultType, CutoffType, ProcessorType = NoProcessor> {
     pub(crate) score_cutoff: CutoffType,
     pub(crate) score_hint: Option<ResultType>,
+    pub(crate) processor: ProcessorType,
 }
 
 impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
@@ -36,11 +40,12 @@ impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
         Args {
             score_cutoff: NoScoreCutoff,
             score_hint: None,
+            processor: NoProcessor,
         }
     }
 }
 
-impl<ResultType, CutoffType> Args<ResultType, CutoffType> {
+impl<ResultType, CutoffType, ProcessorType> Args<ResultType, CutoffType, ProcessorType> {
     pub fn score_hint(mut self, score_hint: ResultType) -> Self {
         self.score_hint = Some(score_hint);
         self
@@ -49,10 +54,20 @@ impl<ResultType, CutoffType> Args<ResultType, CutoffType> {
     pub fn score_cutoff(
         self,
         score_cutoff: ResultType,
-    ) -> Args<ResultType, WithScoreCutoff<ResultType>> {
+    ) -> Args<ResultType, WithScoreCutoff<ResultType>, ProcessorType> {
         Args {
             score_hint: self.score_hint,
             score_cutoff: WithScoreCutoff(score_cutoff),
+            processor: self.processor,
+        }
+    }
+
+    /// preprocessing applied to both sequences before comparing them
+    pub fn processor<P>(self, processor: P) -> Args<ResultType, CutoffType, P> {
+        Args {
+            score_cutoff: self.score_cutoff,
+            score_hint: self.score_hint,
+            processor,
         }
     }
 }
@@ -639,10 +654,10 @@ where
     distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn distance_with_args<Iter1, Iter2, CutoffType>(
+pub fn distance_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -652,9 +667,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff.score(IndividualComparator {}._distance(
         s1_iter.clone(),
         s1_iter.count(),
@@ -678,10 +694,10 @@ where
     similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn similarity_with_args<Iter1, Iter2, CutoffType>(
+pub fn similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -691,9 +707,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff.score(IndividualComparator {}._similarity(
         s1_iter.clone(),
         s1_iter.count(),
@@ -721,10 +738,10 @@ where
     normalized_distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType>(
+pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -734,9 +751,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff
         .score(IndividualComparator {}._normalized_distance(
             s1_iter.clone(),
@@ -765,10 +783,10 @@ where
     normalized_similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType>(
+pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -778,9 +796,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff
         .score(IndividualComparator {}._normalized_similarity(
             s1_iter.clone(),
@@ -792,6 +811,19 @@ where
         ))
 }
 
+/// Marker type describing the Jaro similarity, see [`Metric`](crate::distance::Metric)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Jaro;
+
+impl_metric!(
+    Jaro,
+    f64,
+    higher_is_better: true,
+    optimal_score: 1.0,
+    worst_score: 0.0,
+    comparator: IndividualComparator {},
+);
+
 /// `One x Many` comparisons using the Jaro similarity
 #[derive(Clone)]
 pub struct BatchComparator<Elem1> {
@@ -978,6 +1010,8 @@ where
     }
 }
 
+impl_batch_metric!(BatchComparator, Jaro, args: Args::default());
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1188,6 +1222,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_metric() {
+        use crate::distance::Metric;
+
+        assert_delta!(
+            Some(0.455556),
+            Jaro.similarity("james".chars(), "robert".chars(), None),
+            0.0001
+        );
+        assert_delta!(
+            Some(1.0 - 0.455556),
+            Jaro.normalized_distance("james".chars(), "robert".chars(), Some(0.6)),
+            0.0001
+        );
+        assert_eq!(
+            None,
+            Jaro.normalized_similarity("james".chars(), "robert".chars(), Some(0.5))
+        );
+        assert!(Jaro::is_better(0.9, 0.5));
+    }
+
+    #[test]
+    fn test_batch_metric() {
+        use crate::distance::BatchMetric;
+
+        let scorer = BatchComparator::new("james".chars());
+        assert_delta!(
+            Some(0.455556),
+            BatchMetric::similarity(&scorer, "robert".chars(), None),
+            0.0001
+        );
+        assert_delta!(
+            Some(1.0 - 0.455556),
+            BatchMetric::normalized_distance(&scorer, "robert".chars(), Some(0.6)),
+            0.0001
+        );
+        assert_eq!(
+            None,
+            BatchMetric::normalized_similarity(&scorer, "robert".chars(), Some(0.5))
+        );
+        assert!(<BatchComparator<char> as BatchMetric<char>>::is_better(
+            0.9, 0.5
+        ));
+    }
+
     #[test]
     fn unicode() {
         let args = Args::default().score_cutoff(1.0);
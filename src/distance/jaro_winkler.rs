@@ -13,19 +13,23 @@
 //! ![benchmark results](https://raw.githubusercontent.com/rapidfuzz/rapidfuzz-rs/main/rapidfuzz-benches/results/jaro_winkler.svg)
 //!
 
-use crate::common::{DistanceCutoff, NoScoreCutoff, SimilarityCutoff, WithScoreCutoff};
+use crate::common::{
+    DistanceCutoff, NoProcessor, NoScoreCutoff, Processor, SimilarityCutoff, WithScoreCutoff,
+};
 use crate::details::distance::Metricf64;
 use crate::details::pattern_match_vector::BlockPatternMatchVector;
+use crate::distance::{impl_batch_metric, impl_metric};
 use crate::HashableChar;
 
 use crate::distance::jaro;
 
 #[must_use]
 #[derive(Copy, Clone, Debug)]
-pub struct Args<ResultType, CutoffType> {
+pub struct Args<ResultType, CutoffType, ProcessorType = NoProcessor> {
     score_cutoff: CutoffType,
     score_hint: Option<ResultType>,
     prefix_weight: f64,
+    processor: ProcessorType,
 }
 
 impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
@@ -33,12 +37,13 @@ impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
         Args {
             score_cutoff: NoScoreCutoff,
             score_hint: None,
+            processor: NoProcessor,
             prefix_weight: 0.1,
         }
     }
 }
 
-impl<ResultType, CutoffType> Args<ResultType, CutoffType> {
+impl<ResultType, CutoffType, ProcessorType> Args<ResultType, CutoffType, ProcessorType> {
     pub fn score_hint(mut self, score_hint: ResultType) -> Self {
         self.score_hint = Some(score_hint);
         self
@@ -52,13 +57,24 @@ impl<ResultType, CutoffType> Args<ResultType, CutoffType> {
     pub fn score_cutoff(
         self,
         score_cutoff: ResultType,
-    ) -> Args<ResultType, WithScoreCutoff<ResultType>> {
+    ) -> Args<ResultType, WithScoreCutoff<ResultType>, ProcessorType> {
         Args {
             score_hint: self.score_hint,
             score_cutoff: WithScoreCutoff(score_cutoff),
+            processor: self.processor,
             prefix_weight: self.prefix_weight,
         }
     }
+
+    /// preprocessing applied to both sequences before comparing them
+    pub fn processor<P>(self, processor: P) -> Args<ResultType, CutoffType, P> {
+        Args {
+            score_cutoff: self.score_cutoff,
+            score_hint: self.score_hint,
+            prefix_weight: self.prefix_weight,
+            processor,
+        }
+    }
 }
 
 fn similarity_without_pm<Iter1, Iter2>(
@@ -191,10 +207,10 @@ where
     distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn distance_with_args<Iter1, Iter2, CutoffType>(
+pub fn distance_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -204,9 +220,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff.score(
         IndividualComparator {
             prefix_weight: args.prefix_weight,
@@ -235,10 +252,10 @@ where
     similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn similarity_with_args<Iter1, Iter2, CutoffType>(
+pub fn similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -248,9 +265,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff.score(
         IndividualComparator {
             prefix_weight: args.prefix_weight,
@@ -283,10 +301,10 @@ where
     normalized_distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType>(
+pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -296,9 +314,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff.score(
         IndividualComparator {
             prefix_weight: args.prefix_weight,
@@ -331,10 +350,10 @@ where
     normalized_similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType>(
+pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -344,9 +363,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff.score(
         IndividualComparator {
             prefix_weight: args.prefix_weight,
@@ -399,6 +419,21 @@ impl<CharT> Metricf64 for BatchComparatorImpl<'_, CharT> {
     }
 }
 
+/// Marker type describing the Jaro-Winkler similarity, see [`Metric`](crate::distance::Metric)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct JaroWinkler;
+
+impl_metric!(
+    JaroWinkler,
+    f64,
+    higher_is_better: true,
+    optimal_score: 1.0,
+    worst_score: 0.0,
+    comparator: IndividualComparator {
+        prefix_weight: 0.1,
+    },
+);
+
 /// `One x Many` comparisons using the Jaro-Winkler similarity
 #[derive(Clone)]
 pub struct BatchComparator<Elem1> {
@@ -577,6 +612,8 @@ where
     }
 }
 
+impl_batch_metric!(BatchComparator, JaroWinkler, args: Args::default());
+
 #[cfg(test)]
 mod tests {
     use super::*;
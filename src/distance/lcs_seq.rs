@@ -8,10 +8,10 @@
 //! It's useful in applications where the order of elements is significant, but their exact positions
 //! may vary. Common use cases involve:
 //! - **Bioinformatics:** Commonly used in Bioinformatics for comparing genetic sequences where identifying shared genes
-//!    or regions, even if not contiguous, is important.
+//!   or regions, even if not contiguous, is important.
 //! - **Version Control Systems:** Tracking changes between different versions of a document or codebase.
 //! - **Plagiarism Detection:** Identifying similarities between texts even when the wording is rearranged or some
-//!     content is added or removed.
+//!   content is added or removed.
 //!
 //! ## Performance
 //!
@@ -21,22 +21,26 @@
 //! ![benchmark results](https://raw.githubusercontent.com/rapidfuzz/rapidfuzz-rs/main/rapidfuzz-benches/results/longest_common_subsequence.svg)
 //!
 
-use crate::common::{DistanceCutoff, NoScoreCutoff, SimilarityCutoff, WithScoreCutoff};
-use crate::details::common::remove_common_affix;
+use crate::common::{
+    DistanceCutoff, NoProcessor, NoScoreCutoff, Processor, SimilarityCutoff, WithScoreCutoff,
+};
+use crate::details::common::{find_common_prefix, find_common_suffix, remove_common_affix};
 use crate::details::distance::MetricUsize;
 use crate::details::intrinsics::{carrying_add, ceil_div_usize};
 use crate::details::matrix::ShiftedBitMatrix;
 use crate::details::pattern_match_vector::{
     BitVectorInterface, BlockPatternMatchVector, PatternMatchVector,
 };
+use crate::distance::{impl_batch_metric, impl_metric, EditType, Editop, Editops, MatchingBlock};
 use crate::HashableChar;
 use std::cmp::{max, min};
 
 #[must_use]
 #[derive(Copy, Clone, Debug)]
-pub struct Args<ResultType, CutoffType> {
+pub struct Args<ResultType, CutoffType, ProcessorType = NoProcessor> {
     pub(crate) score_cutoff: CutoffType,
     pub(crate) score_hint: Option<ResultType>,
+    pub(crate) processor: ProcessorType,
 }
 
 impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
@@ -44,11 +48,12 @@ impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
         Args {
             score_cutoff: NoScoreCutoff,
             score_hint: None,
+            processor: NoProcessor,
         }
     }
 }
 
-impl<ResultType, CutoffType> Args<ResultType, CutoffType> {
+impl<ResultType, CutoffType, ProcessorType> Args<ResultType, CutoffType, ProcessorType> {
     pub fn score_hint(mut self, score_hint: ResultType) -> Self {
         self.score_hint = Some(score_hint);
         self
@@ -57,10 +62,20 @@ impl<ResultType, CutoffType> Args<ResultType, CutoffType> {
     pub fn score_cutoff(
         self,
         score_cutoff: ResultType,
-    ) -> Args<ResultType, WithScoreCutoff<ResultType>> {
+    ) -> Args<ResultType, WithScoreCutoff<ResultType>, ProcessorType> {
         Args {
             score_hint: self.score_hint,
             score_cutoff: WithScoreCutoff(score_cutoff),
+            processor: self.processor,
+        }
+    }
+
+    /// preprocessing applied to both sequences before comparing them
+    pub fn processor<P>(self, processor: P) -> Args<ResultType, CutoffType, P> {
+        Args {
+            score_cutoff: self.score_cutoff,
+            score_hint: self.score_hint,
+            processor,
         }
     }
 }
@@ -474,11 +489,7 @@ where
     let affix = remove_common_affix(s1, len1, s2, len2);
     let mut lcs_sim = affix.prefix_len + affix.suffix_len;
     if affix.len1 != 0 && affix.len2 != 0 {
-        let adjusted_cutoff = if score_cutoff >= lcs_sim {
-            score_cutoff - lcs_sim
-        } else {
-            0
-        };
+        let adjusted_cutoff = score_cutoff.saturating_sub(lcs_sim);
         lcs_sim += mbleven2018(affix.s1, affix.len1, affix.s2, affix.len2, adjusted_cutoff);
     }
 
@@ -522,11 +533,7 @@ where
 
     let mut lcs_sim = affix.prefix_len + affix.suffix_len;
     if affix.len1 != 0 && affix.len2 != 0 {
-        let adjusted_cutoff = if score_cutoff >= lcs_sim {
-            score_cutoff - lcs_sim
-        } else {
-            0
-        };
+        let adjusted_cutoff = score_cutoff.saturating_sub(lcs_sim);
         if max_misses < 5 {
             lcs_sim += mbleven2018(affix.s1, affix.len1, affix.s2, affix.len2, adjusted_cutoff);
         } else {
@@ -543,6 +550,133 @@ where
     lcs_sim
 }
 
+fn lcs_matrix<Elem1, Elem2>(s1: &[Elem1], s2: &[Elem2]) -> DistanceResult<1>
+where
+    Elem1: PartialEq<Elem2> + HashableChar + Copy,
+    Elem2: PartialEq<Elem1> + HashableChar + Copy,
+{
+    let len1 = s1.len();
+    let len2 = s2.len();
+    let s1_iter = s1.iter().copied();
+    let s2_iter = s2.iter().copied();
+
+    if len1 == 0 {
+        return DistanceResult::default();
+    }
+
+    if len1 <= 64 {
+        let mut pm = PatternMatchVector::default();
+        pm.insert(s1_iter.clone());
+        return lcs_unroll::<1, 1, _, _, _>(&pm, s1_iter, len1, s2_iter, len2, 0);
+    }
+
+    let mut pm = BlockPatternMatchVector::new(len1);
+    pm.insert(s1_iter.clone());
+    match ceil_div_usize(len1, 64) {
+        2 => lcs_unroll::<2, 1, _, _, _>(&pm, s1_iter, len1, s2_iter, len2, 0),
+        3 => lcs_unroll::<3, 1, _, _, _>(&pm, s1_iter, len1, s2_iter, len2, 0),
+        4 => lcs_unroll::<4, 1, _, _, _>(&pm, s1_iter, len1, s2_iter, len2, 0),
+        5 => lcs_unroll::<5, 1, _, _, _>(&pm, s1_iter, len1, s2_iter, len2, 0),
+        6 => lcs_unroll::<6, 1, _, _, _>(&pm, s1_iter, len1, s2_iter, len2, 0),
+        7 => lcs_unroll::<7, 1, _, _, _>(&pm, s1_iter, len1, s2_iter, len2, 0),
+        8 => lcs_unroll::<8, 1, _, _, _>(&pm, s1_iter, len1, s2_iter, len2, 0),
+        _ => lcs_blockwise::<1, _, _, _>(&pm, s1_iter, len1, s2_iter, len2, 0),
+    }
+}
+
+/// recovers the insertions and deletions from the bit matrix recorded while
+/// calculating the longest common subsequence
+fn recover_alignment<Elem1, Elem2>(
+    s1: &[Elem1],
+    s2: &[Elem2],
+    matrix: &DistanceResult<1>,
+    prefix_len: usize,
+) -> Vec<Editop>
+where
+    Elem1: PartialEq<Elem2>,
+{
+    let mut col = s1.len();
+    let mut row = s2.len();
+    let mut dist = col + row - 2 * matrix.sim;
+    let s = &matrix.record_matrix[0].s;
+
+    let mut editops = vec![
+        Editop {
+            tag: EditType::Delete,
+            src_pos: 0,
+            dest_pos: 0,
+        };
+        dist
+    ];
+    if dist == 0 {
+        return editops;
+    }
+
+    let mut push = |dist: usize, tag: EditType, col: usize, row: usize| {
+        editops[dist] = Editop {
+            tag,
+            src_pos: col + prefix_len,
+            dest_pos: row + prefix_len,
+        };
+    };
+
+    while row != 0 && col != 0 {
+        // Deletion
+        if s.test_bit(row - 1, col - 1, false) {
+            debug_assert!(dist > 0);
+            dist -= 1;
+            col -= 1;
+            push(dist, EditType::Delete, col, row);
+        } else {
+            row -= 1;
+
+            // Insertion
+            if row != 0 && !s.test_bit(row - 1, col - 1, false) {
+                debug_assert!(dist > 0);
+                dist -= 1;
+                push(dist, EditType::Insert, col, row);
+            }
+            // Match
+            else {
+                col -= 1;
+                debug_assert!(s1[col] == s2[row]);
+            }
+        }
+    }
+
+    while col != 0 {
+        dist -= 1;
+        col -= 1;
+        push(dist, EditType::Delete, col, row);
+    }
+
+    while row != 0 {
+        dist -= 1;
+        row -= 1;
+        push(dist, EditType::Insert, col, row);
+    }
+
+    editops
+}
+
+pub(crate) fn editops_impl<Elem1, Elem2>(s1: &[Elem1], s2: &[Elem2]) -> Editops
+where
+    Elem1: PartialEq<Elem2> + HashableChar + Copy,
+    Elem2: PartialEq<Elem1> + HashableChar + Copy,
+{
+    // prefix and suffix are no-ops, which do not need to be added to the editops
+    let prefix_len = find_common_prefix(s1.iter(), s2.iter());
+    let s1_ = &s1[prefix_len..];
+    let s2_ = &s2[prefix_len..];
+    let suffix_len = find_common_suffix(s1_.iter(), s2_.iter());
+    let s1_ = &s1_[..s1_.len() - suffix_len];
+    let s2_ = &s2_[..s2_.len() - suffix_len];
+
+    let matrix = lcs_matrix(s1_, s2_);
+    let ops = recover_alignment(s1_, s2_, &matrix, prefix_len);
+    Editops::new(ops, s1.len(), s2.len())
+}
+
 pub(crate) struct IndividualComparator;
 
 impl MetricUsize for IndividualComparator {
@@ -592,10 +726,10 @@ where
     distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn distance_with_args<Iter1, Iter2, CutoffType>(
+pub fn distance_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<usize, CutoffType>,
+    args: &Args<usize, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -605,9 +739,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<usize>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff.score(IndividualComparator {}._distance(
         s1_iter.clone(),
         s1_iter.count(),
@@ -641,10 +776,10 @@ where
     similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn similarity_with_args<Iter1, Iter2, CutoffType>(
+pub fn similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<usize, CutoffType>,
+    args: &Args<usize, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -654,9 +789,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<usize>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff.score(IndividualComparator {}._similarity(
         s1_iter.clone(),
         s1_iter.count(),
@@ -683,10 +819,10 @@ where
     normalized_distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType>(
+pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -696,9 +832,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff
         .score(IndividualComparator {}._normalized_distance(
             s1_iter.clone(),
@@ -726,10 +863,10 @@ where
     normalized_similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType>(
+pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -739,9 +876,10 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff
         .score(IndividualComparator {}._normalized_similarity(
             s1_iter.clone(),
@@ -753,6 +891,88 @@ where
         ))
 }
 
+/// Returns the insertions and deletions required to transform `s1` into `s2`
+///
+/// Every element, which is not part of the longest common subsequence, is either
+/// deleted from `s1` or inserted from `s2`. So the number of operations is equal to
+/// the [`Indel`] distance.
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::lcs_seq;
+/// use rapidfuzz::distance::{EditType, Editop};
+///
+/// let ops = lcs_seq::editops("qabxcd".chars(), "abycdf".chars());
+/// assert_eq!(
+///     &[
+///         Editop { tag: EditType::Delete, src_pos: 0, dest_pos: 0 },
+///         Editop { tag: EditType::Insert, src_pos: 3, dest_pos: 2 },
+///         Editop { tag: EditType::Delete, src_pos: 3, dest_pos: 3 },
+///         Editop { tag: EditType::Insert, src_pos: 6, dest_pos: 5 },
+///     ],
+///     &*ops
+/// );
+/// ```
+///
+/// [`Indel`]: ../indel/index.html
+pub fn editops<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> Editops
+where
+    Iter1: IntoIterator,
+    Iter2: IntoIterator,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    let s1: Vec<Iter1::Item> = s1.into_iter().collect();
+    let s2: Vec<Iter2::Item> = s2.into_iter().collect();
+    editops_impl(&s1, &s2)
+}
+
+/// Returns the blocks of matching elements in `s1` and `s2`
+///
+/// This is the equivalent of `get_matching_blocks` from Python's `difflib.SequenceMatcher`,
+/// but based on the longest common subsequence. The last block is always a dummy block
+/// `MatchingBlock { src_start: len1, dest_start: len2, size: 0 }`.
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::lcs_seq;
+/// use rapidfuzz::distance::MatchingBlock;
+///
+/// let blocks = lcs_seq::matching_blocks("qabxcd".chars(), "abycdf".chars());
+/// assert_eq!(
+///     vec![
+///         MatchingBlock { src_start: 1, dest_start: 0, size: 2 },
+///         MatchingBlock { src_start: 4, dest_start: 3, size: 2 },
+///         MatchingBlock { src_start: 6, dest_start: 6, size: 0 },
+///     ],
+///     blocks
+/// );
+/// ```
+pub fn matching_blocks<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> Vec<MatchingBlock>
+where
+    Iter1: IntoIterator,
+    Iter2: IntoIterator,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    editops(s1, s2).as_matching_blocks()
+}
+
+/// Marker type describing the Longest Common Subsequence similarity, see [`Metric`](crate::distance::Metric)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LcsSeq;
+
+impl_metric!(
+    LcsSeq,
+    usize,
+    higher_is_better: true,
+    optimal_score: usize::MAX,
+    worst_score: 0,
+    comparator: IndividualComparator {},
+);
+
 /// `One x Many` comparisons using the Longest Common Subsequence
 ///
 /// # Examples
@@ -948,6 +1168,8 @@ where
     }
 }
 
+impl_batch_metric!(BatchComparator, LcsSeq, args: Args::default());
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1264,4 +1486,48 @@ mod tests {
             test_distance("ab".chars(), "ac".chars(), &Args::default())
         );
     }
+
+    fn _test_editops(s1: &str, s2: &str) {
+        let s1: Vec<char> = s1.chars().collect();
+        let s2: Vec<char> = s2.chars().collect();
+        let ops = editops(s1.iter().copied(), s2.iter().copied());
+        assert_eq!(
+            crate::distance::indel::distance(s1.iter(), s2.iter()),
+            ops.len()
+        );
+
+        assert!(ops.iter().all(|op| op.tag != EditType::Replace));
+        assert_eq!(s2, ops.apply(s1.iter().copied(), s2.iter().copied()));
+
+        let blocks = matching_blocks(s1.iter().copied(), s2.iter().copied());
+        let matches: usize = blocks.iter().map(|b| b.size).sum();
+        assert_eq!(similarity(s1.iter(), s2.iter()), matches);
+        for block in blocks {
+            assert_eq!(
+                s1[block.src_start..block.src_start + block.size],
+                s2[block.dest_start..block.dest_start + block.size]
+            );
+        }
+    }
+
+    #[test]
+    fn test_editops() {
+        _test_editops("", "");
+        _test_editops("aaaa", "");
+        _test_editops("", "aaaa");
+        _test_editops("aaaa", "aaaa");
+        _test_editops("qabxcd", "abycdf");
+        _test_editops("lewenstein", "levenshtein");
+        _test_editops("Иванко", "Петрунко");
+
+        // unroll
+        let s1 = "abcdefghij".repeat(20);
+        let s2 = "ghijabcx".repeat(30);
+        _test_editops(&s1, &s2);
+        _test_editops(&s2, &s1);
+        // blockwise
+        let s1 = "abcdefghij".repeat(60);
+        let s2 = "ghijabcx".repeat(70);
+        _test_editops(&s1, &s2);
+    }
 }
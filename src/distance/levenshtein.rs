@@ -32,6 +32,10 @@
 //! );
 //! ```
 //!
+//! Costs depending on the edited elements, e.g. to make substitutions of characters
+//! commonly confused by OCR engines cheap, can be provided using a [`CostModel`] like
+//! [`SubstitutionMatrix`].
+//!
 //! # Performance
 //!
 //! The performance of the implementation depends on the provided weights for edit operations.
@@ -59,16 +63,25 @@
 //!
 //! ## Generic Levenshtein distance
 //!
-//! The implementation has a runtime complexity of `O(N*M)` and a memory usage of `O(N)`.
-//! It's based on the Wagner-Fischer algorithm.
+//! The implementation has a runtime complexity of `O(K*M)` (with `K = MIN(N, score_cutoff)`) and a memory usage of `O(N)`.
+//! It's based on the Wagner-Fischer algorithm, which only calculates the diagonal band of the
+//! matrix that can lead to a distance within the `score_cutoff` as described by Ukkonen.
 //!
 //! ![benchmark results](https://raw.githubusercontent.com/rapidfuzz/rapidfuzz-rs/main/rapidfuzz-benches/results/generic_levenshtein.svg)
 //!
+//! ## Cost models
+//!
+//! The implementation has a runtime complexity of `O(N*M)` and a memory usage of `O(N)`.
+//! It's based on the Wagner-Fischer algorithm and exits early once every cell of a row
+//! exceeds the `score_cutoff`.
+//!
 //!
 //! [`Indel`]: ../levenshtein/index.html
 
-use crate::common::{DistanceCutoff, NoScoreCutoff, SimilarityCutoff, WithScoreCutoff};
-use crate::details::common::remove_common_affix;
+use crate::common::{
+    DistanceCutoff, NoProcessor, NoScoreCutoff, Processor, SimilarityCutoff, WithScoreCutoff,
+};
+use crate::details::common::{find_common_prefix, find_common_suffix, remove_common_affix};
 use crate::details::distance::MetricUsize;
 use crate::details::growing_hashmap::{GrowingHashmap, HybridGrowingHashmap};
 use crate::details::intrinsics::{ceil_div_usize, shr64};
@@ -77,16 +90,21 @@ use crate::details::pattern_match_vector::{
     BitVectorInterface, BlockPatternMatchVector, PatternMatchVector,
 };
 use crate::distance::indel;
-use crate::HashableChar;
+use crate::distance::{impl_batch_metric, impl_metric, EditType, Editop, Editops, Opcodes};
+use crate::{Hash, HashableChar};
 use std::cmp::{max, min};
+use std::collections::{HashMap, VecDeque};
 use std::mem;
 
 #[must_use]
 #[derive(Copy, Clone, Debug)]
-pub struct Args<ResultType, CutoffType> {
+pub struct Args<ResultType, CutoffType, ProcessorType = NoProcessor, CostModelType = NoCostModel> {
     score_cutoff: CutoffType,
     score_hint: Option<ResultType>,
     weights: WeightTable,
+    prefix_mode: bool,
+    processor: ProcessorType,
+    cost_model: CostModelType,
 }
 
 impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
@@ -94,12 +112,16 @@ impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
         Args {
             score_cutoff: NoScoreCutoff,
             score_hint: None,
+            processor: NoProcessor,
             weights: WeightTable::default(),
+            prefix_mode: false,
+            cost_model: NoCostModel,
         }
     }
 }
 
-impl<ResultType, CutoffType> Args<ResultType, CutoffType>
+impl<ResultType, CutoffType, ProcessorType, CostModelType>
+    Args<ResultType, CutoffType, ProcessorType, CostModelType>
 where
     ResultType: Copy,
 {
@@ -111,11 +133,14 @@ where
     pub fn score_cutoff(
         self,
         score_cutoff: ResultType,
-    ) -> Args<ResultType, WithScoreCutoff<ResultType>> {
+    ) -> Args<ResultType, WithScoreCutoff<ResultType>, ProcessorType, CostModelType> {
         Args {
             score_hint: self.score_hint,
             score_cutoff: WithScoreCutoff(score_cutoff),
+            processor: self.processor,
             weights: self.weights,
+            prefix_mode: self.prefix_mode,
+            cost_model: self.cost_model,
         }
     }
 
@@ -123,10 +148,48 @@ where
         self.weights = *weights;
         self
     }
+
+    /// Compare `s1` with the best matching prefix of `s2` instead of the whole sequence
+    ///
+    /// The remaining suffix of `s2` is free, which is useful to score completions of a
+    /// partially typed query. The maximum distance and therefore the normalization only
+    /// depend on the length of `s1`. In contrast to the default mode the result is not
+    /// symmetric.
+    pub fn prefix_mode(mut self, prefix_mode: bool) -> Self {
+        self.prefix_mode = prefix_mode;
+        self
+    }
+
+    /// preprocessing applied to both sequences before comparing them
+    pub fn processor<P>(self, processor: P) -> Args<ResultType, CutoffType, P, CostModelType> {
+        Args {
+            score_cutoff: self.score_cutoff,
+            score_hint: self.score_hint,
+            weights: self.weights,
+            prefix_mode: self.prefix_mode,
+            processor,
+            cost_model: self.cost_model,
+        }
+    }
+
+    /// Use element specific costs from a [`CostModel`] instead of the [`WeightTable`]
+    ///
+    /// The cost model is passed by value, so pass a reference to reuse it for multiple
+    /// comparisons.
+    pub fn cost_model<C>(self, cost_model: C) -> Args<ResultType, CutoffType, ProcessorType, C> {
+        Args {
+            score_cutoff: self.score_cutoff,
+            score_hint: self.score_hint,
+            weights: self.weights,
+            prefix_mode: self.prefix_mode,
+            processor: self.processor,
+            cost_model,
+        }
+    }
 }
 
 /// Weight table to specify the costs of edit operations in the Levenshtein distance
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct WeightTable {
     /// cost of insertions
     pub insertion_cost: usize,
@@ -147,6 +210,284 @@ impl Default for WeightTable {
     }
 }
 
+/// Element specific costs of edit operations in the Levenshtein distance
+///
+/// The elements are passed as their [`Hash`](enum@crate::Hash) (see [`HashableChar`]).
+/// Substituting an element with an equal element is always free, so `substitution_cost`
+/// is only called for different elements.
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::levenshtein::{self, CostModel};
+/// use rapidfuzz::Hash;
+///
+/// /// substituting digits is cheap
+/// struct Digits;
+///
+/// impl CostModel for Digits {
+///     fn insertion_cost(&self, _ch: Hash) -> usize {
+///         2
+///     }
+///
+///     fn deletion_cost(&self, _ch: Hash) -> usize {
+///         2
+///     }
+///
+///     fn substitution_cost(&self, ch1: Hash, ch2: Hash) -> usize {
+///         match (ch1, ch2) {
+///             (Hash::UNSIGNED(a), Hash::UNSIGNED(b))
+///                 if (a as u8).is_ascii_digit() && (b as u8).is_ascii_digit() => 1,
+///             _ => 2,
+///         }
+///     }
+/// }
+///
+/// let args = levenshtein::Args::default().cost_model(Digits);
+/// assert_eq!(1, levenshtein::distance_with_args("a1".chars(), "a7".chars(), &args));
+/// assert_eq!(2, levenshtein::distance_with_args("a1".chars(), "b1".chars(), &args));
+/// ```
+pub trait CostModel {
+    /// cost of inserting `ch`
+    fn insertion_cost(&self, ch: Hash) -> usize;
+    /// cost of deleting `ch`
+    fn deletion_cost(&self, ch: Hash) -> usize;
+    /// cost of replacing `ch1` with `ch2`
+    fn substitution_cost(&self, ch1: Hash, ch2: Hash) -> usize;
+}
+
+impl<T> CostModel for &T
+where
+    T: CostModel + ?Sized,
+{
+    fn insertion_cost(&self, ch: Hash) -> usize {
+        (**self).insertion_cost(ch)
+    }
+
+    fn deletion_cost(&self, ch: Hash) -> usize {
+        (**self).deletion_cost(ch)
+    }
+
+    fn substitution_cost(&self, ch1: Hash, ch2: Hash) -> usize {
+        (**self).substitution_cost(ch1, ch2)
+    }
+}
+
+/// Cost model used by default, which applies the [`WeightTable`]
+#[derive(Default, Copy, Clone, Debug)]
+pub struct NoCostModel;
+
+/// Cost model passed to [`Args::cost_model`]
+///
+/// This is implemented for [`NoCostModel`] and any [`CostModel`].
+pub trait OptionalCostModel {
+    fn cost_model(&self) -> Option<&dyn CostModel>;
+}
+
+impl OptionalCostModel for NoCostModel {
+    fn cost_model(&self) -> Option<&dyn CostModel> {
+        None
+    }
+}
+
+impl<T> OptionalCostModel for T
+where
+    T: CostModel,
+{
+    fn cost_model(&self) -> Option<&dyn CostModel> {
+        Some(self)
+    }
+}
+
+/// [`CostModel`] based on a [`WeightTable`] with exceptions for specific elements
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::levenshtein::{self, SubstitutionMatrix, WeightTable};
+///
+/// let weights = WeightTable {
+///     insertion_cost: 2,
+///     deletion_cost: 2,
+///     substitution_cost: 2,
+/// };
+/// let matrix = SubstitutionMatrix::new(&weights)
+///     .substitution('a', 'ä', 1)
+///     .insertion(' ', 1);
+///
+/// let args = levenshtein::Args::default().cost_model(&matrix);
+/// assert_eq!(2, levenshtein::distance_with_args("Bar".chars(), "Bär ".chars(), &args));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SubstitutionMatrix {
+    weights: WeightTable,
+    insertions: HashMap<Hash, usize>,
+    deletions: HashMap<Hash, usize>,
+    substitutions: HashMap<(Hash, Hash), usize>,
+}
+
+impl Default for SubstitutionMatrix {
+    fn default() -> Self {
+        Self::new(&WeightTable::default())
+    }
+}
+
+impl SubstitutionMatrix {
+    /// creates a matrix using `weights` for all elements
+    pub fn new(weights: &WeightTable) -> Self {
+        Self {
+            weights: *weights,
+            insertions: HashMap::new(),
+            deletions: HashMap::new(),
+            substitutions: HashMap::new(),
+        }
+    }
+
+    /// sets the cost of inserting `ch`
+    pub fn insertion<CharT>(mut self, ch: CharT, cost: usize) -> Self
+    where
+        CharT: HashableChar,
+    {
+        self.insertions.insert(ch.hash_char(), cost);
+        self
+    }
+
+    /// sets the cost of deleting `ch`
+    pub fn deletion<CharT>(mut self, ch: CharT, cost: usize) -> Self
+    where
+        CharT: HashableChar,
+    {
+        self.deletions.insert(ch.hash_char(), cost);
+        self
+    }
+
+    /// sets the cost of substituting `ch1` with `ch2` and vice versa
+    pub fn substitution<CharT>(mut self, ch1: CharT, ch2: CharT, cost: usize) -> Self
+    where
+        CharT: HashableChar,
+    {
+        let (ch1, ch2) = (ch1.hash_char(), ch2.hash_char());
+        self.substitutions.insert((ch1, ch2), cost);
+        self.substitutions.insert((ch2, ch1), cost);
+        self
+    }
+
+    /// Substitutions of keys next to each other on a QWERTY keyboard cost 1,
+    /// while all other operations cost 2
+    ///
+    /// This covers the digits and the letters in lower and upper case.
+    ///
+    /// ```
+    /// use rapidfuzz::distance::levenshtein::{self, SubstitutionMatrix};
+    ///
+    /// let args = levenshtein::Args::default().cost_model(SubstitutionMatrix::qwerty());
+    /// assert_eq!(1, levenshtein::distance_with_args("hello".chars(), "jello".chars(), &args));
+    /// assert_eq!(2, levenshtein::distance_with_args("hello".chars(), "kello".chars(), &args));
+    /// ```
+    pub fn qwerty() -> Self {
+        const ROWS: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+        let mut matrix = Self::new(&WeightTable {
+            insertion_cost: 2,
+            deletion_cost: 2,
+            substitution_cost: 2,
+        });
+        for (row, keys) in ROWS.iter().enumerate() {
+            let keys: Vec<char> = keys.chars().collect();
+            let mut neighbours: Vec<(char, char)> =
+                keys.windows(2).map(|pair| (pair[0], pair[1])).collect();
+            // rows are shifted to the right, so a key is below the key with the same
+            // index and the one after it
+            if let Some(above) = row.checked_sub(1).map(|above| ROWS[above]) {
+                let above: Vec<char> = above.chars().collect();
+                for (i, &key) in keys.iter().enumerate() {
+                    neighbours.extend(above.iter().skip(i).take(2).map(|&other| (key, other)));
+                }
+            }
+
+            for (key1, key2) in neighbours {
+                matrix = matrix.substitution(key1, key2, 1);
+                if key1.is_alphabetic() && key2.is_alphabetic() {
+                    let (upper1, upper2) = (key1.to_ascii_uppercase(), key2.to_ascii_uppercase());
+                    matrix = matrix.substitution(upper1, upper2, 1);
+                }
+            }
+        }
+        matrix
+    }
+
+    /// Substitutions of characters commonly confused by OCR engines, like `0` and `O`
+    /// or `1` and `l`, cost 1, while all other operations cost 2
+    ///
+    /// ```
+    /// use rapidfuzz::distance::levenshtein::{self, SubstitutionMatrix};
+    ///
+    /// let args = levenshtein::Args::default().cost_model(SubstitutionMatrix::ocr());
+    /// assert_eq!(2, levenshtein::distance_with_args("10".chars(), "lO".chars(), &args));
+    /// assert_eq!(4, levenshtein::distance_with_args("10".chars(), "xy".chars(), &args));
+    /// ```
+    pub fn ocr() -> Self {
+        const CONFUSIONS: [(char, char); 22] = [
+            ('0', 'O'),
+            ('0', 'o'),
+            ('0', 'D'),
+            ('O', 'o'),
+            ('O', 'D'),
+            ('O', 'Q'),
+            ('1', 'l'),
+            ('1', 'I'),
+            ('1', 'i'),
+            ('1', '|'),
+            ('l', 'I'),
+            ('l', '|'),
+            ('I', '|'),
+            ('2', 'Z'),
+            ('5', 'S'),
+            ('6', 'G'),
+            ('8', 'B'),
+            ('9', 'g'),
+            ('c', 'e'),
+            ('n', 'h'),
+            ('u', 'v'),
+            (',', '.'),
+        ];
+
+        let weights = WeightTable {
+            insertion_cost: 2,
+            deletion_cost: 2,
+            substitution_cost: 2,
+        };
+        CONFUSIONS
+            .into_iter()
+            .fold(Self::new(&weights), |matrix, (ch1, ch2)| {
+                matrix.substitution(ch1, ch2, 1)
+            })
+    }
+}
+
+impl CostModel for SubstitutionMatrix {
+    fn insertion_cost(&self, ch: Hash) -> usize {
+        self.insertions
+            .get(&ch)
+            .copied()
+            .unwrap_or(self.weights.insertion_cost)
+    }
+
+    fn deletion_cost(&self, ch: Hash) -> usize {
+        self.deletions
+            .get(&ch)
+            .copied()
+            .unwrap_or(self.weights.deletion_cost)
+    }
+
+    fn substitution_cost(&self, ch1: Hash, ch2: Hash) -> usize {
+        self.substitutions
+            .get(&(ch1, ch2))
+            .copied()
+            .unwrap_or(self.weights.substitution_cost)
+    }
+}
+
 #[derive(Clone)]
 struct LevenshteinRow {
     vp: u64,
@@ -209,53 +550,105 @@ impl Default for DistanceResult<0, 1> {
     }
 }
 
-fn generalized_wagner_fischer<Iter1, Iter2>(
-    s1: Iter1,
+/// minimum cost of an alignment between sequences, which differ in length by `diff`
+fn length_difference_cost(diff: isize, weights: &WeightTable) -> usize {
+    if diff >= 0 {
+        diff.unsigned_abs() * weights.deletion_cost
+    } else {
+        diff.unsigned_abs() * weights.insertion_cost
+    }
+}
+
+/// Returns the range of diagonals `i - j` of the matrix, which can be part of an alignment
+/// with a cost of at most `score_cutoff`
+///
+/// Any alignment passing through a cell on diagonal `d` has to delete or insert `d`
+/// elements to reach it and the remaining length difference afterwards.
+fn diagonal_band(
     len1: usize,
+    len2: usize,
+    weights: &WeightTable,
+    score_cutoff: usize,
+) -> (isize, isize) {
+    let center = len1 as isize - len2 as isize;
+    let lower_bound = |d: isize| {
+        length_difference_cost(d, weights)
+            .saturating_add(length_difference_cost(center - d, weights))
+    };
+
+    // both the first and the last cell are on diagonals with the minimal lower bound
+    let mut band_min = min(0, center);
+    while band_min > -(len2 as isize) && lower_bound(band_min - 1) <= score_cutoff {
+        band_min -= 1;
+    }
+    let mut band_max = max(0, center);
+    while band_max < len1 as isize && lower_bound(band_max + 1) <= score_cutoff {
+        band_max += 1;
+    }
+    (band_min, band_max)
+}
+
+/// Wagner-Fischer algorithm for arbitrary weights, which only calculates the cells in
+/// the diagonal band of the matrix that can lead to a distance of at most `score_cutoff`
+/// (Ukkonen). Returns a value larger than `score_cutoff` when the distance exceeds it.
+fn generalized_wagner_fischer<Elem1, Iter2>(
+    s1: &[Elem1],
     s2: Iter2,
-    _len2: usize,
+    len2: usize,
     weights: &WeightTable,
+    score_cutoff: usize,
 ) -> usize
 where
-    Iter1: Iterator + Clone,
-    Iter2: Iterator + Clone,
-    Iter1::Item: PartialEq<Iter2::Item>,
-    Iter2::Item: PartialEq<Iter1::Item>,
+    Iter2: Iterator,
+    Elem1: PartialEq<Iter2::Item>,
 {
-    let cache_size = len1 + 1;
-    let mut cache: Vec<usize> = (0..cache_size).map(|x| x * weights.deletion_cost).collect();
+    let len1 = s1.len();
+    let (band_min, band_max) = diagonal_band(len1, len2, weights, score_cutoff);
+    let rest_cost = |i: usize, j: usize| {
+        length_difference_cost((len1 - i) as isize - (len2 - j) as isize, weights)
+    };
 
-    for ch2 in s2 {
-        let mut cache_iter = cache.iter_mut().peekable();
-        let mut cur_cache: &mut usize = cache_iter
-            .next()
-            .expect("cache always has at least one element");
-        let mut temp = *cur_cache;
-        *cur_cache += weights.insertion_cost;
-
-        for ch1 in s1.clone() {
-            if ch1 != ch2 {
-                temp = min(
-                    *cur_cache + weights.deletion_cost,
-                    temp + weights.substitution_cost,
-                );
-                temp = min(
-                    temp,
-                    **cache_iter
-                        .peek()
-                        .expect("cache has len1 + 1 elements, so this should always exist")
-                        + weights.insertion_cost,
-                );
-            }
+    // cells outside of the band are never part of a result within the score_cutoff
+    let mut cache = vec![usize::MAX; len1 + 1];
+    for (i, cell) in cache.iter_mut().enumerate().take(band_max as usize + 1) {
+        *cell = i * weights.deletion_cost;
+    }
+
+    for (j, ch2) in s2.enumerate().map(|(j, ch2)| (j + 1, ch2)) {
+        let first = max(0, j as isize + band_min) as usize;
+        let last = min(len1 as isize, j as isize + band_max) as usize;
+
+        let mut diag = if first == 0 { 0 } else { cache[first - 1] };
+        let mut up = usize::MAX;
+        let mut band_lower_bound = usize::MAX;
+        for i in first..=last {
+            let left = cache[i];
+            let temp = if i == 0 {
+                j * weights.insertion_cost
+            } else if s1[i - 1] == ch2 {
+                diag
+            } else {
+                min(
+                    diag.saturating_add(weights.substitution_cost),
+                    min(
+                        left.saturating_add(weights.insertion_cost),
+                        up.saturating_add(weights.deletion_cost),
+                    ),
+                )
+            };
+
+            diag = left;
+            up = temp;
+            cache[i] = temp;
+            band_lower_bound = min(band_lower_bound, temp.saturating_add(rest_cost(i, j)));
+        }
 
-            cur_cache = cache_iter
-                .next()
-                .expect("cache has len1 + 1 elements, so this should always exist");
-            mem::swap(cur_cache, &mut temp);
+        if band_lower_bound > score_cutoff {
+            return usize::MAX;
         }
     }
 
-    *cache.last().expect("cache always has at least one element")
+    cache[len1]
 }
 
 /// calculates the maximum possible Levenshtein distance based on
@@ -290,6 +683,7 @@ fn generalized_distance<Iter1, Iter2>(
     len2: usize,
     weights: &WeightTable,
     score_cutoff: usize,
+    mut score_hint: usize,
 ) -> usize
 where
     Iter1: DoubleEndedIterator + Clone,
@@ -304,8 +698,25 @@ where
 
     // common affix does not effect Levenshtein distance
     let affix = remove_common_affix(s1, len1, s2, len2);
+    let s1: Vec<Iter1::Item> = affix.s1.collect();
+
+    // the band grows with the score_cutoff, so start with a small one and increase
+    // it exponentially, similar to the uniform Levenshtein distance
+    score_hint = max(score_hint, 31);
+    while score_hint < score_cutoff {
+        let dist =
+            generalized_wagner_fischer(&s1, affix.s2.clone(), affix.len2, weights, score_hint);
+        if dist <= score_hint {
+            return dist;
+        }
+
+        if usize::MAX / 2 < score_hint {
+            break;
+        }
+        score_hint *= 2;
+    }
 
-    generalized_wagner_fischer(affix.s1, affix.len1, affix.s2, affix.len2, weights)
+    generalized_wagner_fischer(&s1, affix.s2, affix.len2, weights, score_cutoff)
 }
 
 /// An encoded mbleven model table.
@@ -431,7 +842,9 @@ where
 /// This implementation requires the first string to have a length <= 64.
 /// The algorithm used stems from `hyrro_2002` and has a time complexity
 /// of O(N). Comments and variable names in the implementation follow the
-/// paper. This implementation is used internally when the strings are short enough
+/// paper. This implementation is used internally when the strings are short enough.
+/// With `prefix_mode` the minimum distance between `s1` and any prefix of `s2` is
+/// calculated instead
 fn hyrroe2003<const RECORD_MATRIX: usize, const RECORD_BIT_ROW: usize, PmVec, Iter1, Iter2>(
     pm: &PmVec,
     _s1: Iter1,
@@ -439,6 +852,7 @@ fn hyrroe2003<const RECORD_MATRIX: usize, const RECORD_BIT_ROW: usize, PmVec, It
     s2: Iter2,
     len2: usize,
     score_cutoff: usize,
+    prefix_mode: bool,
 ) -> DistanceResult<RECORD_MATRIX, RECORD_BIT_ROW>
 where
     Iter1: Iterator,
@@ -455,6 +869,8 @@ where
     let mut vn: u64 = 0;
 
     let mut dist = len1;
+    // smallest value of D[m,j] seen so far, which is the result in prefix mode
+    let mut best = len1;
     let mut res = DistanceResult::<RECORD_MATRIX, RECORD_BIT_ROW>::default();
     if RECORD_MATRIX == 1 {
         res.record_matrix[0].vp = ShiftedBitMatrix::<u64>::new(len2, 1, !0_u64);
@@ -464,6 +880,12 @@ where
     let mask: u64 = 1_u64 << (len1 - 1);
 
     for (i, ch2) in s2.enumerate() {
+        // D[m,j] >= j - m, so longer prefixes can neither improve on best nor
+        // stay below score_cutoff
+        if prefix_mode && i + 1 >= len1 + min(best, score_cutoff.saturating_add(1)) {
+            break;
+        }
+
         let pm_j = pm.get(0, ch2);
         let x = pm_j;
         let d0 = ((x & vp).wrapping_add(vp) ^ vp) | x | vn;
@@ -475,6 +897,7 @@ where
         // Step 3: Computing the value D[m,j]
         dist += usize::from(hp & mask != 0);
         dist -= usize::from(hn & mask != 0);
+        best = min(best, dist);
 
         // Step 4: Computing Vp and VN
         hp = (hp << 1) | 1;
@@ -489,6 +912,9 @@ where
         }
     }
 
+    if prefix_mode {
+        dist = best;
+    }
     res.dist = if dist <= score_cutoff {
         dist
     } else {
@@ -766,6 +1192,10 @@ where
     res
 }
 
+/// Blockwise version of [`hyrroe2003`] for sequences of any length. Only the blocks
+/// inside the Ukkonen band are calculated. With `prefix_mode` the minimum distance
+/// between `s1` and any prefix of `s2` is calculated instead
+#[allow(clippy::too_many_arguments)]
 fn hyrroe2003_block<const RECORD_MATRIX: usize, const RECORD_BIT_ROW: usize, Iter1, Iter2>(
     pm: &BlockPatternMatchVector,
     _s1: Iter1,
@@ -774,6 +1204,7 @@ fn hyrroe2003_block<const RECORD_MATRIX: usize, const RECORD_BIT_ROW: usize, Ite
     len2: usize,
     mut score_cutoff: usize,
     stop_row: isize,
+    prefix_mode: bool,
 ) -> DistanceResult<RECORD_MATRIX, RECORD_BIT_ROW>
 where
     Iter1: Iterator + Clone,
@@ -783,7 +1214,13 @@ where
     DistanceResult<RECORD_MATRIX, RECORD_BIT_ROW>: Default,
 {
     let mut res: DistanceResult<RECORD_MATRIX, RECORD_BIT_ROW> = DistanceResult::default();
-    if score_cutoff < len1.abs_diff(len2) {
+    // in prefix mode only the part of s1 that is longer than s2 has to be deleted
+    let min_dist = if prefix_mode {
+        len1.saturating_sub(len2)
+    } else {
+        len1.abs_diff(len2)
+    };
+    if score_cutoff < min_dist {
         res.dist = usize::MAX;
         return res;
     }
@@ -807,20 +1244,34 @@ where
         res.bit_row[0].prev_score = 0;
     }
 
-    score_cutoff = min(score_cutoff, max(len1, len2));
+    // smallest value of D[m,j] seen so far, which is the result in prefix mode
+    let mut best = len1;
     // first_block is the index of the first block in Ukkonen band.
     let mut first_block: usize = 0;
     // last_block is the index of the last block in Ukkonen band.
-    let mut last_block = min(
-        words,
-        ceil_div_usize(
-            min(score_cutoff, (score_cutoff + len1 - len2) / 2) + 1,
-            word_size,
-        ),
-    ) - 1;
+    let mut last_block;
+    if prefix_mode {
+        // matching against the empty prefix only requires deleting s1
+        score_cutoff = min(score_cutoff, len1);
+        last_block = min(words, ceil_div_usize(score_cutoff + 1, word_size)) - 1;
+    } else {
+        score_cutoff = min(score_cutoff, max(len1, len2));
+        last_block = min(
+            words,
+            ceil_div_usize(
+                min(score_cutoff, (score_cutoff + len1 - len2) / 2) + 1,
+                word_size,
+            ),
+        ) - 1;
+    }
 
     // Searching
     for (row, ch2) in s2.enumerate() {
+        // D[m,j] >= j - m, so longer prefixes can not stay below score_cutoff
+        if prefix_mode && row >= len1 + score_cutoff {
+            break;
+        }
+
         let mut hp_carry: bool = true;
         let mut hn_carry: bool = false;
 
@@ -894,14 +1345,22 @@ where
             *score -= usize::from(hn_carry);
         }
 
-        score_cutoff = min(
-            score_cutoff as isize,
-            scores[last_block] as isize
-                + max(
-                    len2 as isize - row as isize - 1,
-                    len1 as isize - ((1 + last_block) * word_size - 1) as isize - 1,
-                ),
-        ) as usize;
+        if prefix_mode {
+            // the remaining elements of s1 can always be deleted
+            score_cutoff = min(
+                score_cutoff,
+                scores[last_block] + len1 - 1 - get_row_num(last_block),
+            );
+        } else {
+            score_cutoff = min(
+                score_cutoff as isize,
+                scores[last_block] as isize
+                    + max(
+                        len2 as isize - row as isize - 1,
+                        len1 as isize - ((1 + last_block) * word_size - 1) as isize - 1,
+                    ),
+            ) as usize;
+        }
 
         //---------- Adjust number of blocks according to Ukkonen ----------
         // todo on the last word instead of word_size often s1.size() % 64 should be used
@@ -909,13 +1368,17 @@ where
         // Band adjustment: last_block
         // If block is not beneath band, calculate next block. Only next because others are certainly beneath
         // band.
-        if last_block + 1 < words
-            && get_row_num(last_block) as isize
+        // In prefix mode the band only depends on the row, since D[i,j] >= i - j
+        let extend_band = if prefix_mode {
+            get_row_num(last_block) < row + score_cutoff
+        } else {
+            get_row_num(last_block) as isize
                 <= score_cutoff as isize + 2 * word_size as isize + row as isize + len1 as isize
                     - scores[last_block] as isize
                     - 2
                     - len2 as isize
-        {
+        };
+        if last_block + 1 < words && extend_band {
             last_block += 1;
             vecs[last_block].vp = !0_u64;
             vecs[last_block].vn = 0;
@@ -933,6 +1396,11 @@ where
             scores[last_block] -= usize::from(hn_carry);
         }
 
+        if prefix_mode && last_block + 1 == words {
+            best = min(best, scores[last_block]);
+        }
+
+        let mut band_empty = false;
         while last_block >= first_block {
             // in band if score <= k where score >= score_last - word_size + 1
             let in_band_cond1 = scores[last_block] < score_cutoff + word_size;
@@ -943,34 +1411,40 @@ where
             //
             // this uses a more loose condition similar to edlib:
             // https://github.com/Martinsos/edlib
-            let in_band_cond2 = get_row_num(last_block) as isize
-                <= score_cutoff as isize
-                    + 2 * word_size as isize
-                    + row as isize
-                    + len1 as isize
-                    + 1
-                    - scores[last_block] as isize
-                    - 2
-                    - len2 as isize;
+            let in_band_cond2 = prefix_mode
+                || get_row_num(last_block) as isize
+                    <= score_cutoff as isize
+                        + 2 * word_size as isize
+                        + row as isize
+                        + len1 as isize
+                        + 1
+                        - scores[last_block] as isize
+                        - 2
+                        - len2 as isize;
 
             if in_band_cond1 && in_band_cond2 {
                 break;
             }
+            if last_block == first_block {
+                band_empty = true;
+                break;
+            }
             last_block -= 1;
         }
 
         // Band adjustment: first_block
-        while first_block <= last_block {
+        while !band_empty && first_block <= last_block {
             // in band if score <= k where score >= score_last - word_size + 1
             let in_band_cond1 = scores[first_block] < score_cutoff + word_size;
 
             // in band if row >= score - max - len2 + len1 + i
             // if this condition is met for the last cell in the block, it
             // is met for all other cells in the blocks as well
-            let in_band_cond2 = get_row_num(first_block) as isize
-                >= scores[first_block] as isize + len1 as isize + row as isize
-                    - score_cutoff as isize
-                    - len2 as isize;
+            let in_band_cond2 = prefix_mode
+                || get_row_num(first_block) as isize
+                    >= scores[first_block] as isize + len1 as isize + row as isize
+                        - score_cutoff as isize
+                        - len2 as isize;
 
             if in_band_cond1 && in_band_cond2 {
                 break;
@@ -979,7 +1453,11 @@ where
         }
 
         // distance is larger than max, so band stops to exist
-        if last_block < first_block {
+        if band_empty || last_block < first_block {
+            // in prefix mode a shorter prefix might still be within score_cutoff
+            if prefix_mode {
+                break;
+            }
             res.dist = usize::MAX;
             return res;
         }
@@ -1003,13 +1481,13 @@ where
             res.bit_row[0].first_block = first_block;
             res.bit_row[0].last_block = last_block;
             mem::swap(&mut res.bit_row[0].vecs, &mut vecs);
-            // unknown so set to None
-            res.dist = usize::MAX;
+            // unknown so make sure it is <= max
+            res.dist = 0;
             return res;
         }
     }
 
-    let dist = scores[words - 1];
+    let dist = if prefix_mode { best } else { scores[words - 1] };
     res.dist = if dist <= score_cutoff {
         dist
     } else {
@@ -1060,7 +1538,7 @@ where
         let mut full_band = min(len1, 2 * score_cutoff + 1);
 
         if len1 <= 64 {
-            let res: DistanceResult<0, 0> = hyrroe2003(pm, s1, len1, s2, len2, score_cutoff);
+            let res: DistanceResult<0, 0> = hyrroe2003(pm, s1, len1, s2, len2, score_cutoff, false);
             return res.dist;
         } else if full_band <= 64 {
             return hyrroe2003_small_band_with_pm(pm, s1, len1, s2, len2, score_cutoff);
@@ -1072,8 +1550,16 @@ where
             let score = if full_band <= 64 {
                 hyrroe2003_small_band_with_pm(pm, s1.clone(), len1, s2.clone(), len2, score_hint)
             } else {
-                let res: DistanceResult<0, 0> =
-                    hyrroe2003_block(pm, s1.clone(), len1, s2.clone(), len2, score_hint, -1);
+                let res: DistanceResult<0, 0> = hyrroe2003_block(
+                    pm,
+                    s1.clone(),
+                    len1,
+                    s2.clone(),
+                    len2,
+                    score_hint,
+                    -1,
+                    false,
+                );
                 res.dist
             };
 
@@ -1087,7 +1573,8 @@ where
             score_hint *= 2;
         }
 
-        let res: DistanceResult<0, 0> = hyrroe2003_block(pm, s1, len1, s2, len2, score_cutoff, -1);
+        let res: DistanceResult<0, 0> =
+            hyrroe2003_block(pm, s1, len1, s2, len2, score_cutoff, -1, false);
         return res.dist;
     }
 
@@ -1159,6 +1646,7 @@ where
             affix.s1,
             affix.len1,
             score_cutoff,
+            false,
         );
         res.dist
     } else if full_band <= 64 {
@@ -1194,6 +1682,7 @@ where
                     affix.len2,
                     score_hint,
                     -1,
+                    false,
                 );
                 res.dist
             };
@@ -1216,6 +1705,7 @@ where
             affix.len2,
             score_cutoff,
             -1,
+            false,
         );
         res.dist
     }
@@ -1238,7 +1728,7 @@ where
 {
     // for very short sequences the bitparallel algorithm is not worth it
     if len1 * len2 < 90 {
-        return generalized_distance(s1, len1, s2, len2, weights, score_cutoff);
+        return generalized_distance(s1, len1, s2, len2, weights, score_cutoff, score_hint);
     }
 
     if weights.insertion_cost == weights.deletion_cost {
@@ -1278,7 +1768,7 @@ where
         }
     }
 
-    generalized_distance(s1, len1, s2, len2, weights, score_cutoff)
+    generalized_distance(s1, len1, s2, len2, weights, score_cutoff, score_hint)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1327,72 +1817,792 @@ where
         }
     }
 
-    generalized_distance(s1, len1, s2, len2, weights, score_cutoff)
+    generalized_distance(s1, len1, s2, len2, weights, score_cutoff, score_hint)
 }
 
-struct IndividualComparator {
-    weights: WeightTable,
-}
+/// minimum distance between `s1` and any prefix of `s2` for arbitrary weights
+fn generalized_prefix_distance<Iter1, Iter2>(
+    s1: Iter1,
+    len1: usize,
+    s2: Iter2,
+    weights: &WeightTable,
+    score_cutoff: usize,
+) -> usize
+where
+    Iter1: Iterator + Clone,
+    Iter2: Iterator,
+    Iter1::Item: PartialEq<Iter2::Item>,
+    Iter2::Item: Copy,
+{
+    let mut cache: Vec<usize> = (0..=len1).map(|x| x * weights.deletion_cost).collect();
+    let mut best = cache[len1];
 
-impl MetricUsize for IndividualComparator {
-    fn maximum(&self, len1: usize, len2: usize) -> usize {
-        _maximum(len1, len2, &self.weights)
+    for ch2 in s2 {
+        let mut diag = cache[0];
+        cache[0] += weights.insertion_cost;
+        let mut row_min = cache[0];
+        for (i, ch1) in s1.clone().enumerate() {
+            let above = cache[i + 1];
+            let mut temp = if ch1 == ch2 {
+                diag
+            } else {
+                diag + weights.substitution_cost
+            };
+            temp = min(temp, cache[i] + weights.deletion_cost);
+            temp = min(temp, above + weights.insertion_cost);
+            diag = above;
+            cache[i + 1] = temp;
+            row_min = min(row_min, temp);
+        }
+        best = min(best, cache[len1]);
+
+        // the distance to longer prefixes can not drop below the minimum of this row
+        if row_min > min(best, score_cutoff) {
+            break;
+        }
     }
 
-    fn _distance<Iter1, Iter2>(
-        &self,
-        s1: Iter1,
-        len1: usize,
-        s2: Iter2,
-        len2: usize,
-        score_cutoff: Option<usize>,
-        score_hint: Option<usize>,
-    ) -> usize
-    where
-        Iter1: DoubleEndedIterator + Clone,
-        Iter2: DoubleEndedIterator + Clone,
-        Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
-        Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
-    {
-        _distance_without_pm(
-            s1,
-            len1,
-            s2,
-            len2,
-            &self.weights,
-            score_cutoff.unwrap_or(usize::MAX),
-            score_hint.unwrap_or(usize::MAX),
-        )
+    if best <= score_cutoff {
+        best
+    } else {
+        usize::MAX
     }
 }
 
-/// Levenshtein distance
-///
-/// Calculates the Levenshtein distance.
-///
-/// # Examples
-///
-/// ```
-/// use rapidfuzz::distance::levenshtein;
-///
-/// assert_eq!(3, levenshtein::distance("CA".chars(), "ABC".chars()));
-/// ```
-pub fn distance<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> usize
-where
-    Iter1: IntoIterator,
-    Iter1::IntoIter: DoubleEndedIterator + Clone,
-    Iter2: IntoIterator,
-    Iter2::IntoIter: DoubleEndedIterator + Clone,
+/// minimum uniform distance between the sequence stored in `pm` and any prefix of `s2`
+fn uniform_prefix_distance<Iter1, Iter2>(
+    pm: &BlockPatternMatchVector,
+    s1: Iter1,
+    len1: usize,
+    s2: Iter2,
+    len2: usize,
+    mut score_cutoff: usize,
+    mut score_hint: usize,
+) -> usize
+where
+    Iter1: Iterator + Clone,
+    Iter2: Iterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    // upper bound
+    score_cutoff = min(score_cutoff, len1);
+    score_hint = max(score_hint, 31);
+
+    if len1 <= 64 {
+        let res: DistanceResult<0, 0> = hyrroe2003(pm, s1, len1, s2, len2, score_cutoff, true);
+        return res.dist;
+    }
+
+    while score_hint < score_cutoff {
+        let res: DistanceResult<0, 0> =
+            hyrroe2003_block(pm, s1.clone(), len1, s2.clone(), len2, score_hint, -1, true);
+        if res.dist <= score_hint {
+            return res.dist;
+        }
+
+        if usize::MAX / 2 < score_hint {
+            break;
+        }
+        score_hint *= 2;
+    }
+
+    let res: DistanceResult<0, 0> =
+        hyrroe2003_block(pm, s1, len1, s2, len2, score_cutoff, -1, true);
+    res.dist
+}
+
+/// minimum distance between `s1` and any prefix of `s2`. `pm` is reused when `s1`
+/// was already cached
+#[allow(clippy::too_many_arguments)]
+fn prefix_distance<Iter1, Iter2>(
+    pm: Option<&BlockPatternMatchVector>,
+    s1: Iter1,
+    len1: usize,
+    s2: Iter2,
+    len2: usize,
+    weights: &WeightTable,
+    score_cutoff: usize,
+    score_hint: usize,
+) -> usize
+where
+    Iter1: Iterator + Clone,
+    Iter2: Iterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    if len1 == 0 {
+        return 0;
+    }
+
+    /* uniform Levenshtein multiplied with the common factor */
+    if weights.insertion_cost == weights.deletion_cost
+        && weights.insertion_cost == weights.substitution_cost
+    {
+        /* when all operations are free there can not be any edit distance */
+        if weights.insertion_cost == 0 {
+            return 0;
+        }
+
+        // score_cutoff can make use of the common divisor of the three weights
+        let new_score_cutoff = ceil_div_usize(score_cutoff, weights.insertion_cost);
+        let new_score_hint = ceil_div_usize(score_hint, weights.insertion_cost);
+        let dist = match pm {
+            Some(pm) => {
+                uniform_prefix_distance(pm, s1, len1, s2, len2, new_score_cutoff, new_score_hint)
+            }
+            None if len1 <= 64 => {
+                let mut pm = PatternMatchVector::default();
+                pm.insert(s1.clone());
+                let res: DistanceResult<0, 0> =
+                    hyrroe2003(&pm, s1, len1, s2, len2, min(new_score_cutoff, len1), true);
+                res.dist
+            }
+            None => {
+                let mut pm = BlockPatternMatchVector::new(len1);
+                pm.insert(s1.clone());
+                uniform_prefix_distance(&pm, s1, len1, s2, len2, new_score_cutoff, new_score_hint)
+            }
+        };
+        return dist.saturating_mul(weights.insertion_cost);
+    }
+
+    generalized_prefix_distance(s1, len1, s2, weights, score_cutoff)
+}
+
+/// [`CostModel`] selected in the [`Args`] together with the maximum distance of the
+/// compared sequences, which depends on their elements
+#[derive(Clone, Copy)]
+struct CostModelState<'a> {
+    model: &'a dyn CostModel,
+    maximum: usize,
+}
+
+impl<'a> CostModelState<'a> {
+    fn new<Iter1, Iter2>(
+        model: Option<&'a dyn CostModel>,
+        s1: Iter1,
+        s2: Iter2,
+        prefix_mode: bool,
+    ) -> Option<Self>
+    where
+        Iter1: Iterator,
+        Iter2: Iterator,
+        Iter1::Item: HashableChar,
+        Iter2::Item: HashableChar,
+    {
+        let model = model?;
+        let mut s1 = s1.map(|ch| ch.hash_char());
+        let mut s2 = s2.map(|ch| ch.hash_char());
+        // in prefix mode `s1` can always be compared with the empty prefix of `s2`
+        if prefix_mode {
+            let maximum = s1.map(|ch| model.deletion_cost(ch)).sum();
+            return Some(Self { model, maximum });
+        }
+
+        // similar to `_maximum` either all elements are deleted and inserted, or the
+        // elements at the same position are replaced and only the rest is deleted/inserted.
+        // Unlike `_maximum` equal elements at the same position are kept for free
+        let mut indel = 0;
+        let mut replace = 0;
+        loop {
+            match (s1.next(), s2.next()) {
+                (Some(ch1), Some(ch2)) => {
+                    indel += model.deletion_cost(ch1) + model.insertion_cost(ch2);
+                    if ch1 != ch2 {
+                        replace += model.substitution_cost(ch1, ch2);
+                    }
+                }
+                (Some(ch1), None) => {
+                    let cost = model.deletion_cost(ch1);
+                    indel += cost;
+                    replace += cost;
+                }
+                (None, Some(ch2)) => {
+                    let cost = model.insertion_cost(ch2);
+                    indel += cost;
+                    replace += cost;
+                }
+                (None, None) => break,
+            }
+        }
+        Some(Self {
+            model,
+            maximum: min(indel, replace),
+        })
+    }
+}
+
+/// Wagner-Fischer algorithm using the costs of a [`CostModel`]. In `prefix_mode` the
+/// minimum distance between `s1` and any prefix of `s2` is calculated
+fn cost_model_distance<Iter1, Iter2>(
+    model: &dyn CostModel,
+    s1: Iter1,
+    s2: Iter2,
+    prefix_mode: bool,
+    score_cutoff: usize,
+) -> usize
+where
+    Iter1: Iterator,
+    Iter2: Iterator,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: HashableChar + Copy,
+{
+    let s1: Vec<(Iter1::Item, Hash, usize)> = s1
+        .map(|ch| {
+            let hash = ch.hash_char();
+            (ch, hash, model.deletion_cost(hash))
+        })
+        .collect();
+    let len1 = s1.len();
+
+    let mut cache: Vec<usize> = Vec::with_capacity(len1 + 1);
+    let mut dist = 0;
+    cache.push(dist);
+    for &(_, _, deletion_cost) in &s1 {
+        dist += deletion_cost;
+        cache.push(dist);
+    }
+    let mut best = if prefix_mode { cache[len1] } else { usize::MAX };
+
+    for ch2 in s2 {
+        let hash2 = ch2.hash_char();
+        let insertion_cost = model.insertion_cost(hash2);
+        let mut diag = cache[0];
+        cache[0] += insertion_cost;
+        let mut row_min = cache[0];
+
+        for (i, &(ch1, hash1, deletion_cost)) in s1.iter().enumerate() {
+            let substitution_cost = if ch1 == ch2 {
+                0
+            } else {
+                model.substitution_cost(hash1, hash2)
+            };
+            let dist = min(
+                diag + substitution_cost,
+                min(cache[i] + deletion_cost, cache[i + 1] + insertion_cost),
+            );
+            diag = cache[i + 1];
+            cache[i + 1] = dist;
+            row_min = min(row_min, dist);
+        }
+
+        if prefix_mode {
+            best = min(best, cache[len1]);
+        }
+        // costs are never negative, so the distance can't drop below the row minimum
+        if min(row_min, best) > score_cutoff {
+            return score_cutoff + 1;
+        }
+    }
+
+    if prefix_mode {
+        best
+    } else {
+        cache[len1]
+    }
+}
+
+/// recovers the edit operations from the bit matrices recorded while calculating
+/// the distance. Operations are written to `editops[editop_pos..editop_pos + dist]`
+#[allow(clippy::too_many_arguments)]
+fn recover_alignment<Elem1, Elem2>(
+    editops: &mut [Editop],
+    s1: &[Elem1],
+    s2: &[Elem2],
+    matrix: &ResultMatrix,
+    mut dist: usize,
+    src_pos: usize,
+    dest_pos: usize,
+    editop_pos: usize,
+) where
+    Elem1: PartialEq<Elem2>,
+{
+    let mut col = s1.len();
+    let mut row = s2.len();
+
+    let mut push = |dist: usize, tag: EditType, col: usize, row: usize| {
+        editops[editop_pos + dist] = Editop {
+            tag,
+            src_pos: col + src_pos,
+            dest_pos: row + dest_pos,
+        };
+    };
+
+    while row != 0 && col != 0 {
+        // Deletion
+        if matrix.vp.test_bit(row - 1, col - 1, false) {
+            debug_assert!(dist > 0);
+            dist -= 1;
+            col -= 1;
+            push(dist, EditType::Delete, col, row);
+        } else {
+            row -= 1;
+
+            // Insertion
+            if row != 0 && matrix.vn.test_bit(row - 1, col - 1, false) {
+                debug_assert!(dist > 0);
+                dist -= 1;
+                push(dist, EditType::Insert, col, row);
+            }
+            // Match/Mismatch
+            else {
+                col -= 1;
+
+                // Replace (Matches are not recorded)
+                if s1[col] != s2[row] {
+                    debug_assert!(dist > 0);
+                    dist -= 1;
+                    push(dist, EditType::Replace, col, row);
+                }
+            }
+        }
+    }
+
+    while col != 0 {
+        dist -= 1;
+        col -= 1;
+        push(dist, EditType::Delete, col, row);
+    }
+
+    while row != 0 {
+        dist -= 1;
+        row -= 1;
+        push(dist, EditType::Insert, col, row);
+    }
+}
+
+/// calculates the edit operations required to transform `s1` into `s2`, which are
+/// written to `editops[editop_pos..]`. `max` has to be at least the Levenshtein distance
+/// between the two sequences. `pm` can be passed when it was already created for `s1`.
+#[allow(clippy::too_many_arguments)]
+fn levenshtein_align<Elem1, Elem2>(
+    editops: &mut [Editop],
+    pm: Option<&BlockPatternMatchVector>,
+    s1: &[Elem1],
+    s2: &[Elem2],
+    max: usize,
+    mut src_pos: usize,
+    mut dest_pos: usize,
+    editop_pos: usize,
+) where
+    Elem1: PartialEq<Elem2> + HashableChar + Copy,
+    Elem2: PartialEq<Elem1> + HashableChar + Copy,
+{
+    // prefix and suffix are no-ops, which do not need to be added to the editops
+    let prefix_len = find_common_prefix(s1.iter(), s2.iter());
+    let s1 = &s1[prefix_len..];
+    let s2 = &s2[prefix_len..];
+    let suffix_len = find_common_suffix(s1.iter(), s2.iter());
+    let s1 = &s1[..s1.len() - suffix_len];
+    let s2 = &s2[..s2.len() - suffix_len];
+    src_pos += prefix_len;
+    dest_pos += prefix_len;
+    // the cached pattern match vector only describes the unmodified `s1`
+    let pm = pm.filter(|_| prefix_len == 0 && suffix_len == 0);
+
+    let len1 = s1.len();
+    let len2 = s2.len();
+    let full_band = min(len1, 2 * max + 1);
+
+    let res: DistanceResult<1, 0> = if len1 == 0 || len2 == 0 {
+        DistanceResult {
+            dist: len1 + len2,
+            ..Default::default()
+        }
+    } else if len1 <= 64 {
+        let s1_iter = s1.iter().copied();
+        let s2_iter = s2.iter().copied();
+        if let Some(pm) = pm {
+            hyrroe2003(pm, s1_iter, len1, s2_iter, len2, max, false)
+        } else {
+            let mut pm = PatternMatchVector::default();
+            pm.insert(s1.iter().copied());
+            hyrroe2003(&pm, s1_iter, len1, s2_iter, len2, max, false)
+        }
+    } else if full_band <= 64 {
+        hyrroe2003_small_band_without_pm(s1.iter().copied(), len1, s2.iter().copied(), len2, max)
+    } else {
+        let owned_pm;
+        let pm = if let Some(pm) = pm {
+            pm
+        } else {
+            let mut new_pm = BlockPatternMatchVector::new(len1);
+            new_pm.insert(s1.iter().copied());
+            owned_pm = new_pm;
+            &owned_pm
+        };
+        hyrroe2003_block(
+            pm,
+            s1.iter().copied(),
+            len1,
+            s2.iter().copied(),
+            len2,
+            max,
+            -1,
+            false,
+        )
+    };
+
+    debug_assert!(res.dist != usize::MAX);
+    if res.dist != 0 {
+        recover_alignment(
+            editops,
+            s1,
+            s2,
+            &res.record_matrix[0],
+            res.dist,
+            src_pos,
+            dest_pos,
+            editop_pos,
+        );
+    }
+}
+
+struct HirschbergPos {
+    left_score: usize,
+    right_score: usize,
+    s1_mid: usize,
+    s2_mid: usize,
+}
+
+/// calculates the Levenshtein row at `stop_row` inside the Ukkonen band. `pm` can be
+/// passed when it was already created for `s1`.
+fn levenshtein_row<Iter1, Iter2>(
+    pm: Option<&BlockPatternMatchVector>,
+    s1: Iter1,
+    len1: usize,
+    s2: Iter2,
+    len2: usize,
+    max: usize,
+    stop_row: usize,
+) -> DistanceResult<0, 1>
+where
+    Iter1: Iterator + Clone,
+    Iter2: Iterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    if let Some(pm) = pm {
+        return hyrroe2003_block(pm, s1, len1, s2, len2, max, stop_row as isize, false);
+    }
+    let mut pm = BlockPatternMatchVector::new(len1);
+    pm.insert(s1.clone());
+    hyrroe2003_block(&pm, s1, len1, s2, len2, max, stop_row as isize, false)
+}
+
+/// scores of the cells in the recorded row, starting at the first column of the band
+fn row_scores(row: &ResultRow, len1: usize) -> (usize, Vec<usize>) {
+    let first_pos = row.first_block * 64;
+    let last_pos = min(len1, row.last_block * 64 + 64);
+
+    let mut scores = Vec::with_capacity(last_pos - first_pos + 1);
+    let mut score = row.prev_score;
+    scores.push(score);
+    for i in first_pos..last_pos {
+        let mask = 1_u64 << (i % 64);
+        let vecs = &row.vecs[i / 64];
+        score -= usize::from(vecs.vn & mask != 0);
+        score += usize::from(vecs.vp & mask != 0);
+        scores.push(score);
+    }
+    (first_pos, scores)
+}
+
+/// finds the position where an optimal alignment crosses the middle row of `s2`.
+/// Returns `None` when the alignment requires more than `max` operations.
+fn try_find_hirschberg_pos<Elem1, Elem2>(
+    pm: Option<&BlockPatternMatchVector>,
+    s1: &[Elem1],
+    s2: &[Elem2],
+    max: usize,
+) -> Option<HirschbergPos>
+where
+    Elem1: PartialEq<Elem2> + HashableChar + Copy,
+    Elem2: PartialEq<Elem1> + HashableChar + Copy,
+{
+    let len1 = s1.len();
+    let len2 = s2.len();
+    let left_size = len2 / 2;
+    let right_size = len2 - left_size;
+
+    let right_row = levenshtein_row(
+        None,
+        s1.iter().rev().copied(),
+        len1,
+        s2.iter().rev().copied(),
+        len2,
+        max,
+        right_size - 1,
+    );
+    if right_row.dist > max {
+        return None;
+    }
+    let (right_first_pos, right_scores) = row_scores(&right_row.bit_row[0], len1);
+
+    let left_row = levenshtein_row(
+        pm,
+        s1.iter().copied(),
+        len1,
+        s2.iter().copied(),
+        len2,
+        max,
+        left_size - 1,
+    );
+    if left_row.dist > max {
+        return None;
+    }
+    let (left_first_pos, left_scores) = row_scores(&left_row.bit_row[0], len1);
+
+    let mut best_score = usize::MAX;
+    let mut hpos = HirschbergPos {
+        left_score: 0,
+        right_score: 0,
+        s1_mid: 0,
+        s2_mid: left_size,
+    };
+    for (s1_mid, &left_score) in (left_first_pos..).zip(&left_scores) {
+        // the right half aligns the reversed prefix of length `len1 - s1_mid`
+        let Some(&right_score) = (len1 - s1_mid)
+            .checked_sub(right_first_pos)
+            .and_then(|pos| right_scores.get(pos))
+        else {
+            continue;
+        };
+
+        if left_score + right_score < best_score {
+            best_score = left_score + right_score;
+            hpos.left_score = left_score;
+            hpos.right_score = right_score;
+            hpos.s1_mid = s1_mid;
+        }
+    }
+
+    if best_score > max {
+        return None;
+    }
+    Some(hpos)
+}
+
+fn find_hirschberg_pos<Elem1, Elem2>(
+    pm: Option<&BlockPatternMatchVector>,
+    s1: &[Elem1],
+    s2: &[Elem2],
+    mut max: usize,
+) -> HirschbergPos
+where
+    Elem1: PartialEq<Elem2> + HashableChar + Copy,
+    Elem2: PartialEq<Elem1> + HashableChar + Copy,
+{
+    loop {
+        if let Some(hpos) = try_find_hirschberg_pos(pm, s1, s2, max) {
+            return hpos;
+        }
+        max = max.saturating_mul(2);
+    }
+}
+
+/// calculates the edit operations using Hirschberg's divide and conquer approach
+/// when recording the full bit matrix would require too much memory.
+/// This keeps the memory usage linear in the length of the sequences. `pm` can be passed
+/// when it was already created for `s1`.
+#[allow(clippy::too_many_arguments)]
+fn levenshtein_align_hirschberg<Elem1, Elem2>(
+    editops: &mut [Editop],
+    pm: Option<&BlockPatternMatchVector>,
+    s1: &[Elem1],
+    s2: &[Elem2],
+    mut src_pos: usize,
+    mut dest_pos: usize,
+    editop_pos: usize,
+    max: usize,
+) where
+    Elem1: PartialEq<Elem2> + HashableChar + Copy,
+    Elem2: PartialEq<Elem1> + HashableChar + Copy,
+{
+    // prefix and suffix are no-ops, which do not need to be added to the editops
+    let prefix_len = find_common_prefix(s1.iter(), s2.iter());
+    let s1 = &s1[prefix_len..];
+    let s2 = &s2[prefix_len..];
+    let suffix_len = find_common_suffix(s1.iter(), s2.iter());
+    let s1 = &s1[..s1.len() - suffix_len];
+    let s2 = &s2[..s2.len() - suffix_len];
+    src_pos += prefix_len;
+    dest_pos += prefix_len;
+    let pm = pm.filter(|_| prefix_len == 0 && suffix_len == 0);
+
+    let len1 = s1.len();
+    let len2 = s2.len();
+    let max = min(max, len1.max(len2));
+    let full_band = min(len1, 2 * max + 1);
+
+    // size of the vp and vn matrices recorded by levenshtein_align in bytes
+    let matrix_size = 2 * full_band * len2 / 8;
+    if matrix_size < 1024 * 1024 || len1 < 65 || len2 < 10 {
+        levenshtein_align(editops, pm, s1, s2, max, src_pos, dest_pos, editop_pos);
+    } else {
+        let hpos = find_hirschberg_pos(pm, s1, s2, max);
+
+        levenshtein_align_hirschberg(
+            editops,
+            None,
+            &s1[..hpos.s1_mid],
+            &s2[..hpos.s2_mid],
+            src_pos,
+            dest_pos,
+            editop_pos,
+            hpos.left_score,
+        );
+        levenshtein_align_hirschberg(
+            editops,
+            None,
+            &s1[hpos.s1_mid..],
+            &s2[hpos.s2_mid..],
+            src_pos + hpos.s1_mid,
+            dest_pos + hpos.s2_mid,
+            editop_pos + hpos.left_score,
+            hpos.right_score,
+        );
+    }
+}
+
+/// `pm` can be passed when it was already created for `s1`, e.g. by a [`BatchComparator`]
+fn editops_impl<Elem1, Elem2>(
+    pm: Option<&BlockPatternMatchVector>,
+    s1: &[Elem1],
+    s2: &[Elem2],
+) -> Editops
+where
+    Elem1: PartialEq<Elem2> + HashableChar + Copy,
+    Elem2: PartialEq<Elem1> + HashableChar + Copy,
+{
+    // calculating the distance first allows the alignment to only record the band of
+    // the matrix that can be part of an optimal alignment
+    let dist = match pm {
+        Some(pm) => uniform_distance_with_pm(
+            pm,
+            s1.iter().copied(),
+            s1.len(),
+            s2.iter().copied(),
+            s2.len(),
+            usize::MAX,
+            usize::MAX,
+        ),
+        None => uniform_distance_without_pm(
+            s1.iter().copied(),
+            s1.len(),
+            s2.iter().copied(),
+            s2.len(),
+            usize::MAX,
+            usize::MAX,
+        ),
+    };
+
+    let mut ops = vec![
+        Editop {
+            tag: EditType::Delete,
+            src_pos: 0,
+            dest_pos: 0,
+        };
+        dist
+    ];
+    levenshtein_align_hirschberg(&mut ops, pm, s1, s2, 0, 0, 0, dist);
+    Editops::new(ops, s1.len(), s2.len())
+}
+
+struct IndividualComparator<'a> {
+    weights: WeightTable,
+    prefix_mode: bool,
+    cost_model: Option<CostModelState<'a>>,
+}
+
+impl MetricUsize for IndividualComparator<'_> {
+    fn maximum(&self, len1: usize, len2: usize) -> usize {
+        if let Some(cost_model) = self.cost_model {
+            return cost_model.maximum;
+        }
+        if self.prefix_mode {
+            return len1 * self.weights.deletion_cost;
+        }
+        _maximum(len1, len2, &self.weights)
+    }
+
+    fn _distance<Iter1, Iter2>(
+        &self,
+        s1: Iter1,
+        len1: usize,
+        s2: Iter2,
+        len2: usize,
+        score_cutoff: Option<usize>,
+        score_hint: Option<usize>,
+    ) -> usize
+    where
+        Iter1: DoubleEndedIterator + Clone,
+        Iter2: DoubleEndedIterator + Clone,
+        Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    {
+        if let Some(cost_model) = self.cost_model {
+            return cost_model_distance(
+                cost_model.model,
+                s1,
+                s2,
+                self.prefix_mode,
+                score_cutoff.unwrap_or(usize::MAX),
+            );
+        }
+        if self.prefix_mode {
+            return prefix_distance(
+                None,
+                s1,
+                len1,
+                s2,
+                len2,
+                &self.weights,
+                score_cutoff.unwrap_or(usize::MAX),
+                score_hint.unwrap_or(usize::MAX),
+            );
+        }
+
+        _distance_without_pm(
+            s1,
+            len1,
+            s2,
+            len2,
+            &self.weights,
+            score_cutoff.unwrap_or(usize::MAX),
+            score_hint.unwrap_or(usize::MAX),
+        )
+    }
+}
+
+/// Levenshtein distance
+///
+/// Calculates the Levenshtein distance.
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::levenshtein;
+///
+/// assert_eq!(3, levenshtein::distance("CA".chars(), "ABC".chars()));
+/// ```
+pub fn distance<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> usize
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
 {
     distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn distance_with_args<Iter1, Iter2, CutoffType>(
+pub fn distance_with_args<Iter1, Iter2, CutoffType, ProcessorType, CostModelType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<usize, CutoffType>,
+    args: &Args<usize, CutoffType, ProcessorType, CostModelType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -1402,12 +2612,21 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<usize>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+    CostModelType: OptionalCostModel,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff.score(
         IndividualComparator {
             weights: args.weights,
+            prefix_mode: args.prefix_mode,
+            cost_model: CostModelState::new(
+                args.cost_model.cost_model(),
+                s1_iter.clone(),
+                s2_iter.clone(),
+                args.prefix_mode,
+            ),
         }
         ._distance(
             s1_iter.clone(),
@@ -1448,10 +2667,10 @@ where
     similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn similarity_with_args<Iter1, Iter2, CutoffType>(
+pub fn similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType, CostModelType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<usize, CutoffType>,
+    args: &Args<usize, CutoffType, ProcessorType, CostModelType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -1461,12 +2680,21 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<usize>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+    CostModelType: OptionalCostModel,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff.score(
         IndividualComparator {
             weights: args.weights,
+            prefix_mode: args.prefix_mode,
+            cost_model: CostModelState::new(
+                args.cost_model.cost_model(),
+                s1_iter.clone(),
+                s2_iter.clone(),
+                args.prefix_mode,
+            ),
         }
         ._similarity(
             s1_iter.clone(),
@@ -1506,10 +2734,10 @@ where
     normalized_distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType>(
+pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType, ProcessorType, CostModelType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType, CostModelType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -1519,12 +2747,21 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+    CostModelType: OptionalCostModel,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff.score(
         IndividualComparator {
             weights: args.weights,
+            prefix_mode: args.prefix_mode,
+            cost_model: CostModelState::new(
+                args.cost_model.cost_model(),
+                s1_iter.clone(),
+                s2_iter.clone(),
+                args.prefix_mode,
+            ),
         }
         ._normalized_distance(
             s1_iter.clone(),
@@ -1553,10 +2790,10 @@ where
     normalized_similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType>(
+pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType, CostModelType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType, CostModelType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -1566,12 +2803,21 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+    CostModelType: OptionalCostModel,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
     args.score_cutoff.score(
         IndividualComparator {
             weights: args.weights,
+            prefix_mode: args.prefix_mode,
+            cost_model: CostModelState::new(
+                args.cost_model.cost_model(),
+                s1_iter.clone(),
+                s2_iter.clone(),
+                args.prefix_mode,
+            ),
         }
         ._normalized_similarity(
             s1_iter.clone(),
@@ -1584,13 +2830,439 @@ where
     )
 }
 
+/// Returns the edit operations required to transform `s1` into `s2`
+///
+/// The operations are based on the uniform Levenshtein distance, so the number of
+/// operations is equal to [`distance`]. When multiple optimal alignments exist,
+/// any of them can be returned.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::distance::levenshtein;
+/// use rapidfuzz::distance::{EditType, Editop};
+///
+/// let ops = levenshtein::editops("qabxcd".chars(), "abycdf".chars());
+/// assert_eq!(
+///     &[
+///         Editop { tag: EditType::Delete, src_pos: 0, dest_pos: 0 },
+///         Editop { tag: EditType::Replace, src_pos: 3, dest_pos: 2 },
+///         Editop { tag: EditType::Insert, src_pos: 6, dest_pos: 5 },
+///     ],
+///     &*ops
+/// );
+/// ```
+pub fn editops<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> Editops
+where
+    Iter1: IntoIterator,
+    Iter2: IntoIterator,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    let s1: Vec<Iter1::Item> = s1.into_iter().collect();
+    let s2: Vec<Iter2::Item> = s2.into_iter().collect();
+    editops_impl(None, &s1, &s2)
+}
+
+/// Returns the blocks of operations required to transform `s1` into `s2`
+///
+/// This is equal to [`editops`] converted with [`Editops::as_opcodes`], so the matching
+/// parts of the sequences are included as well.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::distance::levenshtein;
+/// use rapidfuzz::distance::{Opcode, OpcodeType};
+///
+/// let ops = levenshtein::opcodes("qabxcd".chars(), "abycdf".chars());
+/// assert_eq!(
+///     Opcode { tag: OpcodeType::Delete, src_start: 0, src_end: 1, dest_start: 0, dest_end: 0 },
+///     ops[0]
+/// );
+/// assert_eq!(
+///     Opcode { tag: OpcodeType::Equal, src_start: 1, src_end: 3, dest_start: 0, dest_end: 2 },
+///     ops[1]
+/// );
+/// ```
+///
+/// [`Editops::as_opcodes`]: crate::distance::Editops::as_opcodes
+pub fn opcodes<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> Opcodes
+where
+    Iter1: IntoIterator,
+    Iter2: IntoIterator,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    editops(s1, s2).as_opcodes()
+}
+
+/// Advances the bit-parallel Levenshtein matrix of the pattern in `pm` by one text element
+///
+/// `hp_in` is the horizontal delta shifted into the first row. It is `true` for a global
+/// alignment and `false` when leading text is free. Returns the horizontal delta of
+/// the last row as `(hp, hn)`.
+fn myers_step<CharT>(
+    pm: &BlockPatternMatchVector,
+    vecs: &mut [LevenshteinRow],
+    last: u64,
+    ch: CharT,
+    hp_in: bool,
+) -> (bool, bool)
+where
+    CharT: HashableChar + Copy,
+{
+    let words = vecs.len();
+    let mut hp_carry = hp_in;
+    let mut hn_carry = false;
+    for (word, row) in vecs.iter_mut().enumerate() {
+        let x = pm.get(word, ch) | u64::from(hn_carry);
+        let d0 = ((x & row.vp).wrapping_add(row.vp) ^ row.vp) | x | row.vn;
+
+        let mut hp = row.vn | !(d0 | row.vp);
+        let mut hn = d0 & row.vp;
+
+        let hp_carry_temp = hp_carry;
+        let hn_carry_temp = hn_carry;
+        if word < words - 1 {
+            hp_carry = (hp >> 63) != 0;
+            hn_carry = (hn >> 63) != 0;
+        } else {
+            hp_carry = (hp & last) != 0;
+            hn_carry = (hn & last) != 0;
+        }
+
+        hp = (hp << 1) | u64::from(hp_carry_temp);
+        hn = (hn << 1) | u64::from(hn_carry_temp);
+
+        row.vp = hn | !(d0 | hp);
+        row.vn = hp & d0;
+    }
+    (hp_carry, hn_carry)
+}
+
+/// Approximate occurrence of a pattern in a text found by [`find_iter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    /// start of the occurrence in the text. Only calculated when enabled using
+    /// [`FindIter::with_start`]
+    pub start: Option<usize>,
+    /// end of the occurrence in the text (exclusive)
+    pub end: usize,
+    /// Levenshtein distance between the pattern and the occurrence
+    pub distance: usize,
+}
+
+struct StartSearch<Elem> {
+    /// pattern in reverse order
+    pm: BlockPatternMatchVector,
+    /// the last `len(pattern) + max_distance` elements of the text
+    window: VecDeque<Elem>,
+}
+
+/// Iterator over the approximate occurrences of a pattern in a text, created by [`find_iter`]
+pub struct FindIter<Iter2, Elem1>
+where
+    Iter2: Iterator,
+{
+    pattern: Vec<Elem1>,
+    pm: BlockPatternMatchVector,
+    vecs: Vec<LevenshteinRow>,
+    last: u64,
+    text: Iter2,
+    pos: usize,
+    score: usize,
+    max_distance: usize,
+    started: bool,
+    start_search: Option<StartSearch<Iter2::Item>>,
+}
+
+impl<Iter2, Elem1> FindIter<Iter2, Elem1>
+where
+    Iter2: Iterator,
+    Iter2::Item: HashableChar + Copy,
+    Elem1: HashableChar + Copy,
+{
+    /// additionally calculate the start of each occurrence
+    ///
+    /// When multiple starts lead to the same distance, the shortest occurrence is used.
+    /// This has to be called before the first match is requested.
+    #[must_use]
+    pub fn with_start(mut self) -> Self {
+        debug_assert!(!self.started);
+        let mut pm = BlockPatternMatchVector::new(self.pattern.len());
+        pm.insert(self.pattern.iter().rev().copied());
+        self.start_search = Some(StartSearch {
+            pm,
+            window: VecDeque::with_capacity(self.pattern.len() + self.max_distance + 1),
+        });
+        self
+    }
+
+    fn find_start(&self, distance: usize) -> Option<usize> {
+        let search = self.start_search.as_ref()?;
+        let len1 = self.pattern.len();
+        // aligns the reversed pattern with the text in front of the end
+        let mut vecs = vec![LevenshteinRow::default(); search.pm.size()];
+        let mut score = len1;
+        let mut len = 0;
+        for &ch in search.window.iter().rev() {
+            if score <= distance {
+                break;
+            }
+            let (hp, hn) = myers_step(&search.pm, &mut vecs, self.last, ch, true);
+            score = score + usize::from(hp) - usize::from(hn);
+            len += 1;
+        }
+        Some(self.pos - len)
+    }
+}
+
+impl<Iter2, Elem1> Iterator for FindIter<Iter2, Elem1>
+where
+    Iter2: Iterator,
+    Iter2::Item: HashableChar + Copy,
+    Elem1: HashableChar + Copy,
+{
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.started {
+                let ch = self.text.next()?;
+                self.pos += 1;
+                if !self.vecs.is_empty() {
+                    let (hp, hn) = myers_step(&self.pm, &mut self.vecs, self.last, ch, false);
+                    self.score = self.score + usize::from(hp) - usize::from(hn);
+                }
+
+                if let Some(search) = &mut self.start_search {
+                    if search.window.len() == self.pattern.len() + self.max_distance {
+                        search.window.pop_front();
+                    }
+                    search.window.push_back(ch);
+                }
+            }
+            self.started = true;
+
+            if self.score <= self.max_distance {
+                return Some(Match {
+                    start: self.find_start(self.score),
+                    end: self.pos,
+                    distance: self.score,
+                });
+            }
+        }
+    }
+}
+
+/// Finds all approximate occurrences of `pattern` in `text` with a Levenshtein distance
+/// of at most `max_distance`
+///
+/// Leading and trailing parts of the text are free, so the distance is calculated between
+/// the pattern and the part of the text it is aligned with. A match is reported for every
+/// end position within `max_distance`, so a single occurrence can produce multiple matches
+/// with neighbouring end positions.
+///
+/// The implementation has a runtime complexity of `O([N/64]*M)`, where `N` is the length of
+/// the pattern and `M` the length of the text.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::distance::levenshtein;
+///
+/// let matches: Vec<(usize, usize)> = levenshtein::find_iter("fuzy".chars(), "a fuzzy search".chars(), 1)
+///     .map(|m| (m.end, m.distance))
+///     .collect();
+/// assert_eq!(vec![(5, 1), (6, 1), (7, 1)], matches);
+///
+/// let best = levenshtein::find_iter("fuzy".chars(), "a fuzzy search".chars(), 1)
+///     .with_start()
+///     .next()
+///     .unwrap();
+/// assert_eq!((Some(2), 5), (best.start, best.end));
+/// ```
+pub fn find_iter<Iter1, Iter2>(
+    pattern: Iter1,
+    text: Iter2,
+    max_distance: usize,
+) -> FindIter<Iter2::IntoIter, Iter1::Item>
+where
+    Iter1: IntoIterator,
+    Iter2: IntoIterator,
+    Iter1::Item: HashableChar + Copy,
+    Iter2::Item: HashableChar + Copy,
+{
+    let pattern: Vec<Iter1::Item> = pattern.into_iter().collect();
+    let mut pm = BlockPatternMatchVector::new(pattern.len());
+    pm.insert(pattern.iter().copied());
+    let last = if pattern.is_empty() {
+        0
+    } else {
+        1_u64 << ((pattern.len() - 1) % 64)
+    };
+
+    FindIter {
+        vecs: vec![LevenshteinRow::default(); pm.size()],
+        score: pattern.len(),
+        pattern,
+        pm,
+        last,
+        text: text.into_iter(),
+        pos: 0,
+        max_distance,
+        started: false,
+        start_search: None,
+    }
+}
+
+/// State of an [`Automaton`] after consuming a sequence
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AutomatonState<Elem> {
+    /// distance of each prefix of the query to the consumed sequence, capped at `max_distance + 1`
+    row: Vec<usize>,
+    /// row before consuming the last element, used for transpositions
+    prev_row: Vec<usize>,
+    prev_elem: Option<Elem>,
+}
+
+/// Levenshtein automaton accepting all sequences within `max_distance` of a query
+///
+/// The automaton is driven by the caller one element at a time, which allows matching
+/// against dictionaries stored in a trie, a sorted set or a finite state transducer:
+/// subtrees can be skipped as soon as [`Automaton::can_match`] returns `false`.
+/// With [`Automaton::transpositions`] a transposition of two adjacent elements is counted
+/// as a single edit, which mirrors the [`osa`](crate::distance::osa) distance.
+///
+/// Each step has a runtime complexity of `O(N)`, where `N` is the length of the query.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::distance::levenshtein::Automaton;
+///
+/// let dictionary = ["apple", "applet", "apply", "banana", "bandana"];
+/// let automaton = Automaton::new("aple".chars(), 1);
+///
+/// let mut matches = Vec::new();
+/// for word in dictionary {
+///     let mut state = automaton.start();
+///     for ch in word.chars() {
+///         state = automaton.step(&state, ch);
+///         if !automaton.can_match(&state) {
+///             break;
+///         }
+///     }
+///     if let Some(dist) = automaton.distance(&state) {
+///         matches.push((word, dist));
+///     }
+/// }
+/// assert_eq!(vec![("apple", 1)], matches);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Automaton<Elem> {
+    query: Vec<Elem>,
+    max_distance: usize,
+    transpositions: bool,
+}
+
+impl<Elem> Automaton<Elem>
+where
+    Elem: PartialEq + Copy,
+{
+    /// creates an automaton accepting sequences with a distance of at most `max_distance` to `query`
+    pub fn new<Iter>(query: Iter, max_distance: usize) -> Self
+    where
+        Iter: IntoIterator<Item = Elem>,
+    {
+        Self {
+            query: query.into_iter().collect(),
+            max_distance,
+            transpositions: false,
+        }
+    }
+
+    /// count transpositions of adjacent elements as a single edit
+    #[must_use]
+    pub fn transpositions(mut self, transpositions: bool) -> Self {
+        self.transpositions = transpositions;
+        self
+    }
+
+    /// state before consuming any element
+    pub fn start(&self) -> AutomatonState<Elem> {
+        let cap = self.max_distance + 1;
+        AutomatonState {
+            row: (0..=self.query.len()).map(|i| min(i, cap)).collect(),
+            prev_row: Vec::new(),
+            prev_elem: None,
+        }
+    }
+
+    /// state after consuming `elem` in `state`
+    pub fn step(&self, state: &AutomatonState<Elem>, elem: Elem) -> AutomatonState<Elem> {
+        let cap = self.max_distance + 1;
+        let mut row = Vec::with_capacity(state.row.len());
+        row.push(min(state.row[0] + 1, cap));
+
+        for (i, &query_elem) in self.query.iter().enumerate() {
+            let mut dist = min(
+                state.row[i] + usize::from(query_elem != elem),
+                min(state.row[i + 1], row[i]) + 1,
+            );
+
+            if self.transpositions && i > 0 && !state.prev_row.is_empty() {
+                if let Some(prev_elem) = state.prev_elem {
+                    if query_elem == prev_elem && self.query[i - 1] == elem {
+                        dist = min(dist, state.prev_row[i - 1] + 1);
+                    }
+                }
+            }
+            row.push(min(dist, cap));
+        }
+
+        AutomatonState {
+            prev_row: if self.transpositions {
+                state.row.clone()
+            } else {
+                Vec::new()
+            },
+            row,
+            prev_elem: Some(elem),
+        }
+    }
+
+    /// `true` if a sequence starting with the consumed elements can still be accepted
+    pub fn can_match(&self, state: &AutomatonState<Elem>) -> bool {
+        // the distance never decreases and transpositions are no cheaper than substitutions
+        state.row.iter().any(|&dist| dist <= self.max_distance)
+    }
+
+    /// `true` if the consumed sequence is accepted
+    pub fn is_match(&self, state: &AutomatonState<Elem>) -> bool {
+        self.distance(state).is_some()
+    }
+
+    /// distance between the query and the consumed sequence or `None` if it is larger
+    /// than `max_distance`
+    pub fn distance(&self, state: &AutomatonState<Elem>) -> Option<usize> {
+        let dist = state.row[self.query.len()];
+        (dist <= self.max_distance).then_some(dist)
+    }
+}
+
 struct BatchComparatorImpl<'a, Elem1> {
     cache: &'a BatchComparator<Elem1>,
     weights: &'a WeightTable,
+    prefix_mode: bool,
+    cost_model: Option<CostModelState<'a>>,
 }
 
 impl<CharT> MetricUsize for BatchComparatorImpl<'_, CharT> {
     fn maximum(&self, len1: usize, len2: usize) -> usize {
+        if let Some(cost_model) = self.cost_model {
+            return cost_model.maximum;
+        }
+        if self.prefix_mode {
+            return len1 * self.weights.deletion_cost;
+        }
         _maximum(len1, len2, self.weights)
     }
 
@@ -1609,6 +3281,28 @@ impl<CharT> MetricUsize for BatchComparatorImpl<'_, CharT> {
         Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
         Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     {
+        if let Some(cost_model) = self.cost_model {
+            return cost_model_distance(
+                cost_model.model,
+                s1,
+                s2,
+                self.prefix_mode,
+                score_cutoff.unwrap_or(usize::MAX),
+            );
+        }
+        if self.prefix_mode {
+            return prefix_distance(
+                Some(&self.cache.pm),
+                s1,
+                len1,
+                s2,
+                len2,
+                self.weights,
+                score_cutoff.unwrap_or(usize::MAX),
+                score_hint.unwrap_or(usize::MAX),
+            );
+        }
+
         _distance_with_pm(
             &self.cache.pm,
             s1,
@@ -1622,6 +3316,23 @@ impl<CharT> MetricUsize for BatchComparatorImpl<'_, CharT> {
     }
 }
 
+/// Marker type describing the Levenshtein distance, see [`Metric`](crate::distance::Metric)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Levenshtein;
+
+impl_metric!(
+    Levenshtein,
+    usize,
+    higher_is_better: false,
+    optimal_score: 0,
+    worst_score: usize::MAX,
+    comparator: IndividualComparator {
+        weights: WeightTable::default(),
+        prefix_mode: false,
+        cost_model: None,
+    },
+);
+
 /// `One x Many` comparisons using the Levenshtein distance
 ///
 /// # Examples
@@ -1667,10 +3378,10 @@ where
         self.normalized_distance_with_args(s2, &Args::default())
     }
 
-    pub fn normalized_distance_with_args<Iter2, CutoffType>(
+    pub fn normalized_distance_with_args<Iter2, CutoffType, CostModelType>(
         &self,
         s2: Iter2,
-        args: &Args<f64, CutoffType>,
+        args: &Args<f64, CutoffType, NoProcessor, CostModelType>,
     ) -> CutoffType::Output
     where
         Iter2: IntoIterator,
@@ -1678,12 +3389,20 @@ where
         Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
         Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
         CutoffType: DistanceCutoff<f64>,
+        CostModelType: OptionalCostModel,
     {
         let s2_iter = s2.into_iter();
         args.score_cutoff.score(
             BatchComparatorImpl {
                 cache: self,
                 weights: &args.weights,
+                prefix_mode: args.prefix_mode,
+                cost_model: CostModelState::new(
+                    args.cost_model.cost_model(),
+                    self.s1.iter().copied(),
+                    s2_iter.clone(),
+                    args.prefix_mode,
+                ),
             }
             ._normalized_distance(
                 self.s1.iter().copied(),
@@ -1707,10 +3426,10 @@ where
         self.normalized_similarity_with_args(s2, &Args::default())
     }
 
-    pub fn normalized_similarity_with_args<Iter2, CutoffType>(
+    pub fn normalized_similarity_with_args<Iter2, CutoffType, CostModelType>(
         &self,
         s2: Iter2,
-        args: &Args<f64, CutoffType>,
+        args: &Args<f64, CutoffType, NoProcessor, CostModelType>,
     ) -> CutoffType::Output
     where
         Iter2: IntoIterator,
@@ -1718,12 +3437,20 @@ where
         Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
         Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
         CutoffType: SimilarityCutoff<f64>,
+        CostModelType: OptionalCostModel,
     {
         let s2_iter = s2.into_iter();
         args.score_cutoff.score(
             BatchComparatorImpl {
                 cache: self,
                 weights: &args.weights,
+                prefix_mode: args.prefix_mode,
+                cost_model: CostModelState::new(
+                    args.cost_model.cost_model(),
+                    self.s1.iter().copied(),
+                    s2_iter.clone(),
+                    args.prefix_mode,
+                ),
             }
             ._normalized_similarity(
                 self.s1.iter().copied(),
@@ -1747,10 +3474,10 @@ where
         self.distance_with_args(s2, &Args::default())
     }
 
-    pub fn distance_with_args<Iter2, CutoffType>(
+    pub fn distance_with_args<Iter2, CutoffType, CostModelType>(
         &self,
         s2: Iter2,
-        args: &Args<usize, CutoffType>,
+        args: &Args<usize, CutoffType, NoProcessor, CostModelType>,
     ) -> CutoffType::Output
     where
         Iter2: IntoIterator,
@@ -1758,12 +3485,20 @@ where
         Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
         Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
         CutoffType: DistanceCutoff<usize>,
+        CostModelType: OptionalCostModel,
     {
         let s2_iter = s2.into_iter();
         args.score_cutoff.score(
             BatchComparatorImpl {
                 cache: self,
                 weights: &args.weights,
+                prefix_mode: args.prefix_mode,
+                cost_model: CostModelState::new(
+                    args.cost_model.cost_model(),
+                    self.s1.iter().copied(),
+                    s2_iter.clone(),
+                    args.prefix_mode,
+                ),
             }
             ._distance(
                 self.s1.iter().copied(),
@@ -1787,10 +3522,10 @@ where
         self.similarity_with_args(s2, &Args::default())
     }
 
-    pub fn similarity_with_args<Iter2, CutoffType>(
+    pub fn similarity_with_args<Iter2, CutoffType, CostModelType>(
         &self,
         s2: Iter2,
-        args: &Args<usize, CutoffType>,
+        args: &Args<usize, CutoffType, NoProcessor, CostModelType>,
     ) -> CutoffType::Output
     where
         Iter2: IntoIterator,
@@ -1798,12 +3533,20 @@ where
         Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
         Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
         CutoffType: SimilarityCutoff<usize>,
+        CostModelType: OptionalCostModel,
     {
         let s2_iter = s2.into_iter();
         args.score_cutoff.score(
             BatchComparatorImpl {
                 cache: self,
                 weights: &args.weights,
+                prefix_mode: args.prefix_mode,
+                cost_model: CostModelState::new(
+                    args.cost_model.cost_model(),
+                    self.s1.iter().copied(),
+                    s2_iter.clone(),
+                    args.prefix_mode,
+                ),
             }
             ._similarity(
                 self.s1.iter().copied(),
@@ -1815,12 +3558,198 @@ where
             ),
         )
     }
+
+    /// Edit operations calculated similar to [`editops`]
+    pub fn editops<Iter2>(&self, s2: Iter2) -> Editops
+    where
+        Iter2: IntoIterator,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        let s2: Vec<Iter2::Item> = s2.into_iter().collect();
+        editops_impl(Some(&self.pm), &self.s1, &s2)
+    }
+
+    /// Opcodes calculated similar to [`opcodes`]
+    pub fn opcodes<Iter2>(&self, s2: Iter2) -> Opcodes
+    where
+        Iter2: IntoIterator,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.editops(s2).as_opcodes()
+    }
+}
+
+impl_batch_metric!(BatchComparator, Levenshtein, args: Args::default());
+
+/// bit-parallel state of a single candidate of an [`IncrementalComparator`]
+#[derive(Clone)]
+struct IncrementalCandidate {
+    pm: BlockPatternMatchVector,
+    len: usize,
+    last: u64,
+    vecs: Vec<LevenshteinRow>,
+    dist: usize,
+}
+
+/// `Many x One` comparisons using the Levenshtein distance with a query that grows
+/// one element at a time
+///
+/// The comparator keeps the column state of the bit-parallel algorithm for every
+/// candidate, so appending an element to the query only requires a single step of
+/// `O([N/64])` for a candidate of length `N`. This is useful for search-as-you-type,
+/// where all candidates are rescored after every keystroke.
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::levenshtein;
+///
+/// let mut scorer = levenshtein::IncrementalComparator::new(["kitten", "sitting"].map(str::chars));
+/// scorer.extend("kit".chars());
+/// assert_eq!((3, 5), (scorer.distance(0), scorer.distance(1)));
+///
+/// scorer.push('t');
+/// assert_eq!((2, 4), (scorer.distance(0), scorer.distance(1)));
+/// ```
+#[derive(Clone)]
+pub struct IncrementalComparator {
+    candidates: Vec<IncrementalCandidate>,
+    query_len: usize,
+}
+
+impl IncrementalComparator {
+    /// creates a comparator for `candidates` with an empty query
+    pub fn new<Iter, Seq>(candidates: Iter) -> Self
+    where
+        Iter: IntoIterator<Item = Seq>,
+        Seq: IntoIterator,
+        Seq::Item: HashableChar + Copy,
+    {
+        let candidates = candidates
+            .into_iter()
+            .map(|seq| {
+                let seq: Vec<Seq::Item> = seq.into_iter().collect();
+                let mut pm = BlockPatternMatchVector::new(seq.len());
+                pm.insert(seq.iter().copied());
+                IncrementalCandidate {
+                    vecs: vec![LevenshteinRow::default(); pm.size()],
+                    pm,
+                    len: seq.len(),
+                    last: 1_u64 << (seq.len().saturating_sub(1) % 64),
+                    dist: seq.len(),
+                }
+            })
+            .collect();
+
+        Self {
+            candidates,
+            query_len: 0,
+        }
+    }
+
+    /// number of candidates
+    pub fn len(&self) -> usize {
+        self.candidates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.candidates.is_empty()
+    }
+
+    /// number of elements appended to the query
+    pub fn query_len(&self) -> usize {
+        self.query_len
+    }
+
+    /// appends `ch` to the query and updates the distance of every candidate
+    pub fn push<CharT>(&mut self, ch: CharT)
+    where
+        CharT: HashableChar + Copy,
+    {
+        self.query_len += 1;
+        for candidate in &mut self.candidates {
+            if candidate.vecs.is_empty() {
+                candidate.dist += 1;
+                continue;
+            }
+            // the query is not allowed to skip leading elements of the candidate
+            let (hp, hn) = myers_step(&candidate.pm, &mut candidate.vecs, candidate.last, ch, true);
+            candidate.dist = candidate.dist + usize::from(hp) - usize::from(hn);
+        }
+    }
+
+    /// resets the query to an empty sequence
+    pub fn clear(&mut self) {
+        self.query_len = 0;
+        for candidate in &mut self.candidates {
+            candidate.vecs.fill(LevenshteinRow::default());
+            candidate.dist = candidate.len;
+        }
+    }
+
+    /// Levenshtein distance between the query and the candidate at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn distance(&self, index: usize) -> usize {
+        self.candidates[index].dist
+    }
+
+    /// Levenshtein similarity between the query and the candidate at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn similarity(&self, index: usize) -> usize {
+        let candidate = &self.candidates[index];
+        max(self.query_len, candidate.len) - candidate.dist
+    }
+
+    /// normalized Levenshtein distance between the query and the candidate at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn normalized_distance(&self, index: usize) -> f64 {
+        let candidate = &self.candidates[index];
+        let maximum = max(self.query_len, candidate.len);
+        if maximum == 0 {
+            0.0
+        } else {
+            candidate.dist as f64 / maximum as f64
+        }
+    }
+
+    /// normalized Levenshtein similarity between the query and the candidate at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn normalized_similarity(&self, index: usize) -> f64 {
+        1.0 - self.normalized_distance(index)
+    }
+}
+
+impl<CharT> Extend<CharT> for IncrementalComparator
+where
+    CharT: HashableChar + Copy,
+{
+    fn extend<Iter: IntoIterator<Item = CharT>>(&mut self, iter: Iter) {
+        for ch in iter {
+            self.push(ch);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::details::testing::Lcg;
     use crate::distance::example::ocr::{OCR_EXAMPLE1, OCR_EXAMPLE2};
+    use crate::distance::{Opcode, OpcodeType};
 
     static EMPTY: &str = "";
     static TEST: &str = "aaaa";
@@ -1951,27 +3880,27 @@ mod tests {
         let args_sim = &Args::default().score_cutoff(0.0);
         assert_delta!(
             Some(1.0),
-            _test_normalized_similarity_ascii(TEST, TEST, &args_sim),
+            _test_normalized_similarity_ascii(TEST, TEST, args_sim),
             0.0001
         );
         assert_delta!(
             Some(0.75),
-            _test_normalized_similarity_ascii(TEST, NO_SUFFIX, &args_sim),
+            _test_normalized_similarity_ascii(TEST, NO_SUFFIX, args_sim),
             0.0001
         );
         assert_delta!(
             Some(0.75),
-            _test_normalized_similarity_ascii(TEST, NO_SUFFIX2, &args_sim),
+            _test_normalized_similarity_ascii(TEST, NO_SUFFIX2, args_sim),
             0.0001
         );
         assert_delta!(
             Some(0.5),
-            _test_normalized_similarity_ascii(SWAPPED1, SWAPPED2, &args_sim),
+            _test_normalized_similarity_ascii(SWAPPED1, SWAPPED2, args_sim),
             0.0001
         );
         assert_delta!(
             Some(0.0),
-            _test_normalized_similarity_ascii(TEST, REPLACE_ALL, &args_sim),
+            _test_normalized_similarity_ascii(TEST, REPLACE_ALL, args_sim),
             0.0001
         );
     }
@@ -2019,6 +3948,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_weighted_banded() {
+        fn wagner_fischer(s1: &[u8], s2: &[u8], weights: &WeightTable) -> usize {
+            let mut d = vec![vec![0; s2.len() + 1]; s1.len() + 1];
+            for i in 0..=s1.len() {
+                for j in 0..=s2.len() {
+                    d[i][j] = if i == 0 || j == 0 {
+                        i * weights.deletion_cost + j * weights.insertion_cost
+                    } else {
+                        let sub = if s1[i - 1] == s2[j - 1] {
+                            0
+                        } else {
+                            weights.substitution_cost
+                        };
+                        (d[i - 1][j - 1] + sub)
+                            .min(d[i][j - 1] + weights.insertion_cost)
+                            .min(d[i - 1][j] + weights.deletion_cost)
+                    };
+                }
+            }
+            d[s1.len()][s2.len()]
+        }
+
+        let mut rng = Lcg::new(11);
+
+        let weight_tables = [
+            WeightTable {
+                insertion_cost: 1,
+                deletion_cost: 2,
+                substitution_cost: 3,
+            },
+            WeightTable {
+                insertion_cost: 3,
+                deletion_cost: 1,
+                substitution_cost: 2,
+            },
+            WeightTable {
+                insertion_cost: 0,
+                deletion_cost: 2,
+                substitution_cost: 5,
+            },
+        ];
+        for (len1, len2) in [(5, 9), (40, 35), (150, 120), (200, 210)] {
+            let s1 = rng.string(len1, 4);
+            let s2 = rng.string(len2, 4);
+            for weights in &weight_tables {
+                let expected = wagner_fischer(&s1, &s2, weights);
+                let args = Args::default().weights(weights);
+                assert_eq!(expected, distance_with_args(s1.iter(), s2.iter(), &args));
+
+                for score_cutoff in [0, 1, 7, 40, expected, 200] {
+                    let res = distance_with_args(
+                        s1.iter(),
+                        s2.iter(),
+                        &args.score_cutoff(score_cutoff).score_hint(3),
+                    );
+                    assert_eq!((expected <= score_cutoff).then_some(expected), res);
+                }
+
+                let maximum = _maximum(len1, len2, weights);
+                let expected_sim = maximum - expected;
+                for score_cutoff in [0, 1, 7, 40, expected_sim, maximum] {
+                    let res = similarity_with_args(
+                        s1.iter(),
+                        s2.iter(),
+                        &args.score_cutoff(score_cutoff),
+                    );
+                    assert_eq!((expected_sim >= score_cutoff).then_some(expected_sim), res);
+                }
+            }
+        }
+    }
+
     /// test mbleven implementation
     #[test]
     fn test_mbleven() {
@@ -2167,4 +4169,624 @@ mod tests {
             _test_distance("Иванко".chars(), "Петрунко".chars(), &Args::default())
         );
     }
+
+    fn _test_editops(s1: &str, s2: &str) {
+        let s1: Vec<char> = s1.chars().collect();
+        let s2: Vec<char> = s2.chars().collect();
+        let ops = editops(s1.iter().copied(), s2.iter().copied());
+        assert_eq!(distance(s1.iter(), s2.iter()), ops.len());
+        assert_eq!(s1.len(), ops.src_len());
+        assert_eq!(s2.len(), ops.dest_len());
+        assert_eq!(s2, ops.apply(s1.iter().copied(), s2.iter().copied()));
+
+        let scorer = BatchComparator::new(s1.iter().copied());
+        assert_eq!(ops, scorer.editops(s2.iter().copied()));
+    }
+
+    #[test]
+    fn test_metric() {
+        use crate::distance::Metric;
+
+        assert_eq!(
+            Some(3),
+            Levenshtein.distance("kitten".chars(), "sitting".chars(), None)
+        );
+        assert_eq!(
+            Some(3),
+            Levenshtein.distance("kitten".chars(), "sitting".chars(), Some(3))
+        );
+        assert_eq!(
+            None,
+            Levenshtein.distance("kitten".chars(), "sitting".chars(), Some(2))
+        );
+        assert_eq!(
+            Some(4),
+            Levenshtein.similarity("kitten".chars(), "sitting".chars(), Some(4))
+        );
+        assert_eq!(
+            None,
+            Levenshtein.similarity("kitten".chars(), "sitting".chars(), Some(5))
+        );
+        assert_delta!(
+            Some(normalized_distance("kitten".chars(), "sitting".chars())),
+            Levenshtein.normalized_distance("kitten".chars(), "sitting".chars(), None),
+            0.0001
+        );
+        assert_delta!(
+            Some(normalized_similarity("kitten".chars(), "sitting".chars())),
+            Levenshtein.normalized_similarity("kitten".chars(), "sitting".chars(), Some(0.5)),
+            0.0001
+        );
+        assert_eq!(
+            None,
+            Levenshtein.normalized_similarity("kitten".chars(), "sitting".chars(), Some(0.6))
+        );
+        assert!(Levenshtein::is_better(1, 2));
+    }
+
+    #[test]
+    fn test_batch_metric() {
+        use crate::distance::BatchMetric;
+
+        let scorer = BatchComparator::new("kitten".chars());
+        assert_eq!(
+            Some(3),
+            BatchMetric::distance(&scorer, "sitting".chars(), None)
+        );
+        assert_eq!(
+            None,
+            BatchMetric::distance(&scorer, "sitting".chars(), Some(2))
+        );
+        assert_eq!(
+            Some(4),
+            BatchMetric::similarity(&scorer, "sitting".chars(), Some(4))
+        );
+        assert_delta!(
+            Some(normalized_distance("kitten".chars(), "sitting".chars())),
+            BatchMetric::normalized_distance(&scorer, "sitting".chars(), None),
+            0.0001
+        );
+        assert_eq!(
+            None,
+            BatchMetric::normalized_similarity(&scorer, "sitting".chars(), Some(0.6))
+        );
+        assert!(<BatchComparator<char> as BatchMetric<char>>::is_better(
+            1, 2
+        ));
+    }
+
+    #[test]
+    fn test_editops() {
+        assert_eq!(
+            &[
+                Editop {
+                    tag: EditType::Delete,
+                    src_pos: 0,
+                    dest_pos: 0
+                },
+                Editop {
+                    tag: EditType::Replace,
+                    src_pos: 3,
+                    dest_pos: 2
+                },
+                Editop {
+                    tag: EditType::Insert,
+                    src_pos: 6,
+                    dest_pos: 5
+                },
+            ],
+            &*editops("qabxcd".chars(), "abycdf".chars())
+        );
+
+        _test_editops(EMPTY, EMPTY);
+        _test_editops(TEST, EMPTY);
+        _test_editops(EMPTY, TEST);
+        _test_editops(TEST, TEST);
+        _test_editops(TEST, NO_SUFFIX2);
+        _test_editops(SWAPPED1, SWAPPED2);
+        _test_editops(TEST, REPLACE_ALL);
+        _test_editops("Иванко", "Петрунко");
+
+        // small band
+        let s1 = "abcdefghij".repeat(20);
+        let s2 = s1.replace("def", "xy");
+        _test_editops(&s1, &s2);
+        // blockwise
+        let s1 = "abcdefghij".repeat(20);
+        let s2 = "ghijabcx".repeat(30);
+        _test_editops(&s1, &s2);
+        _test_editops(&s2, &s1);
+    }
+
+    #[test]
+    fn test_editops_hirschberg() {
+        // pseudo random sequences, which are too different to record the full bit matrix
+        let mut rng = Lcg::new(42);
+        let s1: String = (0..3000).map(|_| char::from(rng.letter(8))).collect();
+        let s2: String = (0..2500).map(|_| char::from(rng.letter(8))).collect();
+        _test_editops(&s1, &s2);
+        _test_editops(&s2, &s1);
+    }
+
+    #[test]
+    fn test_editops_large_band() {
+        let ops = editops(OCR_EXAMPLE1.iter().copied(), OCR_EXAMPLE2.iter().copied());
+        assert_eq!(5278, ops.len());
+        assert_eq!(
+            &OCR_EXAMPLE2[..],
+            &ops.apply(OCR_EXAMPLE1.iter().copied(), OCR_EXAMPLE2.iter().copied())[..]
+        );
+
+        let scorer = BatchComparator::new(OCR_EXAMPLE1.iter().copied());
+        assert_eq!(ops, scorer.editops(OCR_EXAMPLE2.iter().copied()));
+    }
+
+    #[test]
+    fn test_opcodes() {
+        let ops = opcodes("qabxcd".chars(), "abycdf".chars());
+        let expected = [
+            (OpcodeType::Delete, 0, 1, 0, 0),
+            (OpcodeType::Equal, 1, 3, 0, 2),
+            (OpcodeType::Replace, 3, 4, 2, 3),
+            (OpcodeType::Equal, 4, 6, 3, 5),
+            (OpcodeType::Insert, 6, 6, 5, 6),
+        ];
+        assert_eq!(expected.len(), ops.len());
+        for (op, (tag, src_start, src_end, dest_start, dest_end)) in ops.iter().zip(expected) {
+            assert_eq!(
+                Opcode {
+                    tag,
+                    src_start,
+                    src_end,
+                    dest_start,
+                    dest_end
+                },
+                *op
+            );
+        }
+        assert_eq!(
+            ops,
+            BatchComparator::new("qabxcd".chars()).opcodes("abycdf".chars())
+        );
+    }
+
+    /// semi-global alignment using the Wagner-Fischer algorithm
+    fn find_naive(pattern: &[u8], text: &[u8], max_distance: usize) -> Vec<(usize, usize)> {
+        let mut col: Vec<usize> = (0..=pattern.len()).collect();
+        let mut matches = Vec::new();
+        for end in 0..=text.len() {
+            if end != 0 {
+                let mut diag = col[0];
+                col[0] = 0;
+                for i in 1..=pattern.len() {
+                    let cost = usize::from(pattern[i - 1] != text[end - 1]);
+                    let val = (diag + cost).min(col[i] + 1).min(col[i - 1] + 1);
+                    diag = col[i];
+                    col[i] = val;
+                }
+            }
+            if col[pattern.len()] <= max_distance {
+                matches.push((end, col[pattern.len()]));
+            }
+        }
+        matches
+    }
+
+    #[test]
+    fn test_automaton() {
+        let words = [
+            "", "a", "ab", "ba", "abc", "acb", "bac", "abcd", "abdc", "xyz", "aabbcc", "cba",
+        ];
+        for query in words {
+            for max_distance in 0..3 {
+                let automaton = Automaton::new(query.chars(), max_distance);
+                let osa_automaton = automaton.clone().transpositions(true);
+                for word in words {
+                    let mut state = automaton.start();
+                    let mut osa_state = osa_automaton.start();
+                    for ch in word.chars() {
+                        state = automaton.step(&state, ch);
+                        osa_state = osa_automaton.step(&osa_state, ch);
+                    }
+
+                    let expected = distance(query.chars(), word.chars());
+                    assert_eq!(
+                        (expected <= max_distance).then_some(expected),
+                        automaton.distance(&state)
+                    );
+                    let expected = crate::distance::osa::distance(query.chars(), word.chars());
+                    assert_eq!(
+                        (expected <= max_distance).then_some(expected),
+                        osa_automaton.distance(&osa_state)
+                    );
+                }
+            }
+        }
+
+        let automaton = Automaton::new("abc".chars(), 1);
+        let state = automaton.step(&automaton.start(), 'x');
+        assert!(automaton.can_match(&state));
+        assert!(!automaton.is_match(&state));
+        let state = automaton.step(&state, 'y');
+        assert!(!automaton.can_match(&state));
+    }
+
+    #[test]
+    fn test_find_iter() {
+        let mut rng = Lcg::new(7);
+        let text: Vec<u8> = (0..500).map(|_| rng.letter(4)).collect();
+
+        for (pattern_len, max_distance) in [(0, 0), (3, 0), (8, 2), (20, 6), (70, 20), (150, 50)] {
+            let pattern: Vec<u8> = (0..pattern_len).map(|_| rng.letter(4)).collect();
+            let matches: Vec<Match> = find_iter(pattern.iter(), text.iter(), max_distance)
+                .with_start()
+                .collect();
+            assert_eq!(
+                find_naive(&pattern, &text, max_distance),
+                matches
+                    .iter()
+                    .map(|m| (m.end, m.distance))
+                    .collect::<Vec<_>>()
+            );
+
+            for m in matches {
+                let start = m.start.unwrap();
+                assert_eq!(
+                    m.distance,
+                    distance(pattern.iter(), text[start..m.end].iter())
+                );
+            }
+        }
+
+        assert_eq!(
+            vec![
+                Match {
+                    start: None,
+                    end: 0,
+                    distance: 1
+                },
+                Match {
+                    start: None,
+                    end: 1,
+                    distance: 1
+                }
+            ],
+            find_iter("a".chars(), "b".chars(), 1).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_prefix_mode() {
+        let mut rng = Lcg::new(3);
+
+        let weight_tables = [
+            WeightTable::default(),
+            WeightTable {
+                insertion_cost: 2,
+                deletion_cost: 2,
+                substitution_cost: 2,
+            },
+            WeightTable {
+                insertion_cost: 1,
+                deletion_cost: 2,
+                substitution_cost: 3,
+            },
+        ];
+        for (len1, len2) in [(0, 5), (4, 0), (5, 9), (12, 7), (70, 90), (130, 140)] {
+            let s1: Vec<u8> = (0..len1).map(|_| rng.letter(4)).collect();
+            let s2: Vec<u8> = (0..len2).map(|_| rng.letter(4)).collect();
+            let scorer = BatchComparator::new(s1.iter().copied());
+
+            for weights in &weight_tables {
+                let args = Args::default().weights(weights);
+                let expected = (0..=len2)
+                    .map(|end| distance_with_args(s1.iter(), s2[..end].iter(), &args))
+                    .min()
+                    .unwrap();
+
+                let args = args.prefix_mode(true);
+                assert_eq!(expected, distance_with_args(s1.iter(), s2.iter(), &args));
+                assert_eq!(
+                    expected,
+                    scorer.distance_with_args(s2.iter().copied(), &args)
+                );
+                assert_eq!(
+                    len1 * weights.deletion_cost - expected,
+                    similarity_with_args(s1.iter(), s2.iter(), &args)
+                );
+            }
+        }
+
+        let args = Args::default().prefix_mode(true);
+        assert_eq!(
+            0,
+            distance_with_args("restaur".chars(), "restaurant".chars(), &args)
+        );
+        assert_eq!(
+            Some(1),
+            distance_with_args(
+                "rstaur".chars(),
+                "restaurant".chars(),
+                &args.score_cutoff(1)
+            )
+        );
+        assert_delta!(
+            Some(0.25),
+            Some(normalized_distance_with_args(
+                "rest".chars(),
+                "best match".chars(),
+                &Args::default().prefix_mode(true)
+            )),
+            0.0001
+        );
+    }
+
+    #[test]
+    fn test_prefix_mode_score_cutoff() {
+        let mut rng = Lcg::new(5);
+
+        let weight_tables = [
+            WeightTable::default(),
+            WeightTable {
+                insertion_cost: 2,
+                deletion_cost: 2,
+                substitution_cost: 2,
+            },
+            WeightTable {
+                insertion_cost: 1,
+                deletion_cost: 2,
+                substitution_cost: 3,
+            },
+        ];
+        for (len1, len2) in [(5, 9), (40, 60), (70, 90), (130, 200), (300, 350)] {
+            // derive s2 from s1, so the distances are small enough to be within the cutoffs
+            let s1 = rng.string(len1, 4);
+            let mut s2 = s1.clone();
+            for _ in 0..len1 / 10 {
+                let pos = rng.next(s2.len());
+                s2[pos] = rng.letter(4);
+            }
+            s2.truncate(len1 - rng.next(len1 / 5 + 1));
+            while s2.len() < len2 {
+                s2.push(rng.letter(4));
+            }
+            let scorer = BatchComparator::new(s1.iter().copied());
+
+            for weights in &weight_tables {
+                let args = Args::default().weights(weights);
+                let expected = (0..=len2)
+                    .map(|end| distance_with_args(s1.iter(), s2[..end].iter(), &args))
+                    .min()
+                    .unwrap();
+
+                let args = args.prefix_mode(true);
+
+                for score_cutoff in [0, expected / 2, expected, expected + 1, 2 * expected] {
+                    let cutoff_args = args.score_cutoff(score_cutoff);
+                    let expected = Some(expected).filter(|&dist| dist <= score_cutoff);
+                    assert_eq!(
+                        expected,
+                        distance_with_args(s1.iter(), s2.iter(), &cutoff_args)
+                    );
+                    assert_eq!(
+                        expected,
+                        scorer.distance_with_args(s2.iter().copied(), &cutoff_args)
+                    );
+                    assert_eq!(
+                        expected,
+                        scorer.distance_with_args(
+                            s2.iter().copied(),
+                            &cutoff_args.score_hint(score_cutoff / 4)
+                        )
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_incremental_comparator() {
+        let candidates = ["", "a", "kitten", "sitting", "abcdefghij", &"xy".repeat(80)];
+        let mut scorer = IncrementalComparator::new(candidates.iter().map(|c| c.chars()));
+        assert_eq!(candidates.len(), scorer.len());
+
+        let query: String = "sittingxyxyxy"
+            .chars()
+            .chain("yx".repeat(70).chars())
+            .collect();
+        for round in 0..2 {
+            for (len, ch) in query.char_indices() {
+                for (index, candidate) in candidates.iter().enumerate() {
+                    let query = &query[..len];
+                    assert_eq!(
+                        distance(query.chars(), candidate.chars()),
+                        scorer.distance(index)
+                    );
+                    assert_eq!(
+                        similarity(query.chars(), candidate.chars()),
+                        scorer.similarity(index)
+                    );
+                    assert_delta!(
+                        Some(normalized_distance(query.chars(), candidate.chars())),
+                        Some(scorer.normalized_distance(index)),
+                        0.0001
+                    );
+                }
+                scorer.push(ch);
+            }
+            assert_eq!(query.len(), scorer.query_len());
+
+            if round == 0 {
+                scorer.clear();
+                assert_eq!(0, scorer.query_len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_cost_model() {
+        let mut rng = Lcg::new(3);
+
+        // without exceptions the matrix behaves like the weight table
+        let weight_tables = [
+            WeightTable::default(),
+            WeightTable {
+                insertion_cost: 1,
+                deletion_cost: 1,
+                substitution_cost: 2,
+            },
+            WeightTable {
+                insertion_cost: 2,
+                deletion_cost: 3,
+                substitution_cost: 4,
+            },
+        ];
+        for (len1, len2) in [(0, 4), (6, 9), (70, 65)] {
+            let s1 = rng.string(len1, 4);
+            let s2 = rng.string(len2, 4);
+            for weights in &weight_tables {
+                let matrix = SubstitutionMatrix::new(weights);
+                let scorer = BatchComparator::new(s1.iter().copied());
+                for prefix_mode in [false, true] {
+                    let args = Args::default().weights(weights).prefix_mode(prefix_mode);
+                    let expected = distance_with_args(s1.iter(), s2.iter(), &args);
+
+                    // unlike the weight table the maximum keeps equal elements at the
+                    // same position for free
+                    let maximum = if prefix_mode {
+                        len1 * weights.deletion_cost
+                    } else {
+                        let replaced = s1.iter().zip(&s2).filter(|(a, b)| a != b).count();
+                        let shared = min(len1, len2);
+                        min(
+                            len1 * weights.deletion_cost + len2 * weights.insertion_cost,
+                            replaced * weights.substitution_cost
+                                + (len1 - shared) * weights.deletion_cost
+                                + (len2 - shared) * weights.insertion_cost,
+                        )
+                    };
+                    let expected_similarity = maximum - expected;
+                    let expected_normalized = if maximum == 0 {
+                        0.0
+                    } else {
+                        expected as f64 / maximum as f64
+                    };
+
+                    let args = Args::default().prefix_mode(prefix_mode).cost_model(&matrix);
+                    assert_eq!(expected, distance_with_args(s1.iter(), s2.iter(), &args));
+                    assert_eq!(
+                        expected,
+                        scorer.distance_with_args(s2.iter().copied(), &args)
+                    );
+                    assert_eq!(
+                        expected_similarity,
+                        similarity_with_args(s1.iter(), s2.iter(), &args)
+                    );
+                    let normalized_args =
+                        Args::default().prefix_mode(prefix_mode).cost_model(&matrix);
+                    assert_delta!(
+                        Some(expected_normalized),
+                        Some(normalized_distance_with_args(
+                            s1.iter(),
+                            s2.iter(),
+                            &normalized_args
+                        )),
+                        0.0001
+                    );
+                    assert_delta!(
+                        Some(expected_normalized),
+                        Some(
+                            scorer.normalized_distance_with_args(
+                                s2.iter().copied(),
+                                &normalized_args
+                            )
+                        ),
+                        0.0001
+                    );
+
+                    for score_cutoff in [0, expected / 2, expected, expected + 1] {
+                        let args = args.score_cutoff(score_cutoff);
+                        let expected = (expected <= score_cutoff).then_some(expected);
+                        assert_eq!(expected, distance_with_args(s1.iter(), s2.iter(), &args));
+                        assert_eq!(
+                            expected,
+                            scorer.distance_with_args(s2.iter().copied(), &args)
+                        );
+                    }
+                }
+            }
+        }
+
+        let matrix = SubstitutionMatrix::default()
+            .deletion('x', 5)
+            .insertion('y', 0)
+            .substitution('a', 'b', 0);
+        let args = Args::default().cost_model(&matrix);
+        assert_eq!(0, distance_with_args("ab".chars(), "bay".chars(), &args));
+        // replacing `x` is cheaper than deleting it
+        assert_eq!(2, distance_with_args("xa".chars(), "a".chars(), &args));
+        // the maximum is the cheaper of deleting `s1` and inserting `s2` (7) or
+        // replacing `x` with `a` and deleting `a` (2)
+        assert_eq!(0, similarity_with_args("xa".chars(), "a".chars(), &args));
+        assert_delta!(
+            Some(1.0),
+            Some(normalized_distance_with_args(
+                "xa".chars(),
+                "a".chars(),
+                &Args::default().cost_model(&matrix)
+            )),
+            0.0001
+        );
+        // the normalization matches the default weight table when no equal elements
+        // share a position
+        assert_delta!(
+            Some(2.0 / 3.0),
+            Some(normalized_distance_with_args(
+                "abc".chars(),
+                "bcd".chars(),
+                &Args::default().cost_model(SubstitutionMatrix::default())
+            )),
+            0.0001
+        );
+        assert_eq!(3, distance_with_args("ab".chars(), "bazzz".chars(), &args));
+        assert_eq!(
+            0,
+            distance_with_args("ab".chars(), "bazzz".chars(), &args.prefix_mode(true))
+        );
+    }
+
+    #[test]
+    fn test_cost_model_equal_elements() {
+        struct Strict;
+
+        impl CostModel for Strict {
+            fn insertion_cost(&self, _ch: Hash) -> usize {
+                1
+            }
+
+            fn deletion_cost(&self, _ch: Hash) -> usize {
+                1
+            }
+
+            fn substitution_cost(&self, ch1: Hash, ch2: Hash) -> usize {
+                assert!(ch1 != ch2, "substitution of equal elements");
+                1
+            }
+        }
+
+        let args = Args::default().cost_model(Strict);
+        assert_eq!(1, distance_with_args("abc".chars(), "abd".chars(), &args));
+        assert_eq!(0, similarity_with_args("abc".chars(), "abd".chars(), &args));
+        assert_delta!(
+            Some(1.0),
+            Some(normalized_distance_with_args(
+                "abc".chars(),
+                "abd".chars(),
+                &Args::default().cost_model(Strict)
+            )),
+            0.0001
+        );
+        let scorer = BatchComparator::new("abc".chars());
+        assert_eq!(1, scorer.distance_with_args("abd".chars(), &args));
+    }
 }
@@ -33,20 +33,27 @@
 //!
 //! [`Damerau-Levenshtein`]: ../damerau_levenshtein/index.html
 
-use crate::common::{DistanceCutoff, NoScoreCutoff, SimilarityCutoff, WithScoreCutoff};
+use crate::common::{
+    DistanceCutoff, NoProcessor, NoScoreCutoff, Processor, SimilarityCutoff, WithScoreCutoff,
+};
 use crate::details::common::remove_common_affix;
 use crate::details::distance::MetricUsize;
 use crate::details::pattern_match_vector::{
     BitVectorInterface, BlockPatternMatchVector, PatternMatchVector,
 };
+pub use crate::distance::damerau_levenshtein::WeightTable;
+use crate::distance::{impl_batch_metric, impl_metric};
 use crate::HashableChar;
+use std::cmp::min;
 use std::mem;
 
 #[must_use]
 #[derive(Copy, Clone, Debug)]
-pub struct Args<ResultType, CutoffType> {
+pub struct Args<ResultType, CutoffType, ProcessorType = NoProcessor> {
     score_cutoff: CutoffType,
     score_hint: Option<ResultType>,
+    weights: WeightTable,
+    processor: ProcessorType,
 }
 
 impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
@@ -54,11 +61,13 @@ impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
         Args {
             score_cutoff: NoScoreCutoff,
             score_hint: None,
+            weights: WeightTable::default(),
+            processor: NoProcessor,
         }
     }
 }
 
-impl<ResultType, CutoffType> Args<ResultType, CutoffType> {
+impl<ResultType, CutoffType, ProcessorType> Args<ResultType, CutoffType, ProcessorType> {
     pub fn score_hint(mut self, score_hint: ResultType) -> Self {
         self.score_hint = Some(score_hint);
         self
@@ -67,10 +76,27 @@ impl<ResultType, CutoffType> Args<ResultType, CutoffType> {
     pub fn score_cutoff(
         self,
         score_cutoff: ResultType,
-    ) -> Args<ResultType, WithScoreCutoff<ResultType>> {
+    ) -> Args<ResultType, WithScoreCutoff<ResultType>, ProcessorType> {
         Args {
             score_hint: self.score_hint,
             score_cutoff: WithScoreCutoff(score_cutoff),
+            weights: self.weights,
+            processor: self.processor,
+        }
+    }
+
+    pub fn weights(mut self, weights: &WeightTable) -> Self {
+        self.weights = *weights;
+        self
+    }
+
+    /// preprocessing applied to both sequences before comparing them
+    pub fn processor<P>(self, processor: P) -> Args<ResultType, CutoffType, P> {
+        Args {
+            score_cutoff: self.score_cutoff,
+            score_hint: self.score_hint,
+            weights: self.weights,
+            processor,
         }
     }
 }
@@ -226,11 +252,91 @@ where
     curr_dist
 }
 
-struct IndividualComparator;
+/// OSA distance for arbitrary weights based on the Wagner-Fischer algorithm
+/// extended with transpositions of adjacent elements. Returns `usize::MAX` as soon
+/// as the distance can no longer be within `score_cutoff`
+fn generalized_distance<Iter1, Iter2>(
+    s1: Iter1,
+    len1: usize,
+    s2: Iter2,
+    len2: usize,
+    weights: &WeightTable,
+    score_cutoff: usize,
+) -> usize
+where
+    Iter1: Iterator,
+    Iter2: Iterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + Copy,
+{
+    if score_cutoff < weights.min_distance(len1, len2) {
+        return usize::MAX;
+    }
+
+    // lower bound for the distance of any alignment passing through row `i`
+    let row_lower_bound = |i: usize, row: &[usize]| {
+        row.iter()
+            .enumerate()
+            .map(|(j, &cell)| cell + weights.min_distance(len1 - i, len2 - j))
+            .min()
+            .unwrap_or(usize::MAX)
+    };
+
+    let mut r2: Vec<usize> = Vec::with_capacity(len2 + 1);
+    let mut r1: Vec<usize> = Vec::with_capacity(len2 + 1);
+    let mut r: Vec<usize> = (0..=len2).map(|j| j * weights.insertion_cost).collect();
+    let mut prev_ch1 = None;
+    let mut prev_lower_bound = row_lower_bound(0, &r);
+
+    for (i, ch1) in s1.enumerate().map(|(i, ch1)| (i + 1, ch1)) {
+        // rotate the rows, so `r1` and `r2` hold the two previous rows
+        mem::swap(&mut r2, &mut r1);
+        mem::swap(&mut r1, &mut r);
+        r.clear();
+        r.push(i * weights.deletion_cost);
+
+        let mut prev_ch2 = None;
+        for (j, ch2) in s2.clone().enumerate().map(|(j, ch2)| (j + 1, ch2)) {
+            let diag = r1[j - 1]
+                + if ch1 == ch2 {
+                    0
+                } else {
+                    weights.substitution_cost
+                };
+            let left = r[j - 1] + weights.insertion_cost;
+            let up = r1[j] + weights.deletion_cost;
+            let mut temp = min(diag, min(left, up));
+
+            if let (Some(p1), Some(p2)) = (prev_ch1, prev_ch2) {
+                if ch1 == p2 && p1 == ch2 {
+                    temp = min(temp, r2[j - 2] + weights.transposition_cost);
+                }
+            }
+
+            r.push(temp);
+            prev_ch2 = Some(ch2);
+        }
+        prev_ch1 = Some(ch1);
+
+        // transpositions skip at most one row, so every alignment passes through
+        // one of the last two rows
+        let lower_bound = row_lower_bound(i, &r);
+        if min(lower_bound, prev_lower_bound) > score_cutoff {
+            return usize::MAX;
+        }
+        prev_lower_bound = lower_bound;
+    }
+
+    r[len2]
+}
+
+struct IndividualComparator {
+    weights: WeightTable,
+}
 
 impl MetricUsize for IndividualComparator {
     fn maximum(&self, len1: usize, len2: usize) -> usize {
-        len1.max(len2)
+        self.weights.maximum(len1, len2)
     }
 
     fn _distance<Iter1, Iter2>(
@@ -248,6 +354,37 @@ impl MetricUsize for IndividualComparator {
         Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
         Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     {
+        let Some(cost) = self.weights.uniform_cost() else {
+            let affix = remove_common_affix(s1, len1, s2, len2);
+            return generalized_distance(
+                affix.s1,
+                affix.len1,
+                affix.s2,
+                affix.len2,
+                &self.weights,
+                score_cutoff.unwrap_or(usize::MAX),
+            );
+        };
+        if cost == 0 {
+            return 0;
+        }
+        if cost != 1 {
+            // score_cutoff can make use of the common divisor of the weights
+            let uniform = Self {
+                weights: WeightTable::default(),
+            };
+            return uniform
+                ._distance(
+                    s1,
+                    len1,
+                    s2,
+                    len2,
+                    score_cutoff.map(|score_cutoff| score_cutoff / cost),
+                    score_hint.map(|score_hint| score_hint / cost),
+                )
+                .saturating_mul(cost);
+        }
+
         if len1 < len2 {
             return self._distance(s2, len2, s1, len1, score_cutoff, score_hint);
         }
@@ -280,10 +417,10 @@ where
     distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn distance_with_args<Iter1, Iter2, CutoffType>(
+pub fn distance_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<usize, CutoffType>,
+    args: &Args<usize, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -293,17 +430,23 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<usize>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
-    args.score_cutoff.score(IndividualComparator {}._distance(
-        s1_iter.clone(),
-        s1_iter.count(),
-        s2_iter.clone(),
-        s2_iter.count(),
-        args.score_cutoff.cutoff(),
-        args.score_hint,
-    ))
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
+    args.score_cutoff.score(
+        IndividualComparator {
+            weights: args.weights,
+        }
+        ._distance(
+            s1_iter.clone(),
+            s1_iter.count(),
+            s2_iter.clone(),
+            s2_iter.count(),
+            args.score_cutoff.cutoff(),
+            args.score_hint,
+        ),
+    )
 }
 
 pub fn similarity<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> usize
@@ -318,10 +461,10 @@ where
     similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn similarity_with_args<Iter1, Iter2, CutoffType>(
+pub fn similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<usize, CutoffType>,
+    args: &Args<usize, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -331,17 +474,23 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<usize>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
-    args.score_cutoff.score(IndividualComparator {}._similarity(
-        s1_iter.clone(),
-        s1_iter.count(),
-        s2_iter.clone(),
-        s2_iter.count(),
-        args.score_cutoff.cutoff(),
-        args.score_hint,
-    ))
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
+    args.score_cutoff.score(
+        IndividualComparator {
+            weights: args.weights,
+        }
+        ._similarity(
+            s1_iter.clone(),
+            s1_iter.count(),
+            s2_iter.clone(),
+            s2_iter.count(),
+            args.score_cutoff.cutoff(),
+            args.score_hint,
+        ),
+    )
 }
 
 pub fn normalized_distance<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
@@ -356,10 +505,10 @@ where
     normalized_distance_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType>(
+pub fn normalized_distance_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -369,18 +518,23 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: DistanceCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
-    args.score_cutoff
-        .score(IndividualComparator {}._normalized_distance(
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
+    args.score_cutoff.score(
+        IndividualComparator {
+            weights: args.weights,
+        }
+        ._normalized_distance(
             s1_iter.clone(),
             s1_iter.count(),
             s2_iter.clone(),
             s2_iter.count(),
             args.score_cutoff.cutoff(),
             args.score_hint,
-        ))
+        ),
+    )
 }
 
 pub fn normalized_similarity<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
@@ -395,10 +549,10 @@ where
     normalized_similarity_with_args(s1, s2, &Args::default())
 }
 
-pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType>(
+pub fn normalized_similarity_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -408,29 +562,54 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     CutoffType: SimilarityCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
-    args.score_cutoff
-        .score(IndividualComparator {}._normalized_similarity(
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
+    args.score_cutoff.score(
+        IndividualComparator {
+            weights: args.weights,
+        }
+        ._normalized_similarity(
             s1_iter.clone(),
             s1_iter.count(),
             s2_iter.clone(),
             s2_iter.count(),
             args.score_cutoff.cutoff(),
             args.score_hint,
-        ))
+        ),
+    )
 }
 
+/// Marker type describing the Optimal String Alignment distance, see [`Metric`](crate::distance::Metric)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Osa;
+
+impl_metric!(
+    Osa,
+    usize,
+    higher_is_better: false,
+    optimal_score: 0,
+    worst_score: usize::MAX,
+    comparator: IndividualComparator {
+        weights: WeightTable::default(),
+    },
+);
+
 #[derive(Clone)]
 pub struct BatchComparator<Elem1> {
     s1: Vec<Elem1>,
     pm: BlockPatternMatchVector,
 }
 
-impl<CharT> MetricUsize for BatchComparator<CharT> {
+struct BatchComparatorImpl<'a, Elem1> {
+    cache: &'a BatchComparator<Elem1>,
+    weights: &'a WeightTable,
+}
+
+impl<CharT> MetricUsize for BatchComparatorImpl<'_, CharT> {
     fn maximum(&self, len1: usize, len2: usize) -> usize {
-        len1.max(len2)
+        self.weights.maximum(len1, len2)
     }
 
     fn _distance<Iter1, Iter2>(
@@ -439,7 +618,7 @@ impl<CharT> MetricUsize for BatchComparator<CharT> {
         len1: usize,
         s2: Iter2,
         len2: usize,
-        _score_cutoff: Option<usize>,
+        score_cutoff: Option<usize>,
         _score_hint: Option<usize>,
     ) -> usize
     where
@@ -448,15 +627,27 @@ impl<CharT> MetricUsize for BatchComparator<CharT> {
         Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
         Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
     {
-        if self.s1.is_empty() {
+        let Some(cost) = self.weights.uniform_cost() else {
+            return generalized_distance(
+                s1,
+                len1,
+                s2,
+                len2,
+                self.weights,
+                score_cutoff.unwrap_or(usize::MAX),
+            );
+        };
+
+        let dist = if self.cache.s1.is_empty() {
             len2
         } else if len2 == 0 {
-            self.s1.len()
-        } else if self.s1.len() <= 64 {
-            hyrroe2003(&self.pm, s1, len1, s2, len2)
+            self.cache.s1.len()
+        } else if self.cache.s1.len() <= 64 {
+            hyrroe2003(&self.cache.pm, s1, len1, s2, len2)
         } else {
-            hyrroe2003_block(&self.pm, s1, len1, s2, len2)
-        }
+            hyrroe2003_block(&self.cache.pm, s1, len1, s2, len2)
+        };
+        dist * cost
     }
 }
 
@@ -502,14 +693,20 @@ where
         CutoffType: DistanceCutoff<f64>,
     {
         let s2_iter = s2.into_iter();
-        args.score_cutoff.score(self._normalized_distance(
-            self.s1.iter().copied(),
-            self.s1.len(),
-            s2_iter.clone(),
-            s2_iter.count(),
-            args.score_cutoff.cutoff(),
-            args.score_hint,
-        ))
+        args.score_cutoff.score(
+            BatchComparatorImpl {
+                cache: self,
+                weights: &args.weights,
+            }
+            ._normalized_distance(
+                self.s1.iter().copied(),
+                self.s1.len(),
+                s2_iter.clone(),
+                s2_iter.count(),
+                args.score_cutoff.cutoff(),
+                args.score_hint,
+            ),
+        )
     }
 
     /// Normalized similarity calculated similar to [`normalized_similarity`]
@@ -536,14 +733,20 @@ where
         CutoffType: SimilarityCutoff<f64>,
     {
         let s2_iter = s2.into_iter();
-        args.score_cutoff.score(self._normalized_similarity(
-            self.s1.iter().copied(),
-            self.s1.len(),
-            s2_iter.clone(),
-            s2_iter.count(),
-            args.score_cutoff.cutoff(),
-            args.score_hint,
-        ))
+        args.score_cutoff.score(
+            BatchComparatorImpl {
+                cache: self,
+                weights: &args.weights,
+            }
+            ._normalized_similarity(
+                self.s1.iter().copied(),
+                self.s1.len(),
+                s2_iter.clone(),
+                s2_iter.count(),
+                args.score_cutoff.cutoff(),
+                args.score_hint,
+            ),
+        )
     }
 
     /// Distance calculated similar to [`distance`]
@@ -570,14 +773,20 @@ where
         CutoffType: DistanceCutoff<usize>,
     {
         let s2_iter = s2.into_iter();
-        args.score_cutoff.score(self._distance(
-            self.s1.iter().copied(),
-            self.s1.len(),
-            s2_iter.clone(),
-            s2_iter.count(),
-            args.score_cutoff.cutoff(),
-            args.score_hint,
-        ))
+        args.score_cutoff.score(
+            BatchComparatorImpl {
+                cache: self,
+                weights: &args.weights,
+            }
+            ._distance(
+                self.s1.iter().copied(),
+                self.s1.len(),
+                s2_iter.clone(),
+                s2_iter.count(),
+                args.score_cutoff.cutoff(),
+                args.score_hint,
+            ),
+        )
     }
 
     /// Similarity calculated similar to [`similarity`]
@@ -604,20 +813,29 @@ where
         CutoffType: SimilarityCutoff<usize>,
     {
         let s2_iter = s2.into_iter();
-        args.score_cutoff.score(self._similarity(
-            self.s1.iter().copied(),
-            self.s1.len(),
-            s2_iter.clone(),
-            s2_iter.count(),
-            args.score_cutoff.cutoff(),
-            args.score_hint,
-        ))
+        args.score_cutoff.score(
+            BatchComparatorImpl {
+                cache: self,
+                weights: &args.weights,
+            }
+            ._similarity(
+                self.s1.iter().copied(),
+                self.s1.len(),
+                s2_iter.clone(),
+                s2_iter.count(),
+                args.score_cutoff.cutoff(),
+                args.score_hint,
+            ),
+        )
     }
 }
 
+impl_batch_metric!(BatchComparator, Osa, args: Args::default());
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::details::testing::Lcg;
 
     fn _test_distance<Iter1, Iter2>(
         s1_: Iter1,
@@ -690,4 +908,109 @@ mod tests {
             _test_distance("Иванко".chars(), "Петрунко".chars(), None, None)
         );
     }
+
+    #[test]
+    fn test_weights() {
+        let mut rng = Lcg::new(9);
+
+        for len in [0, 3, 10, 70] {
+            let s1 = rng.string(len, 4);
+            let s2 = rng.string(len + 5, 4);
+            let weights = WeightTable::default();
+            assert_eq!(
+                distance(s1.iter(), s2.iter()),
+                generalized_distance(
+                    s1.iter(),
+                    s1.len(),
+                    s2.iter(),
+                    s2.len(),
+                    &weights,
+                    usize::MAX
+                )
+            );
+
+            let weights = WeightTable {
+                insertion_cost: 2,
+                deletion_cost: 2,
+                substitution_cost: 2,
+                transposition_cost: 2,
+            };
+            let args = Args::default().weights(&weights);
+            assert_eq!(
+                2 * distance(s1.iter(), s2.iter()),
+                BatchComparator::new(s1.iter().copied())
+                    .distance_with_args(s2.iter().copied(), &args)
+            );
+        }
+
+        let weights = WeightTable {
+            insertion_cost: 2,
+            deletion_cost: 3,
+            substitution_cost: 4,
+            transposition_cost: 1,
+        };
+        let args = Args::default().weights(&weights);
+        assert_eq!(1, distance_with_args("teh".chars(), "the".chars(), &args));
+        // a single deletion is cheaper than an insertion and a substitution
+        assert_eq!(3, distance_with_args("abc".chars(), "ab".chars(), &args));
+        assert_eq!(2, distance_with_args("ab".chars(), "abc".chars(), &args));
+        assert_eq!(
+            7,
+            BatchComparator::new("CA".chars()).distance_with_args("ABC".chars(), &args)
+        );
+        assert_eq!(
+            Some(3),
+            similarity_with_args("CA".chars(), "ABC".chars(), &args.score_cutoff(1))
+        );
+    }
+
+    #[test]
+    fn test_weighted_score_cutoff() {
+        let mut rng = Lcg::new(21);
+
+        let weight_tables = [
+            WeightTable {
+                insertion_cost: 2,
+                deletion_cost: 3,
+                substitution_cost: 4,
+                transposition_cost: 1,
+            },
+            WeightTable {
+                insertion_cost: 3,
+                deletion_cost: 3,
+                substitution_cost: 3,
+                transposition_cost: 3,
+            },
+        ];
+        for len in [0, 5, 20, 70] {
+            let s1 = rng.string(len, 3);
+            let mut s2 = s1.clone();
+            for _ in 0..len / 4 {
+                let pos = rng.next(s2.len());
+                s2[pos] = rng.letter(3);
+            }
+            s2.extend(rng.string(3, 3));
+            let scorer = BatchComparator::new(s1.iter().copied());
+
+            for weights in &weight_tables {
+                let expected = generalized_distance(
+                    s1.iter(),
+                    s1.len(),
+                    s2.iter(),
+                    s2.len(),
+                    weights,
+                    usize::MAX,
+                );
+                for score_cutoff in [0, expected / 2, expected, expected + 1] {
+                    let args = Args::default().weights(weights).score_cutoff(score_cutoff);
+                    let expected = (expected <= score_cutoff).then_some(expected);
+                    assert_eq!(expected, distance_with_args(&s1, &s2, &args));
+                    assert_eq!(
+                        expected,
+                        scorer.distance_with_args(s2.iter().copied(), &args)
+                    );
+                }
+            }
+        }
+    }
 }
@@ -1,13 +1,21 @@
-use crate::common::{NoScoreCutoff, SimilarityCutoff, WithScoreCutoff};
+use crate::common::{NoProcessor, NoScoreCutoff, Processor, SimilarityCutoff, WithScoreCutoff};
+use crate::details::common::norm_sim_to_norm_dist;
 use crate::details::distance::MetricUsize;
-use crate::distance::indel;
+use crate::details::growing_hashmap::{GrowingHashmap, HybridGrowingHashmap};
+use crate::details::splitted_sentence::{
+    set_decomposition, SetDecomposition, SplittedSentence, TokenElem,
+};
+use crate::distance::{indel, BatchMetric};
 use crate::HashableChar;
+use std::fmt::Debug;
+use std::mem;
 
 #[must_use]
 #[derive(Clone, Copy, Debug)]
-pub struct Args<ResultType, CutoffType> {
+pub struct Args<ResultType, CutoffType, ProcessorType = NoProcessor> {
     score_cutoff: CutoffType,
     score_hint: Option<ResultType>,
+    processor: ProcessorType,
 }
 
 impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
@@ -15,11 +23,12 @@ impl<ResultType> Default for Args<ResultType, NoScoreCutoff> {
         Args {
             score_cutoff: NoScoreCutoff,
             score_hint: None,
+            processor: NoProcessor,
         }
     }
 }
 
-impl<ResultType, CutoffType> Args<ResultType, CutoffType> {
+impl<ResultType, CutoffType, ProcessorType> Args<ResultType, CutoffType, ProcessorType> {
     pub fn score_hint(mut self, score_hint: ResultType) -> Self {
         self.score_hint = Some(score_hint);
         self
@@ -28,24 +37,1725 @@ impl<ResultType, CutoffType> Args<ResultType, CutoffType> {
     pub fn score_cutoff(
         self,
         score_cutoff: ResultType,
-    ) -> Args<ResultType, WithScoreCutoff<ResultType>> {
+    ) -> Args<ResultType, WithScoreCutoff<ResultType>, ProcessorType> {
         Args {
             score_hint: self.score_hint,
             score_cutoff: WithScoreCutoff(score_cutoff),
+            processor: self.processor,
         }
     }
+
+    /// preprocessing applied to both sequences before comparing them
+    pub fn processor<P>(self, processor: P) -> Args<ResultType, CutoffType, P> {
+        Args {
+            score_cutoff: self.score_cutoff,
+            score_hint: self.score_hint,
+            processor,
+        }
+    }
+}
+
+impl<ResultType, CutoffType, ProcessorType> Args<ResultType, CutoffType, ProcessorType>
+where
+    ResultType: Copy,
+    CutoffType: Copy,
+{
+    /// used by scorers which process the sequences before passing them on to another scorer
+    fn without_processor(&self) -> Args<ResultType, CutoffType> {
+        Args {
+            score_cutoff: self.score_cutoff,
+            score_hint: self.score_hint,
+            processor: NoProcessor,
+        }
+    }
+}
+
+/// Implements [`BatchMetric`] for batch comparators, which only provide a normalized
+/// similarity. The raw scores are the normalized scores in the range `[0.0, 1.0]`.
+macro_rules! impl_batch_metric {
+    ($($comparator:ident),* $(,)?) => {$(
+        impl<Elem1, Elem2> BatchMetric<Elem2> for $comparator<Elem1>
+        where
+            Elem1: PartialEq<Elem2> + HashableChar + Copy,
+            Elem2: PartialEq<Elem1> + HashableChar + Copy,
+        {
+            type Output = f64;
+            const HIGHER_IS_BETTER: bool = true;
+            const OPTIMAL_SCORE: f64 = 1.0;
+            const WORST_SCORE: f64 = 0.0;
+
+            fn distance<Iter2>(&self, s2: Iter2, score_cutoff: Option<f64>) -> Option<f64>
+            where
+                Iter2: IntoIterator<Item = Elem2>,
+                Iter2::IntoIter: DoubleEndedIterator + Clone,
+            {
+                BatchMetric::normalized_distance(self, s2, score_cutoff)
+            }
+
+            fn similarity<Iter2>(&self, s2: Iter2, score_cutoff: Option<f64>) -> Option<f64>
+            where
+                Iter2: IntoIterator<Item = Elem2>,
+                Iter2::IntoIter: DoubleEndedIterator + Clone,
+            {
+                BatchMetric::normalized_similarity(self, s2, score_cutoff)
+            }
+
+            fn normalized_distance<Iter2>(
+                &self,
+                s2: Iter2,
+                score_cutoff: Option<f64>,
+            ) -> Option<f64>
+            where
+                Iter2: IntoIterator<Item = Elem2>,
+                Iter2::IntoIter: DoubleEndedIterator + Clone,
+            {
+                let sim_cutoff = score_cutoff.map(|cutoff| (1.0 - cutoff).max(0.0));
+                let dist = 1.0 - BatchMetric::normalized_similarity(self, s2, sim_cutoff)?;
+                match score_cutoff {
+                    Some(cutoff) if dist > cutoff => None,
+                    _ => Some(dist),
+                }
+            }
+
+            fn normalized_similarity<Iter2>(
+                &self,
+                s2: Iter2,
+                score_cutoff: Option<f64>,
+            ) -> Option<f64>
+            where
+                Iter2: IntoIterator<Item = Elem2>,
+                Iter2::IntoIter: DoubleEndedIterator + Clone,
+            {
+                match score_cutoff {
+                    Some(cutoff) => {
+                        self.similarity_with_args(s2, &Args::default().score_cutoff(cutoff))
+                    }
+                    None => Some(self.similarity_with_args(s2, &Args::default())),
+                }
+            }
+        }
+    )*};
+}
+
+/// Returns a simple ratio between two strings or `None` if `ratio < score_cutoff`
+///
+/// # Example
+/// ```
+/// use rapidfuzz::fuzz;
+/// /// score is 0.9655
+/// let score = fuzz::ratio("this is a test".chars(), "this is a test!".chars());
+/// ```
+///
+pub fn ratio<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    ratio_with_args(s1, s2, &Args::default())
+}
+
+pub fn ratio_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
+    s1: Iter1,
+    s2: Iter2,
+    args: &Args<f64, CutoffType, ProcessorType>,
+) -> CutoffType::Output
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    CutoffType: SimilarityCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+{
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
+    args.score_cutoff
+        .score(indel::IndividualComparator {}._normalized_similarity(
+            s1_iter.clone(),
+            s1_iter.count(),
+            s2_iter.clone(),
+            s2_iter.count(),
+            args.score_cutoff.cutoff(),
+            args.score_hint,
+        ))
+}
+
+/// `One x Many` comparisons using `ratio`
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// let scorer = fuzz::RatioBatchComparator::new("this is a test".chars());
+/// /// score is 0.9655
+/// let score = scorer.similarity("this is a test!".chars());
+/// ```
+#[derive(Clone)]
+pub struct RatioBatchComparator<Elem1> {
+    scorer: indel::BatchComparator<Elem1>,
+}
+
+impl<Elem1> RatioBatchComparator<Elem1>
+where
+    Elem1: HashableChar + Clone,
+{
+    pub fn new<Iter1>(s1: Iter1) -> Self
+    where
+        Iter1: IntoIterator<Item = Elem1>,
+        Iter1::IntoIter: Clone,
+    {
+        Self {
+            scorer: indel::BatchComparator::new(s1),
+        }
+    }
+
+    /// Similarity calculated similar to [`ratio`]
+    pub fn similarity<Iter2>(&self, s2: Iter2) -> f64
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.similarity_with_args(s2, &Args::default())
+    }
+
+    pub fn similarity_with_args<Iter2, CutoffType>(
+        &self,
+        s2: Iter2,
+        args: &Args<f64, CutoffType>,
+    ) -> CutoffType::Output
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+        CutoffType: SimilarityCutoff<f64>,
+    {
+        let s2_iter = s2.into_iter();
+        args.score_cutoff.score(self.scorer._normalized_similarity(
+            self.scorer.scorer.s1.iter().copied(),
+            self.scorer.scorer.s1.len(),
+            s2_iter.clone(),
+            s2_iter.count(),
+            args.score_cutoff.cutoff(),
+            args.score_hint,
+        ))
+    }
+}
+
+/// Alignment of the best matching substrings found by [`partial_ratio_alignment`]
+///
+/// The matched parts can be retrieved as `s1[src_start..src_end]` and
+/// `s2[dest_start..dest_end]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScoreAlignment {
+    /// similarity of the matched substrings in the range [0.0, 1.0]
+    pub score: f64,
+    /// start of the matched substring in s1
+    pub src_start: usize,
+    /// end of the matched substring in s1
+    pub src_end: usize,
+    /// start of the matched substring in s2
+    pub dest_start: usize,
+    /// end of the matched substring in s2
+    pub dest_end: usize,
+}
+
+impl ScoreAlignment {
+    fn swap_src_dest(mut self) -> Self {
+        mem::swap(&mut self.src_start, &mut self.dest_start);
+        mem::swap(&mut self.src_end, &mut self.dest_end);
+        self
+    }
+}
+
+/// Selects the output type of functions returning a [`ScoreAlignment`] based on
+/// the `score_cutoff` passed in [`Args`]
+pub trait AlignmentCutoff: SimilarityCutoff<f64> {
+    type AlignmentOutput: Copy + Into<Option<ScoreAlignment>> + PartialEq + Debug;
+
+    fn alignment(&self, raw: ScoreAlignment) -> Self::AlignmentOutput;
+}
+
+impl AlignmentCutoff for NoScoreCutoff {
+    type AlignmentOutput = ScoreAlignment;
+
+    fn alignment(&self, raw: ScoreAlignment) -> Self::AlignmentOutput {
+        raw
+    }
+}
+
+impl AlignmentCutoff for WithScoreCutoff<f64> {
+    type AlignmentOutput = Option<ScoreAlignment>;
+
+    fn alignment(&self, raw: ScoreAlignment) -> Self::AlignmentOutput {
+        (raw.score >= self.0).then_some(raw)
+    }
+}
+
+/// set of all elements included in a sequence
+#[derive(Clone)]
+struct CharSet {
+    map: HybridGrowingHashmap<bool>,
+}
+
+impl CharSet {
+    fn new<Iter1>(s1: Iter1) -> Self
+    where
+        Iter1: Iterator,
+        Iter1::Item: HashableChar,
+    {
+        let mut map = HybridGrowingHashmap::<bool> {
+            map_unsigned: GrowingHashmap::default(),
+            map_signed: GrowingHashmap::default(),
+            extended_ascii: [false; 256],
+        };
+        for ch in s1 {
+            *map.get_mut(ch) = true;
+        }
+        Self { map }
+    }
+
+    fn contains<CharT>(&self, key: CharT) -> bool
+    where
+        CharT: HashableChar,
+    {
+        self.map.get(key)
+    }
+}
+
+/// searches the best matching window of `s2` for `s1`.
+///
+/// This requires `len1 <= len2`. Windows of the full length of `s1` are searched
+/// using a binary search over the window positions, which skips ranges that can't
+/// improve on the best score found so far. Windows overlapping the start or end
+/// of `s2` are only considered when they start / end with an element of `s1`.
+fn partial_ratio_impl<Elem1, Elem2>(
+    s1: &[Elem1],
+    s2: &[Elem2],
+    scorer: &indel::BatchComparator<Elem1>,
+    s1_char_set: &CharSet,
+    mut score_cutoff: f64,
+) -> ScoreAlignment
+where
+    Elem1: PartialEq<Elem2> + HashableChar + Copy,
+    Elem2: PartialEq<Elem1> + HashableChar + Copy,
+{
+    let len1 = s1.len();
+    let len2 = s2.len();
+    debug_assert!(len1 <= len2);
+
+    let mut res = ScoreAlignment {
+        score: 0.0,
+        src_start: 0,
+        src_end: len1,
+        dest_start: 0,
+        dest_end: len1,
+    };
+
+    if len2 > len1 {
+        let maximum = len1 * 2;
+        let mut cutoff_dist =
+            (maximum as f64 * norm_sim_to_norm_dist(score_cutoff)).ceil() as usize;
+        let mut best_dist = usize::MAX;
+        let mut scores = vec![usize::MAX; len2 - len1];
+        let mut windows = vec![(0, len2 - len1 - 1)];
+        let mut new_windows = Vec::new();
+
+        while !windows.is_empty() {
+            for &(first, last) in &windows {
+                for pos in [first, last] {
+                    if scores[pos] != usize::MAX {
+                        continue;
+                    }
+
+                    scores[pos] = scorer.distance(s2[pos..pos + len1].iter().copied());
+                    if scores[pos] < cutoff_dist {
+                        cutoff_dist = scores[pos];
+                        best_dist = scores[pos];
+                        res.dest_start = pos;
+                        res.dest_end = pos + len1;
+                        if best_dist == 0 {
+                            res.score = 1.0;
+                            return res;
+                        }
+                    }
+                }
+
+                let cell_diff = last - first;
+                if cell_diff <= 1 {
+                    continue;
+                }
+
+                // find the minimum score possible in the range first <-> last
+                let known_edits = scores[first].abs_diff(scores[last]);
+                // half of the cells that are not needed for known_edits can lead to a better score
+                let max_score_improvement = cell_diff.saturating_sub(known_edits / 2) / 2 * 2;
+                let min_score =
+                    scores[first].min(scores[last]) as isize - max_score_improvement as isize;
+                if min_score < cutoff_dist as isize {
+                    let center = cell_diff / 2;
+                    new_windows.push((first, first + center));
+                    new_windows.push((first + center, last));
+                }
+            }
+
+            mem::swap(&mut windows, &mut new_windows);
+            new_windows.clear();
+        }
+
+        if best_dist != usize::MAX {
+            let score = 1.0 - best_dist as f64 / maximum as f64;
+            if score >= score_cutoff {
+                score_cutoff = score;
+                res.score = score;
+            }
+        }
+    }
+
+    let args = indel::Args::default();
+    for i in 1..len1 {
+        if !s1_char_set.contains(s2[i - 1]) {
+            continue;
+        }
+
+        let ls_ratio = scorer.normalized_similarity_with_args(
+            s2[..i].iter().copied(),
+            &args.score_cutoff(score_cutoff),
+        );
+        if let Some(ls_ratio) = ls_ratio {
+            if ls_ratio > res.score {
+                score_cutoff = ls_ratio;
+                res.score = ls_ratio;
+                res.dest_start = 0;
+                res.dest_end = i;
+                if res.score == 1.0 {
+                    return res;
+                }
+            }
+        }
+    }
+
+    for i in len2 - len1..len2 {
+        if !s1_char_set.contains(s2[i]) {
+            continue;
+        }
+
+        let ls_ratio = scorer.normalized_similarity_with_args(
+            s2[i..].iter().copied(),
+            &args.score_cutoff(score_cutoff),
+        );
+        if let Some(ls_ratio) = ls_ratio {
+            if ls_ratio > res.score {
+                score_cutoff = ls_ratio;
+                res.score = ls_ratio;
+                res.dest_start = i;
+                res.dest_end = len2;
+                if res.score == 1.0 {
+                    return res;
+                }
+            }
+        }
+    }
+
+    res
+}
+
+/// Searches the substring of the longer sequence, which matches the shorter sequence best
+///
+/// This returns the [`ratio`] of the shorter sequence and the optimal alignment
+/// inside the longer sequence, together with the positions of this alignment.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// let res = fuzz::partial_ratio_alignment("a certain string".chars(), "cetain".chars());
+/// /// score is 0.8333
+/// assert_eq!((2, 8), (res.src_start, res.src_end));
+/// assert_eq!((0, 6), (res.dest_start, res.dest_end));
+/// ```
+pub fn partial_ratio_alignment<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> ScoreAlignment
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    partial_ratio_alignment_with_args(s1, s2, &Args::default())
+}
+
+pub fn partial_ratio_alignment_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
+    s1: Iter1,
+    s2: Iter2,
+    args: &Args<f64, CutoffType, ProcessorType>,
+) -> CutoffType::AlignmentOutput
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    CutoffType: AlignmentCutoff,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+{
+    let s1: Vec<Iter1::Item> = args.processor.process(s1.into_iter()).collect();
+    let s2: Vec<Iter2::Item> = args.processor.process(s2.into_iter()).collect();
+    let score_cutoff = args.score_cutoff.cutoff().unwrap_or(0.0);
+
+    let res = if s1.len() > s2.len() {
+        PartialRatioBatchComparator::new(s2.iter().copied())
+            ._alignment(&s1, score_cutoff)
+            .swap_src_dest()
+    } else {
+        PartialRatioBatchComparator::new(s1.iter().copied())._alignment(&s2, score_cutoff)
+    };
+    args.score_cutoff.alignment(res)
+}
+
+/// Returns the [`ratio`] of the shorter sequence and the best matching substring of
+/// the longer sequence with the same length.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// assert_eq!(1.0, fuzz::partial_ratio("this is a test".chars(), "this is a test!".chars()));
+/// ```
+pub fn partial_ratio<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    partial_ratio_with_args(s1, s2, &Args::default())
+}
+
+pub fn partial_ratio_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
+    s1: Iter1,
+    s2: Iter2,
+    args: &Args<f64, CutoffType, ProcessorType>,
+) -> CutoffType::Output
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    CutoffType: SimilarityCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+{
+    let s1: Vec<Iter1::Item> = args.processor.process(s1.into_iter()).collect();
+    let s2: Vec<Iter2::Item> = args.processor.process(s2.into_iter()).collect();
+    let score_cutoff = args.score_cutoff.cutoff().unwrap_or(0.0);
+
+    let res = if s1.len() > s2.len() {
+        PartialRatioBatchComparator::new(s2.iter().copied())._alignment(&s1, score_cutoff)
+    } else {
+        PartialRatioBatchComparator::new(s1.iter().copied())._alignment(&s2, score_cutoff)
+    };
+    args.score_cutoff.score(res.score)
+}
+
+/// `One x Many` comparisons using `partial_ratio`
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// let scorer = fuzz::PartialRatioBatchComparator::new("this is a test".chars());
+/// assert_eq!(1.0, scorer.similarity("this is a test!".chars()));
+/// ```
+#[derive(Clone)]
+pub struct PartialRatioBatchComparator<Elem1> {
+    s1: Vec<Elem1>,
+    scorer: indel::BatchComparator<Elem1>,
+    char_set: CharSet,
+}
+
+impl<Elem1> PartialRatioBatchComparator<Elem1>
+where
+    Elem1: HashableChar + Clone,
+{
+    pub fn new<Iter1>(s1_: Iter1) -> Self
+    where
+        Iter1: IntoIterator<Item = Elem1>,
+        Iter1::IntoIter: Clone,
+    {
+        let s1_iter = s1_.into_iter();
+        let s1: Vec<Elem1> = s1_iter.clone().collect();
+        let char_set = CharSet::new(s1.iter().cloned());
+        Self {
+            s1,
+            scorer: indel::BatchComparator::new(s1_iter),
+            char_set,
+        }
+    }
+
+    fn _alignment<Elem2>(&self, s2: &[Elem2], score_cutoff: f64) -> ScoreAlignment
+    where
+        Elem1: PartialEq<Elem2> + HashableChar + Copy,
+        Elem2: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        let len1 = self.s1.len();
+        let len2 = s2.len();
+
+        // the cached sequence has to be the shorter one
+        if len1 > len2 {
+            return PartialRatioBatchComparator::new(s2.iter().copied())
+                ._alignment(&self.s1, score_cutoff)
+                .swap_src_dest();
+        }
+
+        if score_cutoff > 1.0 || len1 == 0 || len2 == 0 {
+            return ScoreAlignment {
+                score: if len1 == len2 && score_cutoff <= 1.0 {
+                    1.0
+                } else {
+                    0.0
+                },
+                src_start: 0,
+                src_end: len1,
+                dest_start: 0,
+                dest_end: len1,
+            };
+        }
+
+        let alignment =
+            partial_ratio_impl(&self.s1, s2, &self.scorer, &self.char_set, score_cutoff);
+
+        // for sequences of the same length the result of the search is not symmetric
+        if alignment.score != 1.0 && len1 == len2 {
+            let cached2 = PartialRatioBatchComparator::new(s2.iter().copied());
+            let alignment2 = partial_ratio_impl(
+                s2,
+                &self.s1,
+                &cached2.scorer,
+                &cached2.char_set,
+                score_cutoff.max(alignment.score),
+            );
+            if alignment2.score > alignment.score {
+                return alignment2.swap_src_dest();
+            }
+        }
+
+        alignment
+    }
+
+    /// Similarity calculated similar to [`partial_ratio`]
+    pub fn similarity<Iter2>(&self, s2: Iter2) -> f64
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.similarity_with_args(s2, &Args::default())
+    }
+
+    pub fn similarity_with_args<Iter2, CutoffType>(
+        &self,
+        s2: Iter2,
+        args: &Args<f64, CutoffType>,
+    ) -> CutoffType::Output
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+        CutoffType: SimilarityCutoff<f64>,
+    {
+        let s2: Vec<Iter2::Item> = s2.into_iter().collect();
+        let res = self._alignment(&s2, args.score_cutoff.cutoff().unwrap_or(0.0));
+        args.score_cutoff.score(res.score)
+    }
+
+    /// Alignment calculated similar to [`partial_ratio_alignment`]
+    pub fn alignment<Iter2>(&self, s2: Iter2) -> ScoreAlignment
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.alignment_with_args(s2, &Args::default())
+    }
+
+    pub fn alignment_with_args<Iter2, CutoffType>(
+        &self,
+        s2: Iter2,
+        args: &Args<f64, CutoffType>,
+    ) -> CutoffType::AlignmentOutput
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+        CutoffType: AlignmentCutoff,
+    {
+        let s2: Vec<Iter2::Item> = s2.into_iter().collect();
+        let res = self._alignment(&s2, args.score_cutoff.cutoff().unwrap_or(0.0));
+        args.score_cutoff.alignment(res)
+    }
+}
+
+fn norm_distance(dist: usize, lensum: usize, score_cutoff: f64) -> f64 {
+    let score = if lensum > 0 {
+        1.0 - dist as f64 / lensum as f64
+    } else {
+        1.0
+    };
+
+    if score >= score_cutoff {
+        score
+    } else {
+        0.0
+    }
+}
+
+fn score_cutoff_to_distance(score_cutoff: f64, lensum: usize) -> usize {
+    (lensum as f64 * norm_sim_to_norm_dist(score_cutoff)).ceil() as usize
+}
+
+/// calculates the `token_set_ratio` based on the decomposition of the deduplicated words
+fn token_set_ratio_impl<Elem1, Elem2>(
+    decomposition: &SetDecomposition<Elem1, Elem2>,
+    score_cutoff: f64,
+) -> f64
+where
+    Elem1: PartialEq<Elem2> + HashableChar + Copy,
+    Elem2: PartialEq<Elem1> + HashableChar + Copy,
+{
+    let intersect = &decomposition.intersection;
+    let diff_ab = &decomposition.difference_ab;
+    let diff_ba = &decomposition.difference_ba;
+
+    // one sentence is part of the other one
+    if !intersect.is_empty() && (diff_ab.is_empty() || diff_ba.is_empty()) {
+        return 1.0;
+    }
+
+    let diff_ab_joined = diff_ab.join();
+    let diff_ba_joined = diff_ba.join();
+
+    let ab_len = diff_ab_joined.len();
+    let ba_len = diff_ba_joined.len();
+    let sect_len = intersect.length();
+
+    // string length sect+ab <-> sect and sect+ba <-> sect
+    let sect_ab_len = sect_len + usize::from(sect_len != 0) + ab_len;
+    let sect_ba_len = sect_len + usize::from(sect_len != 0) + ba_len;
+
+    let lensum = sect_ab_len + sect_ba_len;
+    let cutoff_distance = score_cutoff_to_distance(score_cutoff, lensum);
+    let mut result = indel::distance_with_args(
+        diff_ab_joined,
+        diff_ba_joined,
+        &indel::Args::default().score_cutoff(cutoff_distance),
+    )
+    .map_or(0.0, |dist| norm_distance(dist, lensum, score_cutoff));
+
+    // exit early since the other ratios are 0
+    if sect_len == 0 {
+        return result;
+    }
+
+    // levenshtein distance sect+ab <-> sect and sect+ba <-> sect
+    // since only sect is similar in them the distance can be calculated based on the length difference
+    let sect_ab_dist = 1 + ab_len;
+    let sect_ab_ratio = norm_distance(sect_ab_dist, sect_len + sect_ab_len, score_cutoff);
+
+    let sect_ba_dist = 1 + ba_len;
+    let sect_ba_ratio = norm_distance(sect_ba_dist, sect_len + sect_ba_len, score_cutoff);
+
+    result = result.max(sect_ab_ratio).max(sect_ba_ratio);
+    result
+}
+
+/// Sorts the words in the sequences and calculates the [`ratio`] between them
+///
+/// Words are separated by whitespace.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// assert_eq!(
+///     1.0,
+///     fuzz::token_sort_ratio(
+///         "fuzzy wuzzy was a bear".chars(),
+///         "wuzzy fuzzy was a bear".chars()
+///     )
+/// );
+/// ```
+pub fn token_sort_ratio<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    token_sort_ratio_with_args(s1, s2, &Args::default())
+}
+
+pub fn token_sort_ratio_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
+    s1: Iter1,
+    s2: Iter2,
+    args: &Args<f64, CutoffType, ProcessorType>,
+) -> CutoffType::Output
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    CutoffType: SimilarityCutoff<f64> + Copy,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+{
+    let s1_sorted = SplittedSentence::new_sorted(args.processor.process(s1.into_iter())).join();
+    let s2_sorted = SplittedSentence::new_sorted(args.processor.process(s2.into_iter())).join();
+    ratio_with_args(s1_sorted, s2_sorted, &args.without_processor())
+}
+
+/// `One x Many` comparisons using `token_sort_ratio`
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// let scorer = fuzz::TokenSortRatioBatchComparator::new("fuzzy wuzzy was a bear".chars());
+/// assert_eq!(1.0, scorer.similarity("wuzzy fuzzy was a bear".chars()));
+/// ```
+#[derive(Clone)]
+pub struct TokenSortRatioBatchComparator<Elem1> {
+    scorer: RatioBatchComparator<TokenElem<Elem1>>,
+}
+
+impl<Elem1> TokenSortRatioBatchComparator<Elem1>
+where
+    Elem1: HashableChar + Copy,
+{
+    pub fn new<Iter1>(s1: Iter1) -> Self
+    where
+        Iter1: IntoIterator<Item = Elem1>,
+        Iter1::IntoIter: Clone,
+    {
+        Self {
+            scorer: RatioBatchComparator::new(SplittedSentence::new_sorted(s1).join()),
+        }
+    }
+
+    /// Similarity calculated similar to [`token_sort_ratio`]
+    pub fn similarity<Iter2>(&self, s2: Iter2) -> f64
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.similarity_with_args(s2, &Args::default())
+    }
+
+    pub fn similarity_with_args<Iter2, CutoffType>(
+        &self,
+        s2: Iter2,
+        args: &Args<f64, CutoffType>,
+    ) -> CutoffType::Output
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+        CutoffType: SimilarityCutoff<f64>,
+    {
+        self.scorer
+            .similarity_with_args(SplittedSentence::new_sorted(s2).join(), args)
+    }
+}
+
+/// Sorts the words in the sequences and calculates the [`partial_ratio`] between them
+///
+/// # Example
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// assert_eq!(
+///     1.0,
+///     fuzz::partial_token_sort_ratio(
+///         "york new".chars(),
+///         "new york mets".chars()
+///     )
+/// );
+/// ```
+pub fn partial_token_sort_ratio<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    partial_token_sort_ratio_with_args(s1, s2, &Args::default())
+}
+
+pub fn partial_token_sort_ratio_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
+    s1: Iter1,
+    s2: Iter2,
+    args: &Args<f64, CutoffType, ProcessorType>,
+) -> CutoffType::Output
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    CutoffType: SimilarityCutoff<f64> + Copy,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+{
+    let s1_sorted = SplittedSentence::new_sorted(args.processor.process(s1.into_iter())).join();
+    let s2_sorted = SplittedSentence::new_sorted(args.processor.process(s2.into_iter())).join();
+    partial_ratio_with_args(s1_sorted, s2_sorted, &args.without_processor())
+}
+
+/// `One x Many` comparisons using `partial_token_sort_ratio`
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// let scorer = fuzz::PartialTokenSortRatioBatchComparator::new("york new".chars());
+/// assert_eq!(1.0, scorer.similarity("new york mets".chars()));
+/// ```
+#[derive(Clone)]
+pub struct PartialTokenSortRatioBatchComparator<Elem1> {
+    scorer: PartialRatioBatchComparator<TokenElem<Elem1>>,
+}
+
+impl<Elem1> PartialTokenSortRatioBatchComparator<Elem1>
+where
+    Elem1: HashableChar + Copy,
+{
+    pub fn new<Iter1>(s1: Iter1) -> Self
+    where
+        Iter1: IntoIterator<Item = Elem1>,
+        Iter1::IntoIter: Clone,
+    {
+        Self {
+            scorer: PartialRatioBatchComparator::new(SplittedSentence::new_sorted(s1).join()),
+        }
+    }
+
+    /// Similarity calculated similar to [`partial_token_sort_ratio`]
+    pub fn similarity<Iter2>(&self, s2: Iter2) -> f64
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.similarity_with_args(s2, &Args::default())
+    }
+
+    pub fn similarity_with_args<Iter2, CutoffType>(
+        &self,
+        s2: Iter2,
+        args: &Args<f64, CutoffType>,
+    ) -> CutoffType::Output
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+        CutoffType: SimilarityCutoff<f64>,
+    {
+        self.scorer
+            .similarity_with_args(SplittedSentence::new_sorted(s2).join(), args)
+    }
+}
+
+/// Compares the words in the sequences based on unique and common words between them
+/// using [`ratio`]
+///
+/// The sequences are split into the words only found in `s1`, the words only found in `s2`
+/// and the words found in both of them. The result is the best [`ratio`] between
+/// `intersection + difference_ab` and `intersection + difference_ba`, as well as the
+/// intersection and each of them.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// assert_eq!(
+///     1.0,
+///     fuzz::token_set_ratio(
+///         "fuzzy was a bear".chars(),
+///         "fuzzy fuzzy was a bear".chars()
+///     )
+/// );
+/// ```
+pub fn token_set_ratio<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    token_set_ratio_with_args(s1, s2, &Args::default())
+}
+
+pub fn token_set_ratio_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
+    s1: Iter1,
+    s2: Iter2,
+    args: &Args<f64, CutoffType, ProcessorType>,
+) -> CutoffType::Output
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    CutoffType: SimilarityCutoff<f64> + Copy,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+{
+    TokenSetRatioBatchComparator::new(args.processor.process(s1.into_iter())).similarity_with_args(
+        args.processor.process(s2.into_iter()),
+        &args.without_processor(),
+    )
+}
+
+/// `One x Many` comparisons using `token_set_ratio`
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// let scorer = fuzz::TokenSetRatioBatchComparator::new("fuzzy was a bear".chars());
+/// assert_eq!(1.0, scorer.similarity("fuzzy fuzzy was a bear".chars()));
+/// ```
+#[derive(Clone)]
+pub struct TokenSetRatioBatchComparator<Elem1> {
+    tokens_s1: SplittedSentence<Elem1>,
+}
+
+impl<Elem1> TokenSetRatioBatchComparator<Elem1>
+where
+    Elem1: HashableChar + Copy,
+{
+    pub fn new<Iter1>(s1: Iter1) -> Self
+    where
+        Iter1: IntoIterator<Item = Elem1>,
+        Iter1::IntoIter: Clone,
+    {
+        let mut tokens_s1 = SplittedSentence::new_sorted(s1);
+        tokens_s1.dedupe();
+        Self { tokens_s1 }
+    }
+
+    /// Similarity calculated similar to [`token_set_ratio`]
+    pub fn similarity<Iter2>(&self, s2: Iter2) -> f64
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.similarity_with_args(s2, &Args::default())
+    }
+
+    pub fn similarity_with_args<Iter2, CutoffType>(
+        &self,
+        s2: Iter2,
+        args: &Args<f64, CutoffType>,
+    ) -> CutoffType::Output
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+        CutoffType: SimilarityCutoff<f64>,
+    {
+        let score_cutoff = args.score_cutoff.cutoff().unwrap_or(0.0);
+        let mut tokens_s2 = SplittedSentence::new_sorted(s2);
+        tokens_s2.dedupe();
+
+        // in FuzzyWuzzy this returns 0. For sake of compatibility return 0 here as well
+        let score = if score_cutoff > 1.0 || self.tokens_s1.is_empty() || tokens_s2.is_empty() {
+            0.0
+        } else {
+            token_set_ratio_impl(
+                &set_decomposition(&self.tokens_s1, &tokens_s2),
+                score_cutoff,
+            )
+        };
+        args.score_cutoff.score(score)
+    }
+}
+
+/// Compares the words in the sequences based on unique and common words between them
+/// using [`partial_ratio`]
+///
+/// Returns 1.0 as soon as the sequences share a common word. Otherwise this is the
+/// [`partial_ratio`] between the words only found in `s1` and the words only found in `s2`.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// assert_eq!(
+///     1.0,
+///     fuzz::partial_token_set_ratio(
+///         "fuzzy was a bear".chars(),
+///         "fuzzy fuzzy was a bear".chars()
+///     )
+/// );
+/// ```
+pub fn partial_token_set_ratio<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    partial_token_set_ratio_with_args(s1, s2, &Args::default())
+}
+
+pub fn partial_token_set_ratio_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
+    s1: Iter1,
+    s2: Iter2,
+    args: &Args<f64, CutoffType, ProcessorType>,
+) -> CutoffType::Output
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    CutoffType: SimilarityCutoff<f64> + Copy,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+{
+    PartialTokenSetRatioBatchComparator::new(args.processor.process(s1.into_iter()))
+        .similarity_with_args(
+            args.processor.process(s2.into_iter()),
+            &args.without_processor(),
+        )
+}
+
+/// `One x Many` comparisons using `partial_token_set_ratio`
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// let scorer = fuzz::PartialTokenSetRatioBatchComparator::new("fuzzy was a bear".chars());
+/// assert_eq!(1.0, scorer.similarity("fuzzy fuzzy was a bear".chars()));
+/// ```
+#[derive(Clone)]
+pub struct PartialTokenSetRatioBatchComparator<Elem1> {
+    tokens_s1: SplittedSentence<Elem1>,
+}
+
+impl<Elem1> PartialTokenSetRatioBatchComparator<Elem1>
+where
+    Elem1: HashableChar + Copy,
+{
+    pub fn new<Iter1>(s1: Iter1) -> Self
+    where
+        Iter1: IntoIterator<Item = Elem1>,
+        Iter1::IntoIter: Clone,
+    {
+        let mut tokens_s1 = SplittedSentence::new_sorted(s1);
+        tokens_s1.dedupe();
+        Self { tokens_s1 }
+    }
+
+    /// Similarity calculated similar to [`partial_token_set_ratio`]
+    pub fn similarity<Iter2>(&self, s2: Iter2) -> f64
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.similarity_with_args(s2, &Args::default())
+    }
+
+    pub fn similarity_with_args<Iter2, CutoffType>(
+        &self,
+        s2: Iter2,
+        args: &Args<f64, CutoffType>,
+    ) -> CutoffType::Output
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+        CutoffType: SimilarityCutoff<f64>,
+    {
+        let score_cutoff = args.score_cutoff.cutoff().unwrap_or(0.0);
+        let mut tokens_s2 = SplittedSentence::new_sorted(s2);
+        tokens_s2.dedupe();
+
+        let score = if score_cutoff > 1.0 || self.tokens_s1.is_empty() || tokens_s2.is_empty() {
+            0.0
+        } else {
+            let decomposition = set_decomposition(&self.tokens_s1, &tokens_s2);
+
+            // exit early when there is a common word in both sequences
+            if decomposition.intersection.is_empty() {
+                partial_ratio_with_args(
+                    decomposition.difference_ab.join(),
+                    decomposition.difference_ba.join(),
+                    &Args::default().score_cutoff(score_cutoff),
+                )
+                .unwrap_or(0.0)
+            } else {
+                1.0
+            }
+        };
+        args.score_cutoff.score(score)
+    }
+}
+
+/// Helper method that returns the maximum of [`token_set_ratio`] and [`token_sort_ratio`]
+///
+/// This is faster than calculating both of them separately.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// assert_eq!(
+///     1.0,
+///     fuzz::token_ratio(
+///         "fuzzy wuzzy was a bear".chars(),
+///         "wuzzy fuzzy was a bear".chars()
+///     )
+/// );
+/// ```
+pub fn token_ratio<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    token_ratio_with_args(s1, s2, &Args::default())
+}
+
+pub fn token_ratio_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
+    s1: Iter1,
+    s2: Iter2,
+    args: &Args<f64, CutoffType, ProcessorType>,
+) -> CutoffType::Output
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    CutoffType: SimilarityCutoff<f64> + Copy,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+{
+    TokenRatioBatchComparator::new(args.processor.process(s1.into_iter())).similarity_with_args(
+        args.processor.process(s2.into_iter()),
+        &args.without_processor(),
+    )
+}
+
+/// `One x Many` comparisons using `token_ratio`
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// let scorer = fuzz::TokenRatioBatchComparator::new("fuzzy wuzzy was a bear".chars());
+/// assert_eq!(1.0, scorer.similarity("wuzzy fuzzy was a bear".chars()));
+/// ```
+#[derive(Clone)]
+pub struct TokenRatioBatchComparator<Elem1> {
+    tokens_s1: SplittedSentence<Elem1>,
+    scorer: RatioBatchComparator<TokenElem<Elem1>>,
+}
+
+impl<Elem1> TokenRatioBatchComparator<Elem1>
+where
+    Elem1: HashableChar + Copy,
+{
+    pub fn new<Iter1>(s1: Iter1) -> Self
+    where
+        Iter1: IntoIterator<Item = Elem1>,
+        Iter1::IntoIter: Clone,
+    {
+        let mut tokens_s1 = SplittedSentence::new_sorted(s1);
+        let scorer = RatioBatchComparator::new(tokens_s1.join());
+        tokens_s1.dedupe();
+        Self { tokens_s1, scorer }
+    }
+
+    /// Similarity calculated similar to [`token_ratio`]
+    pub fn similarity<Iter2>(&self, s2: Iter2) -> f64
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.similarity_with_args(s2, &Args::default())
+    }
+
+    pub fn similarity_with_args<Iter2, CutoffType>(
+        &self,
+        s2: Iter2,
+        args: &Args<f64, CutoffType>,
+    ) -> CutoffType::Output
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+        CutoffType: SimilarityCutoff<f64>,
+    {
+        let score_cutoff = args.score_cutoff.cutoff().unwrap_or(0.0);
+        if score_cutoff > 1.0 {
+            return args.score_cutoff.score(0.0);
+        }
+
+        let mut tokens_s2 = SplittedSentence::new_sorted(s2);
+        let s2_sorted = tokens_s2.join();
+        tokens_s2.dedupe();
+
+        let set_score = token_set_ratio_impl(
+            &set_decomposition(&self.tokens_s1, &tokens_s2),
+            score_cutoff,
+        );
+        if set_score == 1.0 {
+            return args.score_cutoff.score(set_score);
+        }
+
+        let sort_score = self
+            .scorer
+            .similarity_with_args(
+                s2_sorted,
+                &Args::default().score_cutoff(score_cutoff.max(set_score)),
+            )
+            .unwrap_or(0.0);
+        args.score_cutoff.score(set_score.max(sort_score))
+    }
+}
+
+/// Helper method that returns the maximum of [`partial_token_set_ratio`] and
+/// [`partial_token_sort_ratio`]
+///
+/// This is faster than calculating both of them separately.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// assert_eq!(
+///     1.0,
+///     fuzz::partial_token_ratio(
+///         "fuzzy was a bear".chars(),
+///         "fuzzy fuzzy was a bear".chars()
+///     )
+/// );
+/// ```
+pub fn partial_token_ratio<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    partial_token_ratio_with_args(s1, s2, &Args::default())
+}
+
+pub fn partial_token_ratio_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
+    s1: Iter1,
+    s2: Iter2,
+    args: &Args<f64, CutoffType, ProcessorType>,
+) -> CutoffType::Output
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    CutoffType: SimilarityCutoff<f64> + Copy,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+{
+    PartialTokenRatioBatchComparator::new(args.processor.process(s1.into_iter()))
+        .similarity_with_args(
+            args.processor.process(s2.into_iter()),
+            &args.without_processor(),
+        )
+}
+
+/// `One x Many` comparisons using `partial_token_ratio`
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// let scorer = fuzz::PartialTokenRatioBatchComparator::new("fuzzy was a bear".chars());
+/// assert_eq!(1.0, scorer.similarity("fuzzy fuzzy was a bear".chars()));
+/// ```
+#[derive(Clone)]
+pub struct PartialTokenRatioBatchComparator<Elem1> {
+    tokens_s1: SplittedSentence<Elem1>,
+    s1_word_count: usize,
+    scorer: PartialRatioBatchComparator<TokenElem<Elem1>>,
+}
+
+impl<Elem1> PartialTokenRatioBatchComparator<Elem1>
+where
+    Elem1: HashableChar + Copy,
+{
+    pub fn new<Iter1>(s1: Iter1) -> Self
+    where
+        Iter1: IntoIterator<Item = Elem1>,
+        Iter1::IntoIter: Clone,
+    {
+        let mut tokens_s1 = SplittedSentence::new_sorted(s1);
+        let s1_word_count = tokens_s1.word_count();
+        let scorer = PartialRatioBatchComparator::new(tokens_s1.join());
+        tokens_s1.dedupe();
+        Self {
+            tokens_s1,
+            s1_word_count,
+            scorer,
+        }
+    }
+
+    /// Similarity calculated similar to [`partial_token_ratio`]
+    pub fn similarity<Iter2>(&self, s2: Iter2) -> f64
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.similarity_with_args(s2, &Args::default())
+    }
+
+    pub fn similarity_with_args<Iter2, CutoffType>(
+        &self,
+        s2: Iter2,
+        args: &Args<f64, CutoffType>,
+    ) -> CutoffType::Output
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+        CutoffType: SimilarityCutoff<f64>,
+    {
+        let mut score_cutoff = args.score_cutoff.cutoff().unwrap_or(0.0);
+        if score_cutoff > 1.0 {
+            return args.score_cutoff.score(0.0);
+        }
+
+        let mut tokens_s2 = SplittedSentence::new_sorted(s2);
+        let s2_sorted = tokens_s2.join();
+        let s2_word_count = tokens_s2.word_count();
+        tokens_s2.dedupe();
+
+        let decomposition = set_decomposition(&self.tokens_s1, &tokens_s2);
+
+        // exit early when there is a common word in both sequences
+        if !decomposition.intersection.is_empty() {
+            return args.score_cutoff.score(1.0);
+        }
+
+        let result = self
+            .scorer
+            .similarity_with_args(s2_sorted, &Args::default().score_cutoff(score_cutoff))
+            .unwrap_or(0.0);
+
+        // do not calculate the same partial_ratio twice
+        if self.s1_word_count == decomposition.difference_ab.word_count()
+            && s2_word_count == decomposition.difference_ba.word_count()
+        {
+            return args.score_cutoff.score(result);
+        }
+
+        score_cutoff = score_cutoff.max(result);
+        let diff_score = partial_ratio_with_args(
+            decomposition.difference_ab.join(),
+            decomposition.difference_ba.join(),
+            &Args::default().score_cutoff(score_cutoff),
+        )
+        .unwrap_or(0.0);
+        args.score_cutoff.score(result.max(diff_score))
+    }
+}
+
+/// combines the sub scorers of `wratio` based on the length ratio of the sequences
+fn wratio_impl(
+    len1: usize,
+    len2: usize,
+    mut score_cutoff: f64,
+    ratio: impl FnOnce(f64) -> f64,
+    token_ratio: impl FnOnce(f64) -> f64,
+    partial_ratio: impl FnOnce(f64) -> f64,
+    partial_token_ratio: impl FnOnce(f64) -> f64,
+) -> f64 {
+    const UNBASE_SCALE: f64 = 0.95;
+
+    if score_cutoff > 1.0 || len1 == 0 || len2 == 0 {
+        return 0.0;
+    }
+
+    let len_ratio = if len1 > len2 {
+        len1 as f64 / len2 as f64
+    } else {
+        len2 as f64 / len1 as f64
+    };
+
+    let mut end_ratio = ratio(score_cutoff);
+    if len_ratio < 1.5 {
+        score_cutoff = score_cutoff.max(end_ratio) / UNBASE_SCALE;
+        return end_ratio.max(token_ratio(score_cutoff) * UNBASE_SCALE);
+    }
+
+    let partial_scale = if len_ratio < 8.0 { 0.9 } else { 0.6 };
+
+    score_cutoff = score_cutoff.max(end_ratio) / partial_scale;
+    end_ratio = end_ratio.max(partial_ratio(score_cutoff) * partial_scale);
+
+    score_cutoff = score_cutoff.max(end_ratio) / UNBASE_SCALE;
+    end_ratio.max(partial_token_ratio(score_cutoff) * UNBASE_SCALE * partial_scale)
+}
+
+/// Calculates a weighted ratio based on the other ratio algorithms
+///
+/// For sequences of a similar length this is the best of [`ratio`] and a scaled down
+/// [`token_ratio`]. When one of the sequences is a lot longer, the partial variants
+/// [`partial_ratio`] and [`partial_token_ratio`] are used instead and scaled down based
+/// on the length ratio.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// /// score is 0.855
+/// let score = fuzz::wratio("this is a test".chars(), "this is a new test!!!".chars());
+/// ```
+pub fn wratio<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+{
+    wratio_with_args(s1, s2, &Args::default())
 }
 
-/// Returns a simple ratio between two strings or `None` if `ratio < score_cutoff`
+pub fn wratio_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
+    s1: Iter1,
+    s2: Iter2,
+    args: &Args<f64, CutoffType, ProcessorType>,
+) -> CutoffType::Output
+where
+    Iter1: IntoIterator,
+    Iter1::IntoIter: DoubleEndedIterator + Clone,
+    Iter2: IntoIterator,
+    Iter2::IntoIter: DoubleEndedIterator + Clone,
+    Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+    Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    CutoffType: SimilarityCutoff<f64>,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
+{
+    let s1: Vec<Iter1::Item> = args.processor.process(s1.into_iter()).collect();
+    let s2: Vec<Iter2::Item> = args.processor.process(s2.into_iter()).collect();
+
+    let score = wratio_impl(
+        s1.len(),
+        s2.len(),
+        args.score_cutoff.cutoff().unwrap_or(0.0),
+        |cutoff| {
+            ratio_with_args(
+                s1.iter().copied(),
+                s2.iter().copied(),
+                &Args::default().score_cutoff(cutoff),
+            )
+            .unwrap_or(0.0)
+        },
+        |cutoff| {
+            token_ratio_with_args(
+                s1.iter().copied(),
+                s2.iter().copied(),
+                &Args::default().score_cutoff(cutoff),
+            )
+            .unwrap_or(0.0)
+        },
+        |cutoff| {
+            partial_ratio_with_args(
+                s1.iter().copied(),
+                s2.iter().copied(),
+                &Args::default().score_cutoff(cutoff),
+            )
+            .unwrap_or(0.0)
+        },
+        |cutoff| {
+            partial_token_ratio_with_args(
+                s1.iter().copied(),
+                s2.iter().copied(),
+                &Args::default().score_cutoff(cutoff),
+            )
+            .unwrap_or(0.0)
+        },
+    );
+    args.score_cutoff.score(score)
+}
+
+/// `One x Many` comparisons using `wratio`
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::fuzz;
+///
+/// let scorer = fuzz::WRatioBatchComparator::new("this is a test".chars());
+/// /// score is 0.855
+/// let score = scorer.similarity("this is a new test!!!".chars());
+/// ```
+#[derive(Clone)]
+pub struct WRatioBatchComparator<Elem1> {
+    len1: usize,
+    ratio: RatioBatchComparator<Elem1>,
+    token_ratio: TokenRatioBatchComparator<Elem1>,
+    partial_ratio: PartialRatioBatchComparator<Elem1>,
+    partial_token_ratio: PartialTokenRatioBatchComparator<Elem1>,
+}
+
+impl<Elem1> WRatioBatchComparator<Elem1>
+where
+    Elem1: HashableChar + Copy,
+{
+    pub fn new<Iter1>(s1: Iter1) -> Self
+    where
+        Iter1: IntoIterator<Item = Elem1>,
+        Iter1::IntoIter: Clone,
+    {
+        let s1: Vec<Elem1> = s1.into_iter().collect();
+        Self {
+            len1: s1.len(),
+            ratio: RatioBatchComparator::new(s1.iter().copied()),
+            token_ratio: TokenRatioBatchComparator::new(s1.iter().copied()),
+            partial_ratio: PartialRatioBatchComparator::new(s1.iter().copied()),
+            partial_token_ratio: PartialTokenRatioBatchComparator::new(s1.iter().copied()),
+        }
+    }
+
+    /// Similarity calculated similar to [`wratio`]
+    pub fn similarity<Iter2>(&self, s2: Iter2) -> f64
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        self.similarity_with_args(s2, &Args::default())
+    }
+
+    pub fn similarity_with_args<Iter2, CutoffType>(
+        &self,
+        s2: Iter2,
+        args: &Args<f64, CutoffType>,
+    ) -> CutoffType::Output
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+        CutoffType: SimilarityCutoff<f64>,
+    {
+        let s2: Vec<Iter2::Item> = s2.into_iter().collect();
+
+        let score = wratio_impl(
+            self.len1,
+            s2.len(),
+            args.score_cutoff.cutoff().unwrap_or(0.0),
+            |cutoff| {
+                self.ratio
+                    .similarity_with_args(s2.iter().copied(), &Args::default().score_cutoff(cutoff))
+                    .unwrap_or(0.0)
+            },
+            |cutoff| {
+                self.token_ratio
+                    .similarity_with_args(s2.iter().copied(), &Args::default().score_cutoff(cutoff))
+                    .unwrap_or(0.0)
+            },
+            |cutoff| {
+                self.partial_ratio
+                    .similarity_with_args(s2.iter().copied(), &Args::default().score_cutoff(cutoff))
+                    .unwrap_or(0.0)
+            },
+            |cutoff| {
+                self.partial_token_ratio
+                    .similarity_with_args(s2.iter().copied(), &Args::default().score_cutoff(cutoff))
+                    .unwrap_or(0.0)
+            },
+        );
+        args.score_cutoff.score(score)
+    }
+}
+
+/// Calculates a quick ratio between two sequences using [`ratio`]
+///
+/// In contrast to [`ratio`] this returns 0.0 when one of the sequences is empty.
 ///
 /// # Example
 /// ```
 /// use rapidfuzz::fuzz;
+///
 /// /// score is 0.9655
-/// let score = fuzz::ratio("this is a test".chars(), "this is a test!".chars());
+/// let score = fuzz::qratio("this is a test".chars(), "this is a test!".chars());
+/// assert_eq!(0.0, fuzz::qratio("".chars(), "".chars()));
 /// ```
-///
-pub fn ratio<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
+pub fn qratio<Iter1, Iter2>(s1: Iter1, s2: Iter2) -> f64
 where
     Iter1: IntoIterator,
     Iter1::IntoIter: DoubleEndedIterator + Clone,
@@ -54,13 +1764,13 @@ where
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
 {
-    ratio_with_args(s1, s2, &Args::default())
+    qratio_with_args(s1, s2, &Args::default())
 }
 
-pub fn ratio_with_args<Iter1, Iter2, CutoffType>(
+pub fn qratio_with_args<Iter1, Iter2, CutoffType, ProcessorType>(
     s1: Iter1,
     s2: Iter2,
-    args: &Args<f64, CutoffType>,
+    args: &Args<f64, CutoffType, ProcessorType>,
 ) -> CutoffType::Output
 where
     Iter1: IntoIterator,
@@ -69,37 +1779,36 @@ where
     Iter2::IntoIter: DoubleEndedIterator + Clone,
     Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
     Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
-    CutoffType: SimilarityCutoff<f64>,
+    CutoffType: SimilarityCutoff<f64> + Copy,
+    ProcessorType: Processor<Iter1::IntoIter> + Processor<Iter2::IntoIter>,
 {
-    let s1_iter = s1.into_iter();
-    let s2_iter = s2.into_iter();
-    args.score_cutoff
-        .score(indel::IndividualComparator {}._normalized_similarity(
-            s1_iter.clone(),
-            s1_iter.count(),
-            s2_iter.clone(),
-            s2_iter.count(),
-            args.score_cutoff.cutoff(),
-            args.score_hint,
-        ))
+    let s1_iter = args.processor.process(s1.into_iter());
+    let s2_iter = args.processor.process(s2.into_iter());
+    if s1_iter.clone().next().is_none() || s2_iter.clone().next().is_none() {
+        return args.score_cutoff.score(0.0);
+    }
+
+    ratio_with_args(s1_iter, s2_iter, &args.without_processor())
 }
 
-/// `One x Many` comparisons using `ratio`
+/// `One x Many` comparisons using `qratio`
 ///
 /// # Examples
 ///
 /// ```
 /// use rapidfuzz::fuzz;
 ///
-/// let scorer = fuzz::RatioBatchComparator::new("this is a test".chars());
+/// let scorer = fuzz::QRatioBatchComparator::new("this is a test".chars());
 /// /// score is 0.9655
 /// let score = scorer.similarity("this is a test!".chars());
 /// ```
-pub struct RatioBatchComparator<Elem1> {
-    scorer: indel::BatchComparator<Elem1>,
+#[derive(Clone)]
+pub struct QRatioBatchComparator<Elem1> {
+    len1: usize,
+    scorer: RatioBatchComparator<Elem1>,
 }
 
-impl<Elem1> RatioBatchComparator<Elem1>
+impl<Elem1> QRatioBatchComparator<Elem1>
 where
     Elem1: HashableChar + Clone,
 {
@@ -108,12 +1817,14 @@ where
         Iter1: IntoIterator<Item = Elem1>,
         Iter1::IntoIter: Clone,
     {
+        let s1_iter = s1.into_iter();
         Self {
-            scorer: indel::BatchComparator::new(s1),
+            len1: s1_iter.clone().count(),
+            scorer: RatioBatchComparator::new(s1_iter),
         }
     }
 
-    /// Similarity calculated similar to [`ratio`]
+    /// Similarity calculated similar to [`qratio`]
     pub fn similarity<Iter2>(&self, s2: Iter2) -> f64
     where
         Iter2: IntoIterator,
@@ -137,27 +1848,37 @@ where
         CutoffType: SimilarityCutoff<f64>,
     {
         let s2_iter = s2.into_iter();
-        args.score_cutoff
-            .score(self.scorer.scorer._normalized_similarity(
-                self.scorer.scorer.s1.iter().copied(),
-                self.scorer.scorer.s1.len(),
-                s2_iter.clone(),
-                s2_iter.count(),
-                args.score_cutoff.cutoff(),
-                args.score_hint,
-            ))
+        if self.len1 == 0 || s2_iter.clone().next().is_none() {
+            return args.score_cutoff.score(0.0);
+        }
+
+        self.scorer.similarity_with_args(s2_iter, args)
     }
 }
 
+impl_batch_metric!(
+    RatioBatchComparator,
+    PartialRatioBatchComparator,
+    TokenSortRatioBatchComparator,
+    PartialTokenSortRatioBatchComparator,
+    TokenSetRatioBatchComparator,
+    PartialTokenSetRatioBatchComparator,
+    TokenRatioBatchComparator,
+    PartialTokenRatioBatchComparator,
+    WRatioBatchComparator,
+    QRatioBatchComparator,
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::DefaultProcessor;
 
     static S1: &str = "new york mets";
     static S3: &str = "the wonderful new york mets";
-    //static S4: &str = "new york mets vs atlanta braves";
-    //static S5: &str = "atlanta braves vs new york mets";
-    //static S7: &str = "new york city mets - atlanta braves";
+    static S4: &str = "new york mets vs atlanta braves";
+    static S5: &str = "atlanta braves vs new york mets";
+    static S7: &str = "new york city mets - atlanta braves";
     // test silly corner cases
     static S8: &str = "{";
     static S9: &str = "{a";
@@ -202,14 +1923,354 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ratio_batch_comparator() {
+        // the Indel based ratio differs from the normalized LCS similarity
+        // whenever the lengths differ, so this would catch the wrong metric
+        let scorer = RatioBatchComparator::new(S1.chars());
+        assert_delta!(
+            Some(ratio(S1.chars(), S3.chars())),
+            Some(scorer.similarity(S3.chars()))
+        );
+        assert_delta!(Some(0.65), Some(scorer.similarity(S3.chars())));
+        assert_delta!(
+            Some(0.65),
+            scorer.similarity_with_args(S3.chars(), &Args::default().score_cutoff(0.6))
+        );
+        assert_eq!(
+            None,
+            scorer.similarity_with_args(S3.chars(), &Args::default().score_cutoff(0.7))
+        );
+    }
+
+    #[test]
+    fn test_batch_metric() {
+        let scorer = RatioBatchComparator::new(S1.chars());
+        assert_delta!(
+            Some(0.65),
+            BatchMetric::similarity(&scorer, S3.chars(), Some(0.6))
+        );
+        assert_eq!(
+            None,
+            BatchMetric::similarity(&scorer, S3.chars(), Some(0.7))
+        );
+        assert_delta!(
+            Some(0.35),
+            BatchMetric::normalized_distance(&scorer, S3.chars(), Some(0.4))
+        );
+        assert_eq!(None, BatchMetric::distance(&scorer, S3.chars(), Some(0.3)));
+
+        let scorer = WRatioBatchComparator::new(S4.chars());
+        assert_delta!(
+            Some(wratio(S4.chars(), S5.chars())),
+            BatchMetric::similarity(&scorer, S5.chars(), None)
+        );
+        assert!(<WRatioBatchComparator<char> as BatchMetric<char>>::is_better(0.9, 0.5));
+    }
+
     #[test]
     fn test_partial_ratio() {
-        //assert_delta!(Some(1.0), partial_ratio(S1.chars(), S1.chars(), None, None));
+        assert_delta!(Some(1.0), Some(partial_ratio(S1.chars(), S1.chars())));
         assert_delta!(
             Some(0.65),
             Some(ratio_with_args(S1.chars(), S3.chars(), &Args::default()))
         );
-        //assert_delta!(Some(1.0), partial_ratio(S1.chars(), S3.chars(), None, None));
+        assert_delta!(
+            Some(0.65),
+            Some(RatioBatchComparator::new(S1.chars()).similarity(S3.chars()))
+        );
+        assert_delta!(Some(1.0), Some(partial_ratio(S1.chars(), S3.chars())));
+        assert_delta!(Some(1.0), Some(partial_ratio(S3.chars(), S1.chars())));
+        assert_delta!(
+            Some(1.0),
+            Some(partial_ratio(
+                "physics 2 vid".chars(),
+                "study physics physics 2 video".chars()
+            ))
+        );
+        assert_delta!(
+            Some(0.3333),
+            Some(partial_ratio("001".bytes(), "220222".bytes()))
+        );
+
+        let scorer = PartialRatioBatchComparator::new(S1.chars());
+        assert_delta!(Some(1.0), Some(scorer.similarity(S3.chars())));
+        assert_delta!(
+            Some(1.0),
+            scorer.similarity_with_args(S3.chars(), &Args::default().score_cutoff(0.9))
+        );
+    }
+
+    #[test]
+    fn test_partial_ratio_empty() {
+        assert_delta!(Some(1.0), Some(partial_ratio("".chars(), "".chars())));
+        assert_delta!(Some(0.0), Some(partial_ratio("test".chars(), "".chars())));
+        assert_delta!(Some(0.0), Some(partial_ratio("".chars(), "test".chars())));
+    }
+
+    #[test]
+    fn test_partial_ratio_score_cutoff() {
+        let score = partial_ratio("a certain string".chars(), "cetain".chars());
+        assert_delta!(Some(0.8333), Some(score));
+        assert_eq!(
+            None,
+            partial_ratio_with_args(
+                "a certain string".chars(),
+                "cetain".chars(),
+                &Args::default().score_cutoff(score + 0.0001)
+            )
+        );
+        assert_delta!(
+            Some(score),
+            partial_ratio_with_args(
+                "a certain string".chars(),
+                "cetain".chars(),
+                &Args::default().score_cutoff(score - 0.0001)
+            )
+        );
+    }
+
+    #[test]
+    fn test_partial_ratio_alignment() {
+        let res = partial_ratio_alignment("a certain string".chars(), "cetain".chars());
+        assert_delta!(Some(0.8333), Some(res.score));
+        assert_eq!(
+            (2, 8, 0, 6),
+            (res.src_start, res.src_end, res.dest_start, res.dest_end)
+        );
+
+        let res = partial_ratio_alignment("cetain".chars(), "a certain string".chars());
+        assert_delta!(Some(0.8333), Some(res.score));
+        assert_eq!(
+            (0, 6, 2, 8),
+            (res.src_start, res.src_end, res.dest_start, res.dest_end)
+        );
+
+        let res = partial_ratio_alignment(S1.chars(), S3.chars());
+        assert_delta!(Some(1.0), Some(res.score));
+        assert_eq!(
+            (0, 13, 14, 27),
+            (res.src_start, res.src_end, res.dest_start, res.dest_end)
+        );
+
+        assert_eq!(
+            None,
+            partial_ratio_alignment_with_args(
+                "a certain string".chars(),
+                "cetain".chars(),
+                &Args::default().score_cutoff(0.9)
+            )
+        );
+
+        let scorer = PartialRatioBatchComparator::new("cetain".chars());
+        let res = scorer
+            .alignment_with_args(
+                "a certain string".chars(),
+                &Args::default().score_cutoff(0.8),
+            )
+            .unwrap();
+        assert_eq!(
+            (0, 6, 2, 8),
+            (res.src_start, res.src_end, res.dest_start, res.dest_end)
+        );
+    }
+
+    #[test]
+    fn test_token_sort_ratio() {
+        assert_delta!(Some(1.0), Some(token_sort_ratio(S1.chars(), S1.chars())));
+        assert_delta!(Some(1.0), Some(token_sort_ratio(S4.chars(), S5.chars())));
+        assert_delta!(
+            Some(0.8421),
+            Some(token_sort_ratio(
+                "fuzzy was a bear".chars(),
+                "fuzzy fuzzy was a bear".chars()
+            ))
+        );
+        assert_delta!(
+            Some(0.8421),
+            Some(
+                TokenSortRatioBatchComparator::new("fuzzy was a bear".bytes())
+                    .similarity("fuzzy fuzzy was a bear".bytes())
+            )
+        );
+        assert_delta!(
+            Some(1.0),
+            Some(partial_token_sort_ratio(S4.chars(), S5.chars()))
+        );
+        assert_delta!(
+            Some(1.0),
+            Some(partial_token_sort_ratio("york new".chars(), S1.chars()))
+        );
+    }
+
+    #[test]
+    fn test_token_set_ratio() {
+        assert_delta!(Some(1.0), Some(token_set_ratio(S4.chars(), S5.chars())));
+        assert_delta!(
+            Some(1.0),
+            Some(token_set_ratio(
+                "fuzzy was a bear".chars(),
+                "fuzzy fuzzy was a bear".chars()
+            ))
+        );
+        assert_delta!(
+            Some(1.0),
+            Some(partial_token_set_ratio(S4.chars(), S7.chars()))
+        );
+        assert_delta!(Some(0.0), Some(token_set_ratio("".chars(), "".chars())));
+        assert_delta!(
+            Some(0.0),
+            Some(partial_token_set_ratio("".chars(), "".chars()))
+        );
+    }
+
+    #[test]
+    fn test_token_ratio() {
+        assert_delta!(
+            Some(1.0),
+            Some(token_ratio(
+                "fuzzy wuzzy was a bear".chars(),
+                "wuzzy fuzzy was a bear".chars()
+            ))
+        );
+        assert_delta!(Some(1.0), Some(partial_token_ratio(S4.chars(), S7.chars())));
+
+        let pairs = [
+            (S1, S3),
+            (S4, S7),
+            ("fuzzy was a bear", "fuzzy fuzzy was a bear"),
+            ("new york yankees", "mets of new york"),
+            ("tonight", "to night"),
+            ("", "test"),
+        ];
+        for (a, b) in pairs {
+            assert_delta!(
+                Some(
+                    token_set_ratio(a.chars(), b.chars())
+                        .max(token_sort_ratio(a.chars(), b.chars()))
+                ),
+                Some(token_ratio(a.chars(), b.chars()))
+            );
+            assert_delta!(
+                Some(
+                    partial_token_set_ratio(a.chars(), b.chars())
+                        .max(partial_token_sort_ratio(a.chars(), b.chars()))
+                ),
+                Some(partial_token_ratio(a.chars(), b.chars()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_token_score_cutoff() {
+        type ScorerFn = fn(&str, &str, f64) -> Option<f64>;
+        let scorers: [ScorerFn; 6] = [
+            |a, b, c| {
+                token_sort_ratio_with_args(a.chars(), b.chars(), &Args::default().score_cutoff(c))
+            },
+            |a, b, c| {
+                token_set_ratio_with_args(a.chars(), b.chars(), &Args::default().score_cutoff(c))
+            },
+            |a, b, c| token_ratio_with_args(a.chars(), b.chars(), &Args::default().score_cutoff(c)),
+            |a, b, c| {
+                partial_token_sort_ratio_with_args(
+                    a.chars(),
+                    b.chars(),
+                    &Args::default().score_cutoff(c),
+                )
+            },
+            |a, b, c| {
+                partial_token_set_ratio_with_args(
+                    a.chars(),
+                    b.chars(),
+                    &Args::default().score_cutoff(c),
+                )
+            },
+            |a, b, c| {
+                partial_token_ratio_with_args(
+                    a.chars(),
+                    b.chars(),
+                    &Args::default().score_cutoff(c),
+                )
+            },
+        ];
+
+        for scorer in scorers {
+            for (a, b) in [
+                ("South Korea", "North Korea"),
+                ("new york yankees", "mets of new york"),
+            ] {
+                let score = scorer(a, b, 0.0).unwrap();
+                assert_eq!(None, scorer(a, b, score + 0.0001));
+                assert_delta!(Some(score), scorer(a, b, score - 0.0001));
+            }
+        }
+    }
+
+    #[test]
+    fn test_wratio() {
+        assert_delta!(Some(1.0), Some(wratio(S1.chars(), S1.chars())));
+        assert_delta!(Some(0.0), Some(wratio("".chars(), "".chars())));
+        assert_delta!(Some(0.0), Some(wratio(S1.chars(), "".chars())));
+        assert_delta!(
+            Some(0.855),
+            Some(wratio(
+                "this is a test".chars(),
+                "this is a new test!!!".chars()
+            ))
+        );
+        assert_delta!(
+            Some(0.855),
+            Some(
+                WRatioBatchComparator::new("this is a test".bytes())
+                    .similarity("this is a new test!!!".bytes())
+            )
+        );
+
+        let pairs = [
+            (S1, S3),
+            (S4, S5),
+            (S4, S7),
+            ("fuzzy was a bear", "fuzzy fuzzy was a bear"),
+            (
+                "new york",
+                "the new york mets vs the atlanta braves in atlanta",
+            ),
+        ];
+        for (a, b) in pairs {
+            let score = wratio(a.chars(), b.chars());
+            let scorer = WRatioBatchComparator::new(a.chars());
+            assert_delta!(Some(score), Some(scorer.similarity(b.chars())));
+            assert_eq!(
+                None,
+                wratio_with_args(
+                    a.chars(),
+                    b.chars(),
+                    &Args::default().score_cutoff(score + 0.0001)
+                )
+            );
+            assert_delta!(
+                Some(score),
+                scorer
+                    .similarity_with_args(b.chars(), &Args::default().score_cutoff(score - 0.0001))
+            );
+        }
+    }
+
+    #[test]
+    fn test_qratio() {
+        assert_delta!(Some(1.0), Some(qratio(S1.chars(), S1.chars())));
+        assert_delta!(Some(0.0), Some(qratio("".chars(), "".chars())));
+        assert_delta!(Some(0.0), Some(qratio(S1.chars(), "".chars())));
+        assert_delta!(Some(0.65), Some(qratio(S1.chars(), S3.chars())));
+
+        let scorer = QRatioBatchComparator::new(S1.chars());
+        assert_delta!(Some(0.65), Some(scorer.similarity(S3.chars())));
+        assert_delta!(Some(0.0), Some(scorer.similarity("".chars())));
+        assert_eq!(
+            None,
+            scorer.similarity_with_args(S3.chars(), &Args::default().score_cutoff(0.66))
+        );
     }
 
     #[test]
@@ -299,4 +2360,41 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn processor() {
+        let args = Args::default().processor(DefaultProcessor);
+        assert_delta!(
+            Some(1.0),
+            Some(ratio_with_args("New York Mets!".chars(), S1.chars(), &args))
+        );
+        assert_delta!(
+            Some(1.0),
+            Some(token_sort_ratio_with_args(
+                "Mets, New-York".chars(),
+                S1.chars(),
+                &args
+            ))
+        );
+        assert_delta!(
+            Some(1.0),
+            Some(token_set_ratio_with_args(
+                "Mets, New-York".chars(),
+                S1.chars(),
+                &args
+            ))
+        );
+        assert_delta!(
+            Some(1.0),
+            Some(wratio_with_args("NEW YORK METS".chars(), S1.chars(), &args))
+        );
+        assert_delta!(
+            Some(0.0),
+            Some(qratio_with_args("!!!".chars(), S1.chars(), &args))
+        );
+        assert_eq!(
+            None,
+            qratio_with_args("!!!".chars(), S1.chars(), &args.score_cutoff(0.1))
+        );
+    }
 }
@@ -0,0 +1,128 @@
+//! Index
+//!
+//! Data structures to search a large collection of sequences for the ones
+//! close to a query, without comparing the query with each of them.
+//!
+//! The indexes are generic over the metric used to compare the sequences. The metric
+//! is selected using the marker types of the [`distance`](crate::distance) modules,
+//! e.g. [`Levenshtein`](crate::distance::levenshtein::Levenshtein).
+//!
+//! Results are returned as `(index, sequence, distance)`, where `index` is the position
+//! at which the sequence was inserted into the index.
+//!
+//! ```
+//! use rapidfuzz::distance::levenshtein::Levenshtein;
+//! use rapidfuzz::index::BkTree;
+//!
+//! let tree = BkTree::build(Levenshtein, ["apple", "banana", "cherry"].map(str::chars));
+//! let matches = tree.find_within("bananas".chars(), 1);
+//! assert_eq!(1, matches.len());
+//! assert_eq!((1, 1), (matches[0].0, matches[0].2));
+//! ```
+
+mod bk_tree;
+mod deletion_index;
+mod qgram_index;
+mod vp_tree;
+
+pub use bk_tree::BkTree;
+pub use deletion_index::DeletionIndex;
+pub use qgram_index::QGramIndex;
+pub use vp_tree::VpTree;
+
+use crate::distance::Metric;
+use crate::HashableChar;
+
+/// Distance between two sequences used by the indexes
+///
+/// This is implemented for every [`Metric`] using its raw distance and for
+/// [`Normalized`] metrics using their normalized distance. For the indexes to
+/// find all matches, the distance has to be a metric, so it has to satisfy the
+/// triangle inequality. This is not the case e.g. for the
+/// [`Osa`](crate::distance::osa::Osa) distance. The
+/// [`Hamming`](crate::distance::hamming::Hamming) distance pads sequences of
+/// different length, so it remains a metric.
+pub trait DistanceMetric<Elem> {
+    /// type of the distance
+    type Output: Copy + PartialOrd;
+
+    /// Returns the distance between `s1` and `s2` or `None` if it is larger than `score_cutoff`
+    ///
+    /// Without a `score_cutoff` the distance is always returned.
+    fn distance(
+        &self,
+        s1: &[Elem],
+        s2: &[Elem],
+        score_cutoff: Option<Self::Output>,
+    ) -> Option<Self::Output>;
+}
+
+impl<M, Elem> DistanceMetric<Elem> for M
+where
+    M: Metric,
+    Elem: PartialEq + HashableChar + Copy,
+{
+    type Output = M::Output;
+
+    fn distance(
+        &self,
+        s1: &[Elem],
+        s2: &[Elem],
+        score_cutoff: Option<M::Output>,
+    ) -> Option<M::Output> {
+        Metric::distance(self, s1.iter().copied(), s2.iter().copied(), score_cutoff)
+    }
+}
+
+/// Normalized distance of a metric in the range `[0.0, 1.0]`
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::indel::Indel;
+/// use rapidfuzz::index::{Normalized, VpTree};
+///
+/// let tree = VpTree::build(Normalized(Indel), ["apple", "banana"].map(str::chars));
+/// let nearest = tree.find_nearest("bananas".chars(), 1);
+/// assert_eq!(1, nearest[0].0);
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Normalized<M>(pub M);
+
+impl<M, Elem> DistanceMetric<Elem> for Normalized<M>
+where
+    M: Metric,
+    Elem: PartialEq + HashableChar + Copy,
+{
+    type Output = f64;
+
+    fn distance(&self, s1: &[Elem], s2: &[Elem], score_cutoff: Option<f64>) -> Option<f64> {
+        self.0
+            .normalized_distance(s1.iter().copied(), s2.iter().copied(), score_cutoff)
+    }
+}
+
+/// `(index, distance)` of all words within `max_distance` of `query`, sorted by distance and index
+#[cfg(test)]
+pub(crate) fn brute_force<M>(
+    metric: &M,
+    words: &[String],
+    query: &str,
+    max_distance: M::Output,
+) -> Vec<(usize, M::Output)>
+where
+    M: DistanceMetric<char>,
+{
+    let query: Vec<char> = query.chars().collect();
+    let mut results: Vec<(usize, M::Output)> = words
+        .iter()
+        .enumerate()
+        .filter_map(|(index, word)| {
+            let word: Vec<char> = word.chars().collect();
+            let dist = metric.distance(&word, &query, None).unwrap();
+            (dist <= max_distance).then_some((index, dist))
+        })
+        .collect();
+    results.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap().then(a.0.cmp(&b.0)));
+    results
+}
@@ -0,0 +1,282 @@
+use crate::index::DistanceMetric;
+use std::collections::BinaryHeap;
+
+#[derive(Clone)]
+struct Node<Elem> {
+    seq: Vec<Elem>,
+    /// `(distance, node)` for each child. All sequences in the subtree of a child
+    /// have the same distance to this node.
+    children: Vec<(usize, usize)>,
+    max_child_dist: usize,
+}
+
+/// BK-tree to search for sequences within a maximum distance of a query
+///
+/// The tree works with any metric implementing [`DistanceMetric`] with integer
+/// distances, e.g. Levenshtein, OSA, Damerau-Levenshtein, Indel, LCSseq and Hamming.
+/// During a search the maximum distance of interest is passed as `score_cutoff`
+/// to the metric, which allows it to exit early for sequences far away from the query.
+///
+/// The OSA distance does not satisfy the triangle inequality, so with it the tree
+/// can miss some matches. Use the Damerau-Levenshtein distance when all matches
+/// including transpositions are required.
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::levenshtein::Levenshtein;
+/// use rapidfuzz::index::BkTree;
+///
+/// let mut tree = BkTree::new(Levenshtein);
+/// tree.insert("book".chars());
+/// tree.insert("books".chars());
+/// tree.insert("cake".chars());
+/// tree.insert("boo".chars());
+///
+/// let matches: Vec<(usize, usize)> = tree
+///     .find_within("bo".chars(), 1)
+///     .into_iter()
+///     .map(|(index, _, dist)| (index, dist))
+///     .collect();
+/// assert_eq!(vec![(3, 1)], matches);
+///
+/// let nearest: Vec<(usize, usize)> = tree
+///     .find_nearest("bookk".chars(), 2)
+///     .into_iter()
+///     .map(|(index, _, dist)| (index, dist))
+///     .collect();
+/// assert_eq!(vec![(0, 1), (1, 1)], nearest);
+/// ```
+#[derive(Clone)]
+pub struct BkTree<Elem, M> {
+    metric: M,
+    nodes: Vec<Node<Elem>>,
+}
+
+impl<Elem, M> BkTree<Elem, M>
+where
+    M: DistanceMetric<Elem, Output = usize>,
+{
+    /// creates an empty tree using `metric` to compare sequences
+    pub fn new(metric: M) -> Self {
+        Self {
+            metric,
+            nodes: Vec::new(),
+        }
+    }
+
+    /// creates a tree containing all sequences in `seqs`
+    pub fn build<Iter, Seq>(metric: M, seqs: Iter) -> Self
+    where
+        Iter: IntoIterator<Item = Seq>,
+        Seq: IntoIterator<Item = Elem>,
+    {
+        let mut tree = Self::new(metric);
+        for seq in seqs {
+            tree.insert(seq);
+        }
+        tree
+    }
+
+    /// number of sequences in the tree
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// sequence inserted at `index`
+    pub fn get(&self, index: usize) -> Option<&[Elem]> {
+        self.nodes.get(index).map(|node| node.seq.as_slice())
+    }
+
+    fn full_distance(&self, s1: &[Elem], s2: &[Elem]) -> usize {
+        self.metric
+            .distance(s1, s2, None)
+            .expect("distance without score_cutoff")
+    }
+
+    /// inserts a sequence into the tree and returns its index
+    pub fn insert<Iter>(&mut self, s: Iter) -> usize
+    where
+        Iter: IntoIterator<Item = Elem>,
+    {
+        let seq: Vec<Elem> = s.into_iter().collect();
+        let index = self.nodes.len();
+
+        if index != 0 {
+            let mut current = 0;
+            loop {
+                let dist = self.full_distance(&self.nodes[current].seq, &seq);
+                let node = &mut self.nodes[current];
+                match node
+                    .children
+                    .iter()
+                    .find(|(child_dist, _)| *child_dist == dist)
+                {
+                    Some(&(_, child)) => current = child,
+                    None => {
+                        node.children.push((dist, index));
+                        node.max_child_dist = node.max_child_dist.max(dist);
+                        break;
+                    }
+                }
+            }
+        }
+
+        self.nodes.push(Node {
+            seq,
+            children: Vec::new(),
+            max_child_dist: 0,
+        });
+        index
+    }
+
+    /// Returns all sequences with a distance of at most `max_dist` to `query`
+    ///
+    /// The results are sorted by distance and then by index.
+    pub fn find_within<Iter>(&self, query: Iter, max_dist: usize) -> Vec<(usize, &[Elem], usize)>
+    where
+        Iter: IntoIterator<Item = Elem>,
+    {
+        let query: Vec<Elem> = query.into_iter().collect();
+        let mut results = Vec::new();
+        if self.nodes.is_empty() {
+            return results;
+        }
+
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            // children can only contain matches when the distance is below this
+            let cutoff = max_dist.saturating_add(node.max_child_dist);
+            let Some(dist) = self.metric.distance(&node.seq, &query, Some(cutoff)) else {
+                continue;
+            };
+
+            if dist <= max_dist {
+                results.push((index, node.seq.as_slice(), dist));
+            }
+            stack.extend(
+                node.children
+                    .iter()
+                    .filter(|(child_dist, _)| child_dist.abs_diff(dist) <= max_dist)
+                    .map(|&(_, child)| child),
+            );
+        }
+
+        results.sort_by_key(|&(index, _, dist)| (dist, index));
+        results
+    }
+
+    /// Returns the `limit` sequences closest to `query`
+    ///
+    /// The results are sorted by distance and then by index.
+    pub fn find_nearest<Iter>(&self, query: Iter, limit: usize) -> Vec<(usize, &[Elem], usize)>
+    where
+        Iter: IntoIterator<Item = Elem>,
+    {
+        let query: Vec<Elem> = query.into_iter().collect();
+        if self.nodes.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        // max-heap of the best `(dist, index)` found so far
+        let mut best: BinaryHeap<(usize, usize)> = BinaryHeap::with_capacity(limit + 1);
+        let radius = |best: &BinaryHeap<(usize, usize)>| match best.peek() {
+            Some(&(dist, _)) if best.len() == limit => dist,
+            _ => usize::MAX,
+        };
+
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let cutoff = radius(&best).checked_add(node.max_child_dist);
+            let Some(dist) = self.metric.distance(&node.seq, &query, cutoff) else {
+                continue;
+            };
+
+            best.push((dist, index));
+            if best.len() > limit {
+                best.pop();
+            }
+
+            // visit the children most likely to be close first, so the radius shrinks quickly
+            let radius = radius(&best);
+            let mut children: Vec<(usize, usize)> = node
+                .children
+                .iter()
+                .map(|&(child_dist, child)| (child_dist.abs_diff(dist), child))
+                .filter(|&(lower_bound, _)| lower_bound <= radius)
+                .collect();
+            children.sort_unstable_by(|a, b| b.cmp(a));
+            stack.extend(children.into_iter().map(|(_, child)| child));
+        }
+
+        best.into_sorted_vec()
+            .into_iter()
+            .map(|(dist, index)| (index, self.nodes[index].seq.as_slice(), dist))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::details::testing::Lcg;
+    use crate::distance::damerau_levenshtein::DamerauLevenshtein;
+    use crate::distance::hamming::Hamming;
+    use crate::distance::indel::Indel;
+    use crate::distance::lcs_seq::LcsSeq;
+    use crate::distance::levenshtein::Levenshtein;
+    use crate::index::brute_force;
+
+    fn check<M>(metric: M)
+    where
+        M: DistanceMetric<char, Output = usize>,
+    {
+        let words = Lcg::new(42).words(300, 3..=7);
+        let tree = BkTree::build(metric, words.iter().map(|word| word.chars()));
+        assert_eq!(words.len(), tree.len());
+
+        for query in ["abc", "dddd", "abcdab", "b"] {
+            for max_dist in 0..4 {
+                let found: Vec<(usize, usize)> = tree
+                    .find_within(query.chars(), max_dist)
+                    .into_iter()
+                    .map(|(index, _, dist)| (index, dist))
+                    .collect();
+                assert_eq!(brute_force(&tree.metric, &words, query, max_dist), found);
+            }
+
+            let expected = brute_force(&tree.metric, &words, query, usize::MAX);
+            for limit in [1, 5, 20] {
+                let found: Vec<(usize, usize)> = tree
+                    .find_nearest(query.chars(), limit)
+                    .into_iter()
+                    .map(|(index, _, dist)| (index, dist))
+                    .collect();
+                assert_eq!(expected[..limit].to_vec(), found);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        check(Levenshtein);
+        check(DamerauLevenshtein);
+        check(Indel);
+        check(LcsSeq);
+        check(Hamming);
+    }
+
+    #[test]
+    fn test_empty() {
+        let tree: BkTree<char, Levenshtein> = BkTree::new(Levenshtein);
+        assert!(tree.is_empty());
+        assert!(tree.find_within("abc".chars(), 2).is_empty());
+        assert!(tree.find_nearest("abc".chars(), 2).is_empty());
+    }
+}
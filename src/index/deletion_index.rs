@@ -0,0 +1,219 @@
+use crate::index::DistanceMetric;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Symmetric deletion index for fast dictionary lookups
+///
+/// For every inserted sequence all variants with up to `max_distance` deleted elements
+/// are stored. Two sequences with a Levenshtein or OSA distance of at most `k` share a
+/// variant with at most `k` deletions, so a lookup only has to generate the deletion
+/// variants of the query. The candidates found this way are verified using the metric,
+/// which receives the maximum distance as `score_cutoff`.
+///
+/// The number of stored variants grows quickly with `max_distance` and the length of
+/// the sequences, so this index is best suited for dictionaries of short words with a
+/// `max_distance` of up to 3.
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::osa::Osa;
+/// use rapidfuzz::index::DeletionIndex;
+///
+/// let index = DeletionIndex::build(
+///     Osa,
+///     2,
+///     [("house", 100), ("mouse", 20), ("hose", 50), ("horse", 70)]
+///         .map(|(word, freq)| (word.chars(), freq)),
+/// );
+///
+/// let suggestions: Vec<(&str, usize)> = index
+///     .lookup("hosue".chars(), 2)
+///     .into_iter()
+///     .map(|(index, _, dist)| (["house", "mouse", "hose", "horse"][index], dist))
+///     .collect();
+/// assert_eq!(
+///     vec![("house", 1), ("hose", 1), ("horse", 2), ("mouse", 2)],
+///     suggestions
+/// );
+/// ```
+#[derive(Clone)]
+pub struct DeletionIndex<Elem, M> {
+    metric: M,
+    max_distance: usize,
+    seqs: Vec<Vec<Elem>>,
+    frequencies: Vec<u64>,
+    deletes: HashMap<Vec<Elem>, Vec<usize>>,
+}
+
+/// all variants of `seq` with up to `max_distance` deleted elements including `seq` itself
+fn deletion_variants<Elem>(seq: &[Elem], max_distance: usize) -> HashSet<Vec<Elem>>
+where
+    Elem: Eq + Hash + Clone,
+{
+    let mut variants = HashSet::new();
+    variants.insert(seq.to_vec());
+
+    let mut level = vec![seq.to_vec()];
+    for _ in 0..max_distance {
+        let mut next = Vec::new();
+        for variant in &level {
+            for i in 0..variant.len() {
+                let mut deleted = variant.clone();
+                deleted.remove(i);
+                if !variants.contains(&deleted) {
+                    variants.insert(deleted.clone());
+                    next.push(deleted);
+                }
+            }
+        }
+        level = next;
+    }
+
+    variants
+}
+
+impl<Elem, M> DeletionIndex<Elem, M>
+where
+    Elem: Eq + Hash + Clone,
+    M: DistanceMetric<Elem, Output = usize>,
+{
+    /// creates an empty index supporting lookups with up to `max_distance` edits
+    pub fn new(metric: M, max_distance: usize) -> Self {
+        Self {
+            metric,
+            max_distance,
+            seqs: Vec::new(),
+            frequencies: Vec::new(),
+            deletes: HashMap::new(),
+        }
+    }
+
+    /// creates an index containing all `(sequence, frequency)` pairs in `seqs`
+    pub fn build<Iter, Seq>(metric: M, max_distance: usize, seqs: Iter) -> Self
+    where
+        Iter: IntoIterator<Item = (Seq, u64)>,
+        Seq: IntoIterator<Item = Elem>,
+    {
+        let mut index = Self::new(metric, max_distance);
+        for (seq, frequency) in seqs {
+            index.insert(seq, frequency);
+        }
+        index
+    }
+
+    /// number of sequences in the index
+    pub fn len(&self) -> usize {
+        self.seqs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seqs.is_empty()
+    }
+
+    /// maximum distance supported by [`DeletionIndex::lookup`]
+    pub fn max_distance(&self) -> usize {
+        self.max_distance
+    }
+
+    /// sequence inserted at `index`
+    pub fn get(&self, index: usize) -> Option<&[Elem]> {
+        self.seqs.get(index).map(Vec::as_slice)
+    }
+
+    /// frequency of the sequence inserted at `index`
+    pub fn frequency(&self, index: usize) -> Option<u64> {
+        self.frequencies.get(index).copied()
+    }
+
+    /// inserts a sequence with its frequency into the index and returns its index
+    pub fn insert<Iter>(&mut self, s: Iter, frequency: u64) -> usize
+    where
+        Iter: IntoIterator<Item = Elem>,
+    {
+        let seq: Vec<Elem> = s.into_iter().collect();
+        let index = self.seqs.len();
+        for variant in deletion_variants(&seq, self.max_distance) {
+            self.deletes.entry(variant).or_default().push(index);
+        }
+
+        self.seqs.push(seq);
+        self.frequencies.push(frequency);
+        index
+    }
+
+    /// Returns all sequences with a distance of at most `max_distance` to `query`
+    ///
+    /// `max_distance` is limited to the maximum distance the index was created with.
+    /// The results are sorted by distance, then by descending frequency and then by index.
+    pub fn lookup<Iter>(&self, query: Iter, max_distance: usize) -> Vec<(usize, &[Elem], usize)>
+    where
+        Iter: IntoIterator<Item = Elem>,
+    {
+        let query: Vec<Elem> = query.into_iter().collect();
+        let max_distance = max_distance.min(self.max_distance);
+
+        let mut candidates = HashSet::new();
+        for variant in deletion_variants(&query, max_distance) {
+            if let Some(indices) = self.deletes.get(&variant) {
+                candidates.extend(indices.iter().copied());
+            }
+        }
+
+        let mut results: Vec<(usize, &[Elem], usize)> = candidates
+            .into_iter()
+            .filter(|&index| self.seqs[index].len().abs_diff(query.len()) <= max_distance)
+            .filter_map(|index| {
+                let seq = self.seqs[index].as_slice();
+                self.metric
+                    .distance(seq, &query, Some(max_distance))
+                    .map(|dist| (index, seq, dist))
+            })
+            .collect();
+
+        results.sort_by_key(|&(index, _, dist)| (dist, Reverse(self.frequencies[index]), index));
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::details::testing::Lcg;
+    use crate::distance::levenshtein::Levenshtein;
+    use crate::distance::osa::Osa;
+    use crate::index::brute_force;
+
+    fn check<M>(metric: M)
+    where
+        M: DistanceMetric<char, Output = usize> + Clone,
+    {
+        let words = Lcg::new(3).words(300, 1..=7);
+        let index = DeletionIndex::build(
+            metric.clone(),
+            2,
+            words.iter().map(|word| (word.chars(), word.len() as u64)),
+        );
+
+        for query in ["abc", "dddd", "abcdab", "b", "badc"] {
+            for max_distance in 0..=3 {
+                let found: Vec<(usize, usize)> = index
+                    .lookup(query.chars(), max_distance)
+                    .into_iter()
+                    .map(|(index, _, dist)| (index, dist))
+                    .collect();
+
+                let mut expected = brute_force(&metric, &words, query, max_distance.min(2));
+                expected.sort_by_key(|&(index, dist)| (dist, Reverse(words[index].len()), index));
+                assert_eq!(expected, found);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        check(Levenshtein);
+        check(Osa);
+    }
+}
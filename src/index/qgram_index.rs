@@ -0,0 +1,258 @@
+use crate::index::DistanceMetric;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// Inverted index of q-grams to generate candidates for a query
+///
+/// A single edit operation changes at most `q` of the q-grams of a sequence. So two
+/// sequences with a Levenshtein distance of at most `k` share at least
+/// `max(len1, len2) - q + 1 - k * q` q-grams (q-gram count lemma). Together with the
+/// length difference, which can't exceed `k`, this filters out most sequences without
+/// calculating their distance. The remaining candidates are verified using the metric,
+/// which receives the maximum distance as `score_cutoff`.
+///
+/// The candidates are guaranteed to contain all matches as long as the metric is never
+/// smaller than the Levenshtein distance. This is the case for the Levenshtein, Indel
+/// and Hamming distance, but not for OSA or Damerau-Levenshtein.
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::levenshtein::Levenshtein;
+/// use rapidfuzz::index::QGramIndex;
+///
+/// let index = QGramIndex::build(
+///     Levenshtein,
+///     2,
+///     ["new york", "new jersey", "newark", "york"].map(str::chars),
+/// );
+///
+/// assert_eq!(vec![0], index.candidates("new yrok".chars(), 2));
+/// let matches: Vec<(usize, usize)> = index
+///     .find_within("new yrok".chars(), 2)
+///     .into_iter()
+///     .map(|(index, _, dist)| (index, dist))
+///     .collect();
+/// assert_eq!(vec![(0, 2)], matches);
+/// ```
+#[derive(Clone)]
+pub struct QGramIndex<Elem, M> {
+    metric: M,
+    q: usize,
+    seqs: Vec<Vec<Elem>>,
+    /// indices of the sequences grouped by their length
+    lengths: BTreeMap<usize, Vec<usize>>,
+    /// `(index, count)` of the sequences containing a q-gram
+    postings: HashMap<Vec<Elem>, Vec<(usize, usize)>>,
+}
+
+fn count_qgrams<Elem>(seq: &[Elem], q: usize) -> HashMap<&[Elem], usize>
+where
+    Elem: Eq + Hash,
+{
+    let mut counts = HashMap::new();
+    for qgram in seq.windows(q) {
+        *counts.entry(qgram).or_insert(0) += 1;
+    }
+    counts
+}
+
+impl<Elem, M> QGramIndex<Elem, M>
+where
+    Elem: Eq + Hash + Clone,
+    M: DistanceMetric<Elem, Output = usize>,
+{
+    /// creates an empty index using q-grams of length `q`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `q` is 0
+    pub fn new(metric: M, q: usize) -> Self {
+        assert!(q > 0, "q-grams need a length of at least 1");
+        Self {
+            metric,
+            q,
+            seqs: Vec::new(),
+            lengths: BTreeMap::new(),
+            postings: HashMap::new(),
+        }
+    }
+
+    /// creates an index containing all sequences in `seqs`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `q` is 0
+    pub fn build<Iter, Seq>(metric: M, q: usize, seqs: Iter) -> Self
+    where
+        Iter: IntoIterator<Item = Seq>,
+        Seq: IntoIterator<Item = Elem>,
+    {
+        let mut index = Self::new(metric, q);
+        for seq in seqs {
+            index.insert(seq);
+        }
+        index
+    }
+
+    /// number of sequences in the index
+    pub fn len(&self) -> usize {
+        self.seqs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seqs.is_empty()
+    }
+
+    /// sequence inserted at `index`
+    pub fn get(&self, index: usize) -> Option<&[Elem]> {
+        self.seqs.get(index).map(Vec::as_slice)
+    }
+
+    /// inserts a sequence into the index and returns its index
+    pub fn insert<Iter>(&mut self, s: Iter) -> usize
+    where
+        Iter: IntoIterator<Item = Elem>,
+    {
+        let seq: Vec<Elem> = s.into_iter().collect();
+        let index = self.seqs.len();
+        for (qgram, count) in count_qgrams(&seq, self.q) {
+            self.postings
+                .entry(qgram.to_vec())
+                .or_default()
+                .push((index, count));
+        }
+
+        self.lengths.entry(seq.len()).or_default().push(index);
+        self.seqs.push(seq);
+        index
+    }
+
+    /// minimum number of q-grams shared with a sequence of length `len` within `score_cutoff`
+    fn min_common(&self, query_len: usize, len: usize, score_cutoff: usize) -> usize {
+        (query_len.max(len) + 1).saturating_sub(self.q.saturating_mul(score_cutoff + 1))
+    }
+
+    /// Returns the indices of all sequences which might have a distance of at most
+    /// `score_cutoff` to `query` in ascending order
+    pub fn candidates<Iter>(&self, query: Iter, score_cutoff: usize) -> Vec<usize>
+    where
+        Iter: IntoIterator<Item = Elem>,
+    {
+        let query: Vec<Elem> = query.into_iter().collect();
+        self.candidates_impl(&query, score_cutoff)
+    }
+
+    fn candidates_impl(&self, query: &[Elem], score_cutoff: usize) -> Vec<usize> {
+        let min_len = query.len().saturating_sub(score_cutoff);
+        let max_len = query.len().saturating_add(score_cutoff);
+        let is_valid_len = |len: usize| min_len <= len && len <= max_len;
+
+        let mut candidates = Vec::new();
+        let mut needs_count = false;
+        for (&len, indices) in self.lengths.range(min_len..=max_len) {
+            if self.min_common(query.len(), len, score_cutoff) == 0 {
+                candidates.extend_from_slice(indices);
+            } else {
+                needs_count = true;
+            }
+        }
+
+        if needs_count {
+            let mut common: HashMap<usize, usize> = HashMap::new();
+            for (qgram, query_count) in count_qgrams(query, self.q) {
+                let Some(postings) = self.postings.get(qgram) else {
+                    continue;
+                };
+                for &(index, count) in postings {
+                    if is_valid_len(self.seqs[index].len()) {
+                        *common.entry(index).or_insert(0) += query_count.min(count);
+                    }
+                }
+            }
+
+            candidates.extend(common.into_iter().filter_map(|(index, count)| {
+                let min_common = self.min_common(query.len(), self.seqs[index].len(), score_cutoff);
+                // sequences without required q-grams were already added
+                (min_common != 0 && count >= min_common).then_some(index)
+            }));
+        }
+
+        candidates.sort_unstable();
+        candidates
+    }
+
+    /// Returns all sequences with a distance of at most `score_cutoff` to `query`
+    ///
+    /// The results are sorted by distance and then by index.
+    pub fn find_within<Iter>(
+        &self,
+        query: Iter,
+        score_cutoff: usize,
+    ) -> Vec<(usize, &[Elem], usize)>
+    where
+        Iter: IntoIterator<Item = Elem>,
+    {
+        let query: Vec<Elem> = query.into_iter().collect();
+        let mut results: Vec<(usize, &[Elem], usize)> = self
+            .candidates_impl(&query, score_cutoff)
+            .into_iter()
+            .filter_map(|index| {
+                let seq = self.seqs[index].as_slice();
+                self.metric
+                    .distance(seq, &query, Some(score_cutoff))
+                    .map(|dist| (index, seq, dist))
+            })
+            .collect();
+
+        results.sort_by_key(|&(index, _, dist)| (dist, index));
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::details::testing::Lcg;
+    use crate::distance::indel::Indel;
+    use crate::distance::levenshtein::Levenshtein;
+    use crate::index::brute_force;
+
+    fn check<M>(metric: M)
+    where
+        M: DistanceMetric<char, Output = usize> + Clone,
+    {
+        let words = Lcg::new(11).words(300, 1..=12);
+        for q in 1..=3 {
+            let index = QGramIndex::build(metric.clone(), q, words.iter().map(|word| word.chars()));
+
+            for query in ["abcabcabc", "dddd", "abcdab", "b", "aabbccddaabb"] {
+                for score_cutoff in 0..4 {
+                    let found: Vec<(usize, usize)> = index
+                        .find_within(query.chars(), score_cutoff)
+                        .into_iter()
+                        .map(|(index, _, dist)| (index, dist))
+                        .collect();
+                    assert_eq!(brute_force(&metric, &words, query, score_cutoff), found);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        check(Levenshtein);
+        check(Indel);
+    }
+
+    #[test]
+    fn test_candidates_are_filtered() {
+        let index = QGramIndex::build(
+            Levenshtein,
+            2,
+            ["abcdefgh", "abcdefgx", "zyxwvuts"].map(str::chars),
+        );
+        assert_eq!(vec![0, 1], index.candidates("abcdefgh".chars(), 1));
+        assert_eq!(vec![0], index.candidates("abcdefgh".chars(), 0));
+    }
+}
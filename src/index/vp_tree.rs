@@ -0,0 +1,314 @@
+use crate::index::DistanceMetric;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+
+#[derive(Clone)]
+struct Node {
+    /// index of the vantage point
+    index: usize,
+    /// largest distance between the vantage point and a sequence in `inside`
+    inner_radius: f64,
+    /// smallest distance between the vantage point and a sequence in `outside`
+    outer_radius: f64,
+    inside: Option<usize>,
+    outside: Option<usize>,
+}
+
+/// Vantage-point tree to search for sequences close to a query
+///
+/// The tree works with any metric implementing [`DistanceMetric`] with `f64` distances,
+/// e.g. the normalized [`Jaro`](crate::distance::jaro::Jaro) distance or the normalized distances
+/// provided by [`Normalized`](crate::index::Normalized). The search is only guaranteed to
+/// find all matches for metrics satisfying the triangle inequality. For metrics that only
+/// approximately satisfy it, like most normalized distances, some matches might be missed.
+///
+/// During a search the current pruning radius is passed as `score_cutoff` to the metric,
+/// which allows it to exit early for sequences far away from the query.
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::distance::jaro_winkler::JaroWinkler;
+/// use rapidfuzz::index::VpTree;
+///
+/// let tree = VpTree::build(JaroWinkler, ["martha", "dixon", "marhta", "dwayne"].map(str::chars));
+///
+/// let nearest = tree.find_nearest("martha".chars(), 2);
+/// assert_eq!((0, 0.0), (nearest[0].0, nearest[0].2));
+/// assert_eq!(2, nearest[1].0);
+///
+/// let matches = tree.find_within("dicksonx".chars(), 0.25);
+/// assert_eq!(1, matches.len());
+/// assert_eq!(1, matches[0].0);
+/// ```
+#[derive(Clone)]
+pub struct VpTree<Elem, M> {
+    metric: M,
+    seqs: Vec<Vec<Elem>>,
+    nodes: Vec<Node>,
+}
+
+impl<Elem, M> VpTree<Elem, M>
+where
+    M: DistanceMetric<Elem, Output = f64>,
+{
+    /// creates a tree containing all sequences in `seqs`
+    pub fn build<Iter, Seq>(metric: M, seqs: Iter) -> Self
+    where
+        Iter: IntoIterator<Item = Seq>,
+        Seq: IntoIterator<Item = Elem>,
+    {
+        let seqs: Vec<Vec<Elem>> = seqs.into_iter().map(|s| s.into_iter().collect()).collect();
+        let mut tree = Self {
+            metric,
+            nodes: Vec::with_capacity(seqs.len()),
+            seqs: Vec::new(),
+        };
+        let items = (0..seqs.len()).collect();
+        tree.seqs = seqs;
+        tree.build_node(items);
+        tree
+    }
+
+    fn full_distance(&self, s1: &[Elem], s2: &[Elem]) -> f64 {
+        self.metric
+            .distance(s1, s2, None)
+            .expect("distance without score_cutoff")
+    }
+
+    fn build_node(&mut self, mut items: Vec<usize>) -> Option<usize> {
+        // the first item is used as vantage point
+        let vantage_point = *items.first()?;
+        let vp_seq = &self.seqs[vantage_point];
+        let mut dists: Vec<(f64, usize)> = items
+            .drain(1..)
+            .map(|index| (self.full_distance(vp_seq, &self.seqs[index]), index))
+            .collect();
+        dists.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let outside = dists.split_off(dists.len() / 2);
+        let inner_radius = dists.last().map_or(0.0, |&(dist, _)| dist);
+        let outer_radius = outside.first().map_or(f64::INFINITY, |&(dist, _)| dist);
+
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            index: vantage_point,
+            inner_radius,
+            outer_radius,
+            inside: None,
+            outside: None,
+        });
+        self.nodes[node].inside = self.build_node(dists.into_iter().map(|(_, i)| i).collect());
+        self.nodes[node].outside = self.build_node(outside.into_iter().map(|(_, i)| i).collect());
+        Some(node)
+    }
+
+    /// number of sequences in the tree
+    pub fn len(&self) -> usize {
+        self.seqs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seqs.is_empty()
+    }
+
+    /// sequence at position `index` of the sequences used to build the tree
+    pub fn get(&self, index: usize) -> Option<&[Elem]> {
+        self.seqs.get(index).map(Vec::as_slice)
+    }
+
+    /// Visits all nodes which might contain sequences with a distance of at most `radius()`
+    ///
+    /// `found` is called for every sequence within the radius, which may shrink it.
+    fn search<R, F>(&self, query: &[Elem], radius: R, mut found: F)
+    where
+        R: Fn() -> f64,
+        F: FnMut(usize, f64),
+    {
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        // nodes to visit with a lower bound for the distance of the sequences they contain
+        let mut stack = vec![(0, 0.0)];
+        while let Some((node, lower_bound)) = stack.pop() {
+            let r = radius();
+            if lower_bound > r {
+                continue;
+            }
+
+            let node = &self.nodes[node];
+            // beyond this distance neither the vantage point nor `inside` can match,
+            // while `outside` has to be searched anyway
+            let mut cutoff = r;
+            if node.inside.is_some() {
+                cutoff += node.inner_radius;
+            }
+            if node.outside.is_some() {
+                cutoff = cutoff.max(node.outer_radius - r);
+            }
+            let cutoff = Some(cutoff).filter(|cutoff| cutoff.is_finite());
+
+            let Some(dist) = self.metric.distance(&self.seqs[node.index], query, cutoff) else {
+                if let Some(outside) = node.outside {
+                    stack.push((outside, 0.0));
+                }
+                continue;
+            };
+
+            if dist <= r {
+                found(node.index, dist);
+            }
+
+            let inside = node
+                .inside
+                .map(|inside| (inside, (dist - node.inner_radius).max(0.0)));
+            let outside = node
+                .outside
+                .map(|outside| (outside, (node.outer_radius - dist).max(0.0)));
+            // visit the side containing the query first
+            if dist <= node.inner_radius {
+                stack.extend(outside);
+                stack.extend(inside);
+            } else {
+                stack.extend(inside);
+                stack.extend(outside);
+            }
+        }
+    }
+
+    /// Returns all sequences with a distance of at most `max_dist` to `query`
+    ///
+    /// The results are sorted by distance and then by index.
+    pub fn find_within<Iter>(&self, query: Iter, max_dist: f64) -> Vec<(usize, &[Elem], f64)>
+    where
+        Iter: IntoIterator<Item = Elem>,
+    {
+        let query: Vec<Elem> = query.into_iter().collect();
+        let mut results = Vec::new();
+        self.search(
+            &query,
+            || max_dist,
+            |index, dist| results.push((dist, index)),
+        );
+
+        results.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        results
+            .into_iter()
+            .map(|(dist, index)| (index, self.seqs[index].as_slice(), dist))
+            .collect()
+    }
+
+    /// Returns the `limit` sequences closest to `query`
+    ///
+    /// The results are sorted by distance and then by index.
+    pub fn find_nearest<Iter>(&self, query: Iter, limit: usize) -> Vec<(usize, &[Elem], f64)>
+    where
+        Iter: IntoIterator<Item = Elem>,
+    {
+        let query: Vec<Elem> = query.into_iter().collect();
+        if limit == 0 {
+            return Vec::new();
+        }
+
+        // best `(dist, index)` found so far in ascending order
+        let best = RefCell::new(Vec::<(f64, usize)>::with_capacity(limit + 1));
+        self.search(
+            &query,
+            || {
+                let best = best.borrow();
+                if best.len() == limit {
+                    best[limit - 1].0
+                } else {
+                    f64::INFINITY
+                }
+            },
+            |index, dist| {
+                let mut best = best.borrow_mut();
+                let pos = best.partition_point(|&(d, i)| (d, i) < (dist, index));
+                best.insert(pos, (dist, index));
+                best.truncate(limit);
+            },
+        );
+
+        best.into_inner()
+            .into_iter()
+            .map(|(dist, index)| (index, self.seqs[index].as_slice(), dist))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::details::testing::Lcg;
+    use crate::distance::levenshtein;
+    use crate::index::{brute_force, Normalized};
+
+    /// Levenshtein distance as `f64`, which satisfies the triangle inequality
+    struct Scaled;
+
+    impl DistanceMetric<char> for Scaled {
+        type Output = f64;
+
+        fn distance(&self, s1: &[char], s2: &[char], score_cutoff: Option<f64>) -> Option<f64> {
+            let dist = levenshtein::distance(s1.iter().copied(), s2.iter().copied()) as f64 / 8.0;
+            match score_cutoff {
+                Some(cutoff) if dist > cutoff => None,
+                _ => Some(dist),
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let words = Lcg::new(7).words(300, 3..=7);
+        let tree = VpTree::build(Scaled, words.iter().map(|word| word.chars()));
+        assert_eq!(words.len(), tree.len());
+
+        for query in ["abc", "dddd", "abcdab", "b"] {
+            for max_dist in [0.0, 0.125, 0.25, 0.375] {
+                let found: Vec<(usize, f64)> = tree
+                    .find_within(query.chars(), max_dist)
+                    .into_iter()
+                    .map(|(index, _, dist)| (index, dist))
+                    .collect();
+                assert_eq!(brute_force(&Scaled, &words, query, max_dist), found);
+            }
+
+            let expected = brute_force(&Scaled, &words, query, f64::INFINITY);
+            for limit in [1, 5, 20] {
+                let found: Vec<(usize, f64)> = tree
+                    .find_nearest(query.chars(), limit)
+                    .into_iter()
+                    .map(|(index, _, dist)| (index, dist))
+                    .collect();
+                assert_eq!(expected[..limit].to_vec(), found);
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalized() {
+        let tree = VpTree::build(
+            Normalized(levenshtein::Levenshtein),
+            ["kitten", "sitting", "mitten"].map(str::chars),
+        );
+        let nearest = tree.find_nearest("kitten".chars(), 3);
+        assert_eq!(
+            vec![0, 2, 1],
+            nearest
+                .iter()
+                .map(|&(index, _, _)| index)
+                .collect::<Vec<_>>()
+        );
+        assert!(tree.find_within("xyz".chars(), 0.5).is_empty());
+
+        let empty: VpTree<char, _> = VpTree::build(
+            Normalized(levenshtein::Levenshtein),
+            Vec::<Vec<char>>::new(),
+        );
+        assert!(empty.is_empty());
+        assert!(empty.find_nearest("abc".chars(), 1).is_empty());
+    }
+}
@@ -23,7 +23,11 @@
 //! ## Usage
 //!
 //! The following examples show the usage with the [`Levenshtein`] distance. Other metrics
-//! can be found in the [`fuzz`] and [`distance`] modules.
+//! can be found in the [`fuzz`] and [`distance`] modules. The [`process`] module provides
+//! helpers to find the best matches in a list of choices and [`utils`] provides helpers
+//! to preprocess the sequences. When the metric is only known at runtime, [`ScorerKind`] can
+//! be used to select it. The [`index`] module provides indexes to search large collections
+//! of sequences.
 //!
 //! ```rust
 //! use rapidfuzz::distance::levenshtein;
@@ -74,6 +78,10 @@
 //! [`Levenshtein`]: distance/levenshtein/index.html
 //! [`fuzz`]: fuzz/index.html
 //! [`distance`]: distance/index.html
+//! [`index`]: index/index.html
+//! [`process`]: process/index.html
+//! [`utils`]: utils/index.html
+//! [`ScorerKind`]: enum.ScorerKind.html
 
 #![forbid(unsafe_code)]
 #![allow(
@@ -98,9 +106,15 @@ pub mod common;
 pub(crate) mod details;
 pub mod distance;
 pub mod fuzz;
+pub mod index;
+pub mod process;
+mod scorer;
+pub mod utils;
+
+pub use scorer::{BatchScorer, ParseScorerError, Scorer, ScorerKind};
 
 /// Hash value in the range `i64::MIN` - `u64::MAX`
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Hash {
     UNSIGNED(u64),
     SIGNED(i64),
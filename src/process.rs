@@ -0,0 +1,1046 @@
+//! Process
+//!
+//! Helpers to compare a query with a collection of choices. The query is usually
+//! captured inside of the scorer, e.g. by creating one of the `BatchComparator`s
+//! once and using it to compare each of the choices.
+//!
+//! Scorers are wrapped in either [`Similarity`] or [`Distance`] to select whether
+//! higher or lower scores are considered better. The wrapped function receives the
+//! worst score still of interest, which should be forwarded as `score_cutoff`
+//! to the `*_with_args` function of the scorer. This allows the implementation to
+//! skip work for choices which can't make it into the result anyway.
+//!
+//! The `*_with_args` variants receive the query together with a function creating the
+//! scorer for it instead of the scorer itself, so the `processor` selected in the [`Args`]
+//! can be applied to the query as well as to each of the choices.
+//!
+//! Results are returned as `(index, choice, score)`, where `index` is the position of the
+//! choice in the iterator. For keyed collections like a `HashMap`, pass `map.iter()`, so
+//! each choice is a `(key, value)` pair.
+//!
+//! ```
+//! use rapidfuzz::distance::levenshtein;
+//! use rapidfuzz::process;
+//!
+//! let choices = ["apple", "banana", "cherry", "grape"];
+//! let scorer = levenshtein::BatchComparator::new("bananas".chars());
+//!
+//! let best = process::extract_one(
+//!     choices.iter(),
+//!     &process::Distance::new(|choice: &&&str, score_cutoff: usize| {
+//!         scorer.distance_with_args(
+//!             choice.chars(),
+//!             &levenshtein::Args::default().score_cutoff(score_cutoff),
+//!         )
+//!     }),
+//! );
+//! assert_eq!(Some((1, &"banana", 1)), best);
+//! ```
+
+use crate::common::NoProcessor;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+/// Score types which can be returned by a [`Scorer`]
+pub trait ScoreValue: Copy + PartialOrd + Debug {
+    /// worst possible score for a distance
+    const WORST_DISTANCE: Self;
+    /// worst possible score for a similarity
+    const WORST_SIMILARITY: Self;
+}
+
+impl ScoreValue for usize {
+    const WORST_DISTANCE: Self = usize::MAX;
+    const WORST_SIMILARITY: Self = 0;
+}
+
+impl ScoreValue for f64 {
+    const WORST_DISTANCE: Self = f64::INFINITY;
+    const WORST_SIMILARITY: Self = f64::NEG_INFINITY;
+}
+
+/// Scorer used to compare each of the choices with the query
+pub trait Scorer<Choice> {
+    type Score: ScoreValue;
+
+    /// `true` for similarities and `false` for distances
+    const HIGHER_IS_BETTER: bool;
+
+    /// Compares a choice with the query.
+    ///
+    /// Returns `None` if the score is worse than `score_cutoff`.
+    fn score(&self, choice: &Choice, score_cutoff: Self::Score) -> Option<Self::Score>;
+
+    /// worst possible score of the scorer
+    fn worst_score(&self) -> Self::Score {
+        if Self::HIGHER_IS_BETTER {
+            Self::Score::WORST_SIMILARITY
+        } else {
+            Self::Score::WORST_DISTANCE
+        }
+    }
+
+    /// ordering of two scores where the better score is considered smaller
+    fn compare(&self, a: Self::Score, b: Self::Score) -> Ordering {
+        let ordering = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+        if Self::HIGHER_IS_BETTER {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+impl<Choice, S> Scorer<Choice> for &S
+where
+    S: Scorer<Choice> + ?Sized,
+{
+    type Score = S::Score;
+
+    const HIGHER_IS_BETTER: bool = S::HIGHER_IS_BETTER;
+
+    fn score(&self, choice: &Choice, score_cutoff: Self::Score) -> Option<Self::Score> {
+        (**self).score(choice, score_cutoff)
+    }
+}
+
+/// Wraps a similarity function, so higher scores are considered better
+#[derive(Clone, Copy, Debug)]
+pub struct Similarity<F, ScoreType> {
+    scorer: F,
+    _score: PhantomData<fn() -> ScoreType>,
+}
+
+impl<F, ScoreType> Similarity<F, ScoreType> {
+    pub fn new<Choice>(scorer: F) -> Self
+    where
+        F: Fn(&Choice, ScoreType) -> Option<ScoreType>,
+    {
+        Self {
+            scorer,
+            _score: PhantomData,
+        }
+    }
+}
+
+/// Wraps a distance function, so lower scores are considered better
+#[derive(Clone, Copy, Debug)]
+pub struct Distance<F, ScoreType> {
+    scorer: F,
+    _score: PhantomData<fn() -> ScoreType>,
+}
+
+impl<F, ScoreType> Distance<F, ScoreType> {
+    pub fn new<Choice>(scorer: F) -> Self
+    where
+        F: Fn(&Choice, ScoreType) -> Option<ScoreType>,
+    {
+        Self {
+            scorer,
+            _score: PhantomData,
+        }
+    }
+}
+
+impl<Choice, ScoreType, F> Scorer<Choice> for Similarity<F, ScoreType>
+where
+    F: Fn(&Choice, ScoreType) -> Option<ScoreType>,
+    ScoreType: ScoreValue,
+{
+    type Score = ScoreType;
+
+    const HIGHER_IS_BETTER: bool = true;
+
+    fn score(&self, choice: &Choice, score_cutoff: Self::Score) -> Option<Self::Score> {
+        (self.scorer)(choice, score_cutoff).filter(|&score| score >= score_cutoff)
+    }
+}
+
+impl<Choice, ScoreType, F> Scorer<Choice> for Distance<F, ScoreType>
+where
+    F: Fn(&Choice, ScoreType) -> Option<ScoreType>,
+    ScoreType: ScoreValue,
+{
+    type Score = ScoreType;
+
+    const HIGHER_IS_BETTER: bool = false;
+
+    fn score(&self, choice: &Choice, score_cutoff: Self::Score) -> Option<Self::Score> {
+        (self.scorer)(choice, score_cutoff).filter(|&score| score <= score_cutoff)
+    }
+}
+
+/// Preprocessing applied to the query and each of the choices before they are passed
+/// to the scorer
+///
+/// This is implemented for [`NoProcessor`] and any function `Fn(&Choice) -> Processed`.
+pub trait ChoiceProcessor<Choice> {
+    type Output;
+
+    /// calls `f` with the processed query or choice
+    fn with_processed<R, F>(&self, choice: &Choice, f: F) -> R
+    where
+        F: FnOnce(&Self::Output) -> R;
+}
+
+impl<Choice> ChoiceProcessor<Choice> for NoProcessor {
+    type Output = Choice;
+
+    fn with_processed<R, F>(&self, choice: &Choice, f: F) -> R
+    where
+        F: FnOnce(&Self::Output) -> R,
+    {
+        f(choice)
+    }
+}
+
+impl<Choice, Processed, P> ChoiceProcessor<Choice> for P
+where
+    P: Fn(&Choice) -> Processed,
+{
+    type Output = Processed;
+
+    fn with_processed<R, F>(&self, choice: &Choice, f: F) -> R
+    where
+        F: FnOnce(&Self::Output) -> R,
+    {
+        f(&self(choice))
+    }
+}
+
+#[must_use]
+#[derive(Clone, Copy, Debug)]
+pub struct Args<ScoreType, ProcessorType = NoProcessor> {
+    score_cutoff: Option<ScoreType>,
+    processor: ProcessorType,
+}
+
+impl<ScoreType> Default for Args<ScoreType> {
+    fn default() -> Args<ScoreType> {
+        Args {
+            score_cutoff: None,
+            processor: NoProcessor,
+        }
+    }
+}
+
+impl<ScoreType, ProcessorType> Args<ScoreType, ProcessorType> {
+    /// only choices with a score at least as good as `score_cutoff` are returned
+    pub fn score_cutoff(mut self, score_cutoff: ScoreType) -> Self {
+        self.score_cutoff = Some(score_cutoff);
+        self
+    }
+
+    /// preprocessing applied to the query and each of the choices before they are passed
+    /// to the scorer
+    ///
+    /// # Example
+    /// ```
+    /// use rapidfuzz::distance::levenshtein;
+    /// use rapidfuzz::{process, utils};
+    ///
+    /// let best = process::extract_one_with_args(
+    ///     &"New York",
+    ///     ["new york!", "Newark"],
+    ///     |query: &String| {
+    ///         let scorer = levenshtein::BatchComparator::new(query.chars().collect::<Vec<_>>());
+    ///         process::Distance::new(move |choice: &String, score_cutoff: usize| {
+    ///             scorer.distance_with_args(
+    ///                 choice.chars(),
+    ///                 &levenshtein::Args::default().score_cutoff(score_cutoff),
+    ///             )
+    ///         })
+    ///     },
+    ///     &process::Args::default().processor(|s: &&str| utils::default_process(s)),
+    /// );
+    /// assert_eq!(Some((0, "new york!", 0)), best);
+    /// ```
+    pub fn processor<P>(self, processor: P) -> Args<ScoreType, P> {
+        Args {
+            score_cutoff: self.score_cutoff,
+            processor,
+        }
+    }
+}
+
+/// Iterator over all choices with a score at least as good as the `score_cutoff`
+///
+/// Created by [`extract_iter`] and [`extract_iter_with_args`]. The choices are
+/// yielded in their original order as `(index, choice, score)`.
+pub struct ExtractIter<Iter, S, ProcessorType = NoProcessor>
+where
+    Iter: Iterator,
+    ProcessorType: ChoiceProcessor<Iter::Item>,
+    S: Scorer<ProcessorType::Output>,
+{
+    choices: std::iter::Enumerate<Iter>,
+    scorer: S,
+    score_cutoff: S::Score,
+    processor: ProcessorType,
+}
+
+impl<Iter, S, ProcessorType> Iterator for ExtractIter<Iter, S, ProcessorType>
+where
+    Iter: Iterator,
+    ProcessorType: ChoiceProcessor<Iter::Item>,
+    S: Scorer<ProcessorType::Output>,
+{
+    type Item = (usize, Iter::Item, S::Score);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, choice) in self.choices.by_ref() {
+            let score = self.processor.with_processed(&choice, |processed| {
+                self.scorer.score(processed, self.score_cutoff)
+            });
+            if let Some(score) = score {
+                return Some((index, choice, score));
+            }
+        }
+        None
+    }
+}
+
+/// Lazily compares the query with each of the choices
+///
+/// # Example
+/// ```
+/// use rapidfuzz::fuzz;
+/// use rapidfuzz::process;
+///
+/// let scorer = fuzz::RatioBatchComparator::new("apple".chars());
+/// let scorer = process::Similarity::new(|choice: &&str, score_cutoff: f64| {
+///     scorer.similarity_with_args(choice.chars(), &fuzz::Args::default().score_cutoff(score_cutoff))
+/// });
+///
+/// let matches: Vec<_> = process::extract_iter(["apples", "banana", "apple"], &scorer)
+///     .map(|(index, _, _)| index)
+///     .collect();
+/// assert_eq!(vec![0, 1, 2], matches);
+/// ```
+pub fn extract_iter<Choices, S>(choices: Choices, scorer: &S) -> ExtractIter<Choices::IntoIter, &S>
+where
+    Choices: IntoIterator,
+    S: Scorer<Choices::Item>,
+{
+    ExtractIter {
+        choices: choices.into_iter().enumerate(),
+        scorer,
+        score_cutoff: scorer.worst_score(),
+        processor: NoProcessor,
+    }
+}
+
+/// Lazily compares `query` with each of the choices
+///
+/// `scorer` is called once with the processed `query` and returns the [`Scorer`] used
+/// to compare it with each of the processed choices.
+pub fn extract_iter_with_args<Query, Choices, F, S, ProcessorType>(
+    query: &Query,
+    choices: Choices,
+    scorer: F,
+    args: &Args<S::Score, ProcessorType>,
+) -> ExtractIter<Choices::IntoIter, S, ProcessorType>
+where
+    Choices: IntoIterator,
+    ProcessorType: ChoiceProcessor<Query> + ChoiceProcessor<Choices::Item> + Clone,
+    F: FnOnce(&<ProcessorType as ChoiceProcessor<Query>>::Output) -> S,
+    S: Scorer<<ProcessorType as ChoiceProcessor<Choices::Item>>::Output>,
+{
+    let scorer = args.processor.with_processed(query, scorer);
+    ExtractIter {
+        choices: choices.into_iter().enumerate(),
+        score_cutoff: args.score_cutoff.unwrap_or_else(|| scorer.worst_score()),
+        scorer,
+        processor: args.processor.clone(),
+    }
+}
+
+/// Finds the best match of the query in a collection of choices
+///
+/// Returns `(index, choice, score)` of the best match. When multiple choices
+/// have the same score, the first one is returned. The best score found so far
+/// is used as `score_cutoff` for the remaining choices.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::fuzz;
+/// use rapidfuzz::process;
+///
+/// let scorer = fuzz::RatioBatchComparator::new("new york".chars());
+/// let best = process::extract_one(
+///     ["Atlanta Falcons", "New York Jets", "new york giants"],
+///     &process::Similarity::new(|choice: &&str, score_cutoff: f64| {
+///         scorer.similarity_with_args(choice.chars(), &fuzz::Args::default().score_cutoff(score_cutoff))
+///     }),
+/// );
+/// assert_eq!(Some(2), best.map(|(index, _, _)| index));
+/// ```
+pub fn extract_one<Choices, S>(
+    choices: Choices,
+    scorer: &S,
+) -> Option<(usize, Choices::Item, S::Score)>
+where
+    Choices: IntoIterator,
+    S: Scorer<Choices::Item>,
+{
+    extract_one_impl(choices, scorer, None, &NoProcessor)
+}
+
+/// Finds the best match of `query` in a collection of choices
+///
+/// `scorer` is called once with the processed `query` and returns the [`Scorer`] used
+/// to compare it with each of the processed choices.
+pub fn extract_one_with_args<Query, Choices, F, S, ProcessorType>(
+    query: &Query,
+    choices: Choices,
+    scorer: F,
+    args: &Args<S::Score, ProcessorType>,
+) -> Option<(usize, Choices::Item, S::Score)>
+where
+    Choices: IntoIterator,
+    ProcessorType: ChoiceProcessor<Query> + ChoiceProcessor<Choices::Item>,
+    F: FnOnce(&<ProcessorType as ChoiceProcessor<Query>>::Output) -> S,
+    S: Scorer<<ProcessorType as ChoiceProcessor<Choices::Item>>::Output>,
+{
+    let scorer = args.processor.with_processed(query, scorer);
+    extract_one_impl(choices, &scorer, args.score_cutoff, &args.processor)
+}
+
+fn extract_one_impl<Choices, S, ProcessorType>(
+    choices: Choices,
+    scorer: &S,
+    score_cutoff: Option<S::Score>,
+    processor: &ProcessorType,
+) -> Option<(usize, Choices::Item, S::Score)>
+where
+    Choices: IntoIterator,
+    ProcessorType: ChoiceProcessor<Choices::Item>,
+    S: Scorer<ProcessorType::Output>,
+{
+    let mut score_cutoff = score_cutoff.unwrap_or_else(|| scorer.worst_score());
+    let mut result = None;
+
+    for (index, choice) in choices.into_iter().enumerate() {
+        let score =
+            processor.with_processed(&choice, |processed| scorer.score(processed, score_cutoff));
+        let Some(score) = score else {
+            continue;
+        };
+
+        let is_better = match &result {
+            Some((_, _, best)) => scorer.compare(score, *best) == Ordering::Less,
+            None => true,
+        };
+        if is_better {
+            score_cutoff = score;
+            result = Some((index, choice, score));
+        }
+    }
+
+    result
+}
+
+/// Finds the best matches of the query in a collection of choices
+///
+/// Returns up to `limit` results as `(index, choice, score)` sorted from the best to the
+/// worst score. Choices with the same score keep their original order. When `limit`
+/// is `None` all choices are returned. Once `limit` matches are found, the worst of them
+/// is used as `score_cutoff` for the remaining choices.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::distance::levenshtein;
+/// use rapidfuzz::process;
+///
+/// let scorer = levenshtein::BatchComparator::new("kitten".chars());
+/// let scorer = process::Distance::new(|choice: &&str, score_cutoff: usize| {
+///     scorer.distance_with_args(choice.chars(), &levenshtein::Args::default().score_cutoff(score_cutoff))
+/// });
+///
+/// let matches = process::extract(["sitting", "kitten", "mitten"], &scorer, Some(2));
+/// assert_eq!(vec![(1, "kitten", 0), (2, "mitten", 1)], matches);
+/// ```
+pub fn extract<Choices, S>(
+    choices: Choices,
+    scorer: &S,
+    limit: Option<usize>,
+) -> Vec<(usize, Choices::Item, S::Score)>
+where
+    Choices: IntoIterator,
+    S: Scorer<Choices::Item>,
+{
+    extract_impl(choices, scorer, limit, None, &NoProcessor)
+}
+
+/// Finds the best matches of `query` in a collection of choices
+///
+/// `scorer` is called once with the processed `query` and returns the [`Scorer`] used
+/// to compare it with each of the processed choices.
+pub fn extract_with_args<Query, Choices, F, S, ProcessorType>(
+    query: &Query,
+    choices: Choices,
+    scorer: F,
+    limit: Option<usize>,
+    args: &Args<S::Score, ProcessorType>,
+) -> Vec<(usize, Choices::Item, S::Score)>
+where
+    Choices: IntoIterator,
+    ProcessorType: ChoiceProcessor<Query> + ChoiceProcessor<Choices::Item>,
+    F: FnOnce(&<ProcessorType as ChoiceProcessor<Query>>::Output) -> S,
+    S: Scorer<<ProcessorType as ChoiceProcessor<Choices::Item>>::Output>,
+{
+    let scorer = args.processor.with_processed(query, scorer);
+    extract_impl(choices, &scorer, limit, args.score_cutoff, &args.processor)
+}
+
+fn extract_impl<Choices, S, ProcessorType>(
+    choices: Choices,
+    scorer: &S,
+    limit: Option<usize>,
+    score_cutoff: Option<S::Score>,
+    processor: &ProcessorType,
+) -> Vec<(usize, Choices::Item, S::Score)>
+where
+    Choices: IntoIterator,
+    ProcessorType: ChoiceProcessor<Choices::Item>,
+    S: Scorer<ProcessorType::Output>,
+{
+    let limit = limit.unwrap_or(usize::MAX);
+    if limit == 0 {
+        return Vec::new();
+    }
+
+    let mut score_cutoff = score_cutoff.unwrap_or_else(|| scorer.worst_score());
+    let mut results: Vec<(usize, Choices::Item, S::Score)> = Vec::new();
+
+    for (index, choice) in choices.into_iter().enumerate() {
+        let score =
+            processor.with_processed(&choice, |processed| scorer.score(processed, score_cutoff));
+        let Some(score) = score else {
+            continue;
+        };
+
+        // results is kept sorted, so equal scores are inserted after the existing ones
+        let pos = results
+            .partition_point(|(_, _, other)| scorer.compare(*other, score) != Ordering::Greater);
+        if pos >= limit {
+            continue;
+        }
+
+        results.insert(pos, (index, choice, score));
+        if results.len() > limit {
+            results.pop();
+        }
+        if results.len() == limit {
+            score_cutoff = results[limit - 1].2;
+        }
+    }
+
+    results
+}
+
+/// Dense row-major matrix of scores created by [`cdist`]
+///
+/// The score between `queries[row]` and `choices[col]` is stored at `(row, col)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreMatrix<ScoreType> {
+    rows: usize,
+    cols: usize,
+    data: Vec<ScoreType>,
+}
+
+impl<ScoreType> ScoreMatrix<ScoreType> {
+    /// number of queries
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// number of choices
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// score between `queries[row]` and `choices[col]`
+    pub fn get(&self, row: usize, col: usize) -> Option<&ScoreType> {
+        if row < self.rows && col < self.cols {
+            self.data.get(row * self.cols + col)
+        } else {
+            None
+        }
+    }
+
+    /// scores of `queries[row]` compared with each of the choices
+    pub fn row(&self, row: usize) -> &[ScoreType] {
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// all scores in row-major order
+    pub fn as_slice(&self) -> &[ScoreType] {
+        &self.data
+    }
+
+    pub fn into_vec(self) -> Vec<ScoreType> {
+        self.data
+    }
+}
+
+impl<ScoreType> std::ops::Index<(usize, usize)> for ScoreMatrix<ScoreType> {
+    type Output = ScoreType;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        assert!(col < self.cols, "column {col} out of range");
+        &self.data[row * self.cols + col]
+    }
+}
+
+/// Calculates the scores between each query and each choice
+///
+/// `scorer` is called once per query and returns the function used to compare this
+/// query with all choices. This allows the creation of a single `BatchComparator` per
+/// row. Passing the same list as `queries` and `choices` creates the `N x N` matrix of
+/// all pairs, which [`cdist_symmetric`] calculates faster for symmetric metrics.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::distance::levenshtein;
+/// use rapidfuzz::process;
+///
+/// let queries = ["kitten", "sitting"];
+/// let choices = ["kitten", "sitting", "mitten"];
+///
+/// let matrix = process::cdist(&queries, &choices, |query: &&str| {
+///     let scorer = levenshtein::BatchComparator::new(query.chars());
+///     move |choice: &&str| scorer.distance(choice.chars())
+/// });
+/// assert_eq!(&[0, 3, 1], matrix.row(0));
+/// assert_eq!(&[3, 0, 3], matrix.row(1));
+/// ```
+pub fn cdist<Query, Choice, F, RowScorer, ScoreType>(
+    queries: &[Query],
+    choices: &[Choice],
+    scorer: F,
+) -> ScoreMatrix<ScoreType>
+where
+    F: Fn(&Query) -> RowScorer,
+    RowScorer: Fn(&Choice) -> ScoreType,
+{
+    let mut data = Vec::with_capacity(queries.len() * choices.len());
+    for query in queries {
+        let row_scorer = scorer(query);
+        data.extend(choices.iter().map(row_scorer));
+    }
+
+    ScoreMatrix {
+        rows: queries.len(),
+        cols: choices.len(),
+        data,
+    }
+}
+
+/// Calculates the scores between all pairs of `choices`
+///
+/// Produces the same `N x N` matrix as passing `choices` as both `queries` and `choices`
+/// to [`cdist`], but only calculates the upper triangle including the diagonal and
+/// mirrors it. This is only correct for symmetric metrics, where comparing `a` with `b`
+/// results in the same score as comparing `b` with `a`.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::distance::levenshtein;
+/// use rapidfuzz::process;
+///
+/// let names = ["kitten", "sitting", "mitten"];
+///
+/// let matrix = process::cdist_symmetric(&names, |query: &&str| {
+///     let scorer = levenshtein::BatchComparator::new(query.chars());
+///     move |choice: &&str| scorer.distance(choice.chars())
+/// });
+/// assert_eq!(&[0, 3, 1], matrix.row(0));
+/// assert_eq!(&[3, 0, 3], matrix.row(1));
+/// assert_eq!(&[1, 3, 0], matrix.row(2));
+/// ```
+pub fn cdist_symmetric<Choice, F, RowScorer, ScoreType>(
+    choices: &[Choice],
+    scorer: F,
+) -> ScoreMatrix<ScoreType>
+where
+    F: Fn(&Choice) -> RowScorer,
+    RowScorer: Fn(&Choice) -> ScoreType,
+    ScoreType: Clone,
+{
+    let rows = choices
+        .iter()
+        .enumerate()
+        .map(|(row, query)| choices[row..].iter().map(scorer(query)).collect())
+        .collect();
+    mirror_upper_triangle(rows)
+}
+
+/// creates the symmetric matrix from the rows of its upper triangle, where `rows[i]`
+/// starts at the diagonal
+fn mirror_upper_triangle<ScoreType>(rows: Vec<Vec<ScoreType>>) -> ScoreMatrix<ScoreType>
+where
+    ScoreType: Clone,
+{
+    let len = rows.len();
+    let mut data = Vec::with_capacity(len * len);
+    for row in 0..len {
+        data.extend((0..row).map(|col| rows[col][row - col].clone()));
+        data.extend_from_slice(&rows[row]);
+    }
+
+    ScoreMatrix {
+        rows: len,
+        cols: len,
+        data,
+    }
+}
+
+/// runs `calculate` on a thread pool with `workers` threads, or on the global rayon
+/// thread pool when `workers` is 0
+#[cfg(feature = "rayon")]
+fn install<R, F>(workers: usize, calculate: F) -> Result<R, rayon::ThreadPoolBuildError>
+where
+    F: FnOnce() -> R + Send,
+    R: Send,
+{
+    if workers == 0 {
+        return Ok(calculate());
+    }
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(workers)
+        .build()?;
+    Ok(pool.install(calculate))
+}
+
+/// Multithreaded version of [`cdist`]
+///
+/// The rows are distributed over `workers` threads. When `workers` is 0, the
+/// global rayon thread pool is used. Otherwise a new thread pool is created for
+/// each call, so passing 0 is cheaper when calling this repeatedly for small
+/// inputs. An error is returned when this thread pool can not be created.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::fuzz;
+/// use rapidfuzz::process;
+///
+/// let names = ["new york mets", "new york yankees", "atlanta braves"];
+///
+/// let matrix = process::cdist_with_workers(&names, &names, |query: &&str| {
+///     let scorer = fuzz::RatioBatchComparator::new(query.chars());
+///     move |choice: &&str| scorer.similarity(choice.chars())
+/// }, 2)?;
+/// assert_eq!(1.0, matrix[(2, 2)]);
+/// # Ok::<(), rayon::ThreadPoolBuildError>(())
+/// ```
+#[cfg(feature = "rayon")]
+pub fn cdist_with_workers<Query, Choice, F, RowScorer, ScoreType>(
+    queries: &[Query],
+    choices: &[Choice],
+    scorer: F,
+    workers: usize,
+) -> Result<ScoreMatrix<ScoreType>, rayon::ThreadPoolBuildError>
+where
+    Query: Sync,
+    Choice: Sync,
+    F: Fn(&Query) -> RowScorer + Sync,
+    RowScorer: Fn(&Choice) -> ScoreType,
+    ScoreType: Send,
+{
+    use rayon::prelude::*;
+
+    if workers == 1 {
+        return Ok(cdist(queries, choices, scorer));
+    }
+
+    let data = install(workers, || {
+        queries
+            .par_iter()
+            .flat_map_iter(|query| choices.iter().map(scorer(query)))
+            .collect::<Vec<ScoreType>>()
+    })?;
+
+    Ok(ScoreMatrix {
+        rows: queries.len(),
+        cols: choices.len(),
+        data,
+    })
+}
+
+/// Multithreaded version of [`cdist_symmetric`]
+///
+/// The rows of the upper triangle are distributed over `workers` threads like
+/// in [`cdist_with_workers`].
+#[cfg(feature = "rayon")]
+pub fn cdist_symmetric_with_workers<Choice, F, RowScorer, ScoreType>(
+    choices: &[Choice],
+    scorer: F,
+    workers: usize,
+) -> Result<ScoreMatrix<ScoreType>, rayon::ThreadPoolBuildError>
+where
+    Choice: Sync,
+    F: Fn(&Choice) -> RowScorer + Sync,
+    RowScorer: Fn(&Choice) -> ScoreType,
+    ScoreType: Clone + Send,
+{
+    use rayon::prelude::*;
+
+    if workers == 1 {
+        return Ok(cdist_symmetric(choices, scorer));
+    }
+
+    let rows = install(workers, || {
+        choices
+            .par_iter()
+            .enumerate()
+            .map(|(row, query)| choices[row..].iter().map(scorer(query)).collect())
+            .collect::<Vec<Vec<ScoreType>>>()
+    })?;
+    Ok(mirror_upper_triangle(rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::levenshtein;
+    use crate::fuzz;
+    use crate::utils::default_process;
+
+    static CHOICES: [&str; 4] = [
+        "new york mets vs chicago cubs",
+        "chicago cubs at new york mets",
+        "atlanta braves vs pittsbugh pirates",
+        "new york yankees vs boston red sox",
+    ];
+
+    fn ratio_scorer(query: &str) -> Similarity<impl Fn(&&'static str, f64) -> Option<f64>, f64> {
+        let scorer = fuzz::RatioBatchComparator::new(query.chars().collect::<Vec<_>>());
+        Similarity::new(move |choice: &&str, score_cutoff: f64| {
+            scorer.similarity_with_args(
+                choice.chars(),
+                &fuzz::Args::default().score_cutoff(score_cutoff),
+            )
+        })
+    }
+
+    fn levenshtein_scorer(
+        query: &str,
+    ) -> Distance<impl Fn(&&'static str, usize) -> Option<usize>, usize> {
+        let scorer = levenshtein::BatchComparator::new(query.chars().collect::<Vec<_>>());
+        Distance::new(move |choice: &&str, score_cutoff: usize| {
+            scorer.distance_with_args(
+                choice.chars(),
+                &levenshtein::Args::default().score_cutoff(score_cutoff),
+            )
+        })
+    }
+
+    #[test]
+    fn test_extract_one() {
+        let scorer = ratio_scorer("new york mets at chicago cubs");
+        let best = extract_one(CHOICES, &scorer).unwrap();
+        assert_eq!(0, best.0);
+        assert_eq!(CHOICES[0], best.1);
+
+        let scorer = levenshtein_scorer("yankees vs boston red sox");
+        let best = extract_one(CHOICES, &scorer).unwrap();
+        assert_eq!(3, best.0);
+        assert_eq!(9, best.2);
+
+        assert_eq!(
+            None,
+            extract_one_with_args(
+                &"yankees vs boston red sox",
+                CHOICES,
+                |query: &&str| levenshtein_scorer(query),
+                &Args::default().score_cutoff(8)
+            )
+        );
+        assert_eq!(None, extract_one(Vec::<&str>::new(), &scorer));
+    }
+
+    #[test]
+    fn test_extract_one_keyed() {
+        let choices =
+            std::collections::BTreeMap::from([("mets", CHOICES[0]), ("yankees", CHOICES[3])]);
+        let query = levenshtein::BatchComparator::new("yankees vs boston red sox".chars());
+        let scorer = Distance::new(|choice: &(&&str, &&str), score_cutoff: usize| {
+            query.distance_with_args(
+                choice.1.chars(),
+                &levenshtein::Args::default().score_cutoff(score_cutoff),
+            )
+        });
+
+        let best = extract_one(choices.iter(), &scorer);
+        assert_eq!(Some("yankees"), best.map(|(_, (key, _), _)| *key));
+    }
+
+    #[test]
+    fn test_extract_one_ties() {
+        let scorer = levenshtein_scorer("abc");
+        let best = extract_one(["abd", "abe", "abc", "abc"], &scorer);
+        assert_eq!(Some((2, "abc", 0)), best);
+
+        let scorer = ratio_scorer("abc");
+        let best = extract_one(["abd", "abe"], &scorer);
+        assert_eq!(Some(0), best.map(|(index, _, _)| index));
+    }
+
+    #[test]
+    fn test_extract() {
+        let scorer = levenshtein_scorer("abc");
+        let choices = ["abcd", "xyz", "abc", "ab", "axc"];
+
+        assert_eq!(
+            vec![
+                (2, "abc", 0),
+                (0, "abcd", 1),
+                (3, "ab", 1),
+                (4, "axc", 1),
+                (1, "xyz", 3)
+            ],
+            extract(choices, &scorer, None)
+        );
+        assert_eq!(
+            vec![(2, "abc", 0), (0, "abcd", 1)],
+            extract(choices, &scorer, Some(2))
+        );
+        assert_eq!(
+            vec![(2, "abc", 0), (0, "abcd", 1), (3, "ab", 1), (4, "axc", 1)],
+            extract_with_args(
+                &"abc",
+                choices,
+                |query: &&str| levenshtein_scorer(query),
+                None,
+                &Args::default().score_cutoff(1)
+            )
+        );
+        assert!(extract(choices, &scorer, Some(0)).is_empty());
+
+        let scorer = ratio_scorer("abc");
+        let results = extract(choices, &scorer, Some(3));
+        assert_eq!(
+            vec![2, 0, 3],
+            results
+                .iter()
+                .map(|(index, _, _)| *index)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_cdist() {
+        let queries = ["abc", "abd", ""];
+        let choices = ["abc", "xyz"];
+
+        let matrix = cdist(&queries, &choices, |query: &&str| {
+            let scorer = levenshtein::BatchComparator::new(query.chars());
+            move |choice: &&str| scorer.distance(choice.chars())
+        });
+        assert_eq!((3, 2), (matrix.rows(), matrix.cols()));
+        assert_eq!(&[0, 3, 1, 3, 3, 3], matrix.as_slice());
+        assert_eq!(Some(&1), matrix.get(1, 0));
+        assert_eq!(None, matrix.get(3, 0));
+        assert_eq!(None, matrix.get(0, 2));
+        assert_eq!(3, matrix[(2, 1)]);
+
+        let matrix = cdist(&queries, &[] as &[&str], |query: &&str| {
+            let scorer = levenshtein::BatchComparator::new(query.chars());
+            move |choice: &&str| scorer.distance(choice.chars())
+        });
+        assert_eq!((3, 0), (matrix.rows(), matrix.cols()));
+        assert!(matrix.into_vec().is_empty());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_cdist_with_workers() {
+        let names: Vec<String> = (0..50).map(|i| format!("name {}", i * 7)).collect();
+        let scorer = |query: &String| {
+            let scorer = fuzz::RatioBatchComparator::new(query.chars().collect::<Vec<_>>());
+            move |choice: &String| scorer.similarity(choice.chars())
+        };
+
+        let expected = cdist(&names, &names, scorer);
+        for workers in [0, 1, 3] {
+            assert_eq!(
+                expected,
+                cdist_with_workers(&names, &names, scorer, workers).unwrap()
+            );
+            assert_eq!(
+                expected,
+                cdist_symmetric_with_workers(&names, scorer, workers).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_cdist_symmetric() {
+        let names = ["abc", "abd", "", "xyz", "bc"];
+        let scorer = |query: &&str| {
+            let scorer = levenshtein::BatchComparator::new(query.chars());
+            move |choice: &&str| scorer.distance(choice.chars())
+        };
+
+        let matrix = cdist_symmetric(&names, scorer);
+        assert_eq!(cdist(&names, &names, scorer), matrix);
+        assert_eq!((5, 5), (matrix.rows(), matrix.cols()));
+
+        let matrix = cdist_symmetric(&[] as &[&str], scorer);
+        assert_eq!((0, 0), (matrix.rows(), matrix.cols()));
+    }
+
+    #[test]
+    fn test_extract_iter() {
+        let scorer = levenshtein_scorer("abc");
+        let choices = ["abcd", "xyz", "abc", "ab", "axc"];
+
+        assert_eq!(
+            vec![(0, "abcd", 1), (2, "abc", 0), (3, "ab", 1), (4, "axc", 1)],
+            extract_iter_with_args(
+                &"abc",
+                choices,
+                |query: &&str| levenshtein_scorer(query),
+                &Args::default().score_cutoff(1)
+            )
+            .collect::<Vec<_>>()
+        );
+        assert_eq!(5, extract_iter(choices, &scorer).count());
+    }
+
+    #[test]
+    fn test_processor() {
+        let choices = ["New York Mets!", "new york mets vs chicago cubs"];
+        assert_eq!(
+            Some((0, "New York Mets!", 1)),
+            extract_one(choices, &levenshtein_scorer("New York Mets"))
+        );
+
+        // the processor is applied to the query as well as to the choices
+        let scorer = |query: &String| {
+            let scorer = levenshtein::BatchComparator::new(query.chars().collect::<Vec<_>>());
+            Distance::new(move |choice: &String, score_cutoff: usize| {
+                scorer.distance_with_args(
+                    choice.chars(),
+                    &levenshtein::Args::default().score_cutoff(score_cutoff),
+                )
+            })
+        };
+        let args = Args::default().processor(|s: &&str| default_process(s));
+        assert_eq!(
+            Some((0, "New York Mets!", 0)),
+            extract_one_with_args(&"New York Mets", choices, scorer, &args)
+        );
+        assert_eq!(
+            vec![(0, "New York Mets!", 0)],
+            extract_with_args(&"NEW YORK METS", choices, scorer, Some(1), &args)
+        );
+        assert_eq!(
+            vec![(0, "New York Mets!", 0)],
+            extract_iter_with_args(&" new-york mets ", choices, scorer, &args.score_cutoff(0))
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,567 @@
+//! Scorers selectable at runtime
+//!
+//! The metrics in [`distance`](crate::distance) and [`fuzz`](crate::fuzz) are
+//! selected at compile time. [`ScorerKind`] allows selecting them at runtime instead,
+//! e.g. based on a configuration file. All scorers are compared using their
+//! normalized similarity, which is always in the range `[0.0, 1.0]`.
+//!
+
+use crate::distance::levenshtein::WeightTable;
+use crate::distance::{
+    damerau_levenshtein, hamming, indel, jaro, jaro_winkler, lcs_seq, levenshtein, osa, postfix,
+    prefix,
+};
+use crate::fuzz;
+use crate::HashableChar;
+
+use std::error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Kind of scorer selected at runtime
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::ScorerKind;
+///
+/// let scorer: ScorerKind = "levenshtein".parse().unwrap();
+/// assert_eq!(
+///     Some(0.5),
+///     scorer.normalized_similarity("abcd".chars(), "abef".chars(), None)
+/// );
+/// assert_eq!(
+///     None,
+///     scorer.normalized_similarity("abcd".chars(), "abef".chars(), Some(0.8))
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScorerKind {
+    /// see [`levenshtein`]
+    Levenshtein { weights: WeightTable },
+    /// see [`indel`]
+    Indel,
+    /// see [`osa`]
+    Osa,
+    /// see [`damerau_levenshtein`]
+    DamerauLevenshtein,
+    /// see [`jaro`]
+    Jaro,
+    /// see [`jaro_winkler`]
+    JaroWinkler { prefix_weight: f64 },
+    /// see [`hamming`]. When `pad` is `false`, sequences of different length are
+    /// never considered a match
+    Hamming { pad: bool },
+    /// see [`lcs_seq`]
+    LcsSeq,
+    /// see [`prefix`]
+    Prefix,
+    /// see [`postfix`]
+    Postfix,
+    /// see [`fuzz::ratio`]
+    Ratio,
+    /// see [`fuzz::partial_ratio`]
+    PartialRatio,
+    /// see [`fuzz::token_sort_ratio`]
+    TokenSortRatio,
+    /// see [`fuzz::partial_token_sort_ratio`]
+    PartialTokenSortRatio,
+    /// see [`fuzz::token_set_ratio`]
+    TokenSetRatio,
+    /// see [`fuzz::partial_token_set_ratio`]
+    PartialTokenSetRatio,
+    /// see [`fuzz::token_ratio`]
+    TokenRatio,
+    /// see [`fuzz::partial_token_ratio`]
+    PartialTokenRatio,
+    /// see [`fuzz::wratio`]
+    WRatio,
+    /// see [`fuzz::qratio`]
+    QRatio,
+}
+
+/// Alias of [`ScorerKind`] exported as `rapidfuzz::Scorer`
+///
+/// The enum is named `ScorerKind`, so it can be imported together with the
+/// [`process::Scorer`](crate::process::Scorer) trait without renaming either of them.
+pub type Scorer = ScorerKind;
+
+impl ScorerKind {
+    /// name used by [`Display`] and accepted by [`FromStr`]
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Levenshtein { .. } => "levenshtein",
+            Self::Indel => "indel",
+            Self::Osa => "osa",
+            Self::DamerauLevenshtein => "damerau_levenshtein",
+            Self::Jaro => "jaro",
+            Self::JaroWinkler { .. } => "jaro_winkler",
+            Self::Hamming { .. } => "hamming",
+            Self::LcsSeq => "lcs_seq",
+            Self::Prefix => "prefix",
+            Self::Postfix => "postfix",
+            Self::Ratio => "ratio",
+            Self::PartialRatio => "partial_ratio",
+            Self::TokenSortRatio => "token_sort_ratio",
+            Self::PartialTokenSortRatio => "partial_token_sort_ratio",
+            Self::TokenSetRatio => "token_set_ratio",
+            Self::PartialTokenSetRatio => "partial_token_set_ratio",
+            Self::TokenRatio => "token_ratio",
+            Self::PartialTokenRatio => "partial_token_ratio",
+            Self::WRatio => "wratio",
+            Self::QRatio => "qratio",
+        }
+    }
+
+    /// Normalized similarity in the range `[0.0, 1.0]` or `None` if it is below `score_cutoff`
+    pub fn normalized_similarity<Iter1, Iter2>(
+        &self,
+        s1: Iter1,
+        s2: Iter2,
+        score_cutoff: Option<f64>,
+    ) -> Option<f64>
+    where
+        Iter1: IntoIterator,
+        Iter1::IntoIter: DoubleEndedIterator + Clone,
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Iter1::Item: PartialEq<Iter2::Item> + HashableChar + Copy,
+        Iter2::Item: PartialEq<Iter1::Item> + HashableChar + Copy,
+    {
+        let cutoff = score_cutoff.unwrap_or(0.0);
+        let fuzz_args = fuzz::Args::default().score_cutoff(cutoff);
+        match *self {
+            Self::Levenshtein { weights } => levenshtein::normalized_similarity_with_args(
+                s1,
+                s2,
+                &levenshtein::Args::default()
+                    .weights(&weights)
+                    .score_cutoff(cutoff),
+            ),
+            Self::Indel => indel::normalized_similarity_with_args(
+                s1,
+                s2,
+                &indel::Args::default().score_cutoff(cutoff),
+            ),
+            Self::Osa => osa::normalized_similarity_with_args(
+                s1,
+                s2,
+                &osa::Args::default().score_cutoff(cutoff),
+            ),
+            Self::DamerauLevenshtein => damerau_levenshtein::normalized_similarity_with_args(
+                s1,
+                s2,
+                &damerau_levenshtein::Args::default().score_cutoff(cutoff),
+            ),
+            Self::Jaro => jaro::normalized_similarity_with_args(
+                s1,
+                s2,
+                &jaro::Args::default().score_cutoff(cutoff),
+            ),
+            Self::JaroWinkler { prefix_weight } => jaro_winkler::normalized_similarity_with_args(
+                s1,
+                s2,
+                &jaro_winkler::Args::default()
+                    .prefix_weight(prefix_weight)
+                    .score_cutoff(cutoff),
+            ),
+            Self::Hamming { pad: true } => hamming::normalized_similarity_with_args(
+                s1,
+                s2,
+                &hamming::Args::default().pad(true).score_cutoff(cutoff),
+            ),
+            Self::Hamming { pad: false } => hamming::normalized_similarity_with_args(
+                s1,
+                s2,
+                &hamming::Args::default().score_cutoff(cutoff),
+            )
+            .ok()
+            .flatten(),
+            Self::LcsSeq => lcs_seq::normalized_similarity_with_args(
+                s1,
+                s2,
+                &lcs_seq::Args::default().score_cutoff(cutoff),
+            ),
+            Self::Prefix => prefix::normalized_similarity_with_args(
+                s1,
+                s2,
+                &prefix::Args::default().score_cutoff(cutoff),
+            ),
+            Self::Postfix => postfix::normalized_similarity_with_args(
+                s1,
+                s2,
+                &postfix::Args::default().score_cutoff(cutoff),
+            ),
+            Self::Ratio => fuzz::ratio_with_args(s1, s2, &fuzz_args),
+            Self::PartialRatio => fuzz::partial_ratio_with_args(s1, s2, &fuzz_args),
+            Self::TokenSortRatio => fuzz::token_sort_ratio_with_args(s1, s2, &fuzz_args),
+            Self::PartialTokenSortRatio => {
+                fuzz::partial_token_sort_ratio_with_args(s1, s2, &fuzz_args)
+            }
+            Self::TokenSetRatio => fuzz::token_set_ratio_with_args(s1, s2, &fuzz_args),
+            Self::PartialTokenSetRatio => {
+                fuzz::partial_token_set_ratio_with_args(s1, s2, &fuzz_args)
+            }
+            Self::TokenRatio => fuzz::token_ratio_with_args(s1, s2, &fuzz_args),
+            Self::PartialTokenRatio => fuzz::partial_token_ratio_with_args(s1, s2, &fuzz_args),
+            Self::WRatio => fuzz::wratio_with_args(s1, s2, &fuzz_args),
+            Self::QRatio => fuzz::qratio_with_args(s1, s2, &fuzz_args),
+        }
+    }
+
+    /// creates a [`BatchScorer`] for `One x Many` comparisons with `s1`
+    pub fn batch<Iter1, Elem1>(&self, s1: Iter1) -> BatchScorer<Elem1>
+    where
+        Iter1: IntoIterator<Item = Elem1>,
+        Iter1::IntoIter: Clone,
+        Elem1: HashableChar + Copy,
+    {
+        BatchScorer::new(self, s1)
+    }
+}
+
+impl Display for ScorerKind {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", self.name())
+    }
+}
+
+/// Error returned when parsing a [`ScorerKind`] from an unknown name
+///
+/// The error message contains the name which failed to parse.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseScorerError(String);
+
+impl Display for ParseScorerError {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "unknown scorer: {}", self.0)
+    }
+}
+
+impl error::Error for ParseScorerError {}
+
+impl FromStr for ScorerKind {
+    type Err = ParseScorerError;
+
+    /// Parses the name of a scorer
+    ///
+    /// The name is compared ignoring case, `_` and `-`, so `"jaro_winkler"`,
+    /// `"JaroWinkler"` and `"jaro-winkler"` are all accepted. Scorers with parameters
+    /// use their default values.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s
+            .trim()
+            .chars()
+            .filter(|&ch| ch != '_' && ch != '-')
+            .map(|ch| ch.to_ascii_lowercase())
+            .collect();
+
+        Ok(match name.as_str() {
+            "levenshtein" => Self::Levenshtein {
+                weights: WeightTable::default(),
+            },
+            "indel" => Self::Indel,
+            "osa" => Self::Osa,
+            "dameraulevenshtein" => Self::DamerauLevenshtein,
+            "jaro" => Self::Jaro,
+            "jarowinkler" => Self::JaroWinkler { prefix_weight: 0.1 },
+            "hamming" => Self::Hamming { pad: true },
+            "lcsseq" => Self::LcsSeq,
+            "prefix" => Self::Prefix,
+            "postfix" => Self::Postfix,
+            "ratio" => Self::Ratio,
+            "partialratio" => Self::PartialRatio,
+            "tokensortratio" => Self::TokenSortRatio,
+            "partialtokensortratio" => Self::PartialTokenSortRatio,
+            "tokensetratio" => Self::TokenSetRatio,
+            "partialtokensetratio" => Self::PartialTokenSetRatio,
+            "tokenratio" => Self::TokenRatio,
+            "partialtokenratio" => Self::PartialTokenRatio,
+            "wratio" => Self::WRatio,
+            "qratio" => Self::QRatio,
+            _ => return Err(ParseScorerError(s.to_string())),
+        })
+    }
+}
+
+/// `One x Many` comparisons using a [`ScorerKind`] selected at runtime
+///
+/// # Examples
+///
+/// ```
+/// use rapidfuzz::ScorerKind;
+///
+/// let scorer: ScorerKind = "indel".parse().unwrap();
+/// let batch = scorer.batch("abcd".chars());
+/// assert_eq!(Some(0.5), batch.normalized_similarity("abef".chars(), None));
+/// assert_eq!(Some(1.0), batch.normalized_similarity("abcd".chars(), Some(0.8)));
+/// ```
+// created once per query, so the size of the fuzz comparators does not matter
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
+pub enum BatchScorer<Elem1> {
+    Levenshtein {
+        scorer: levenshtein::BatchComparator<Elem1>,
+        weights: WeightTable,
+    },
+    Indel(indel::BatchComparator<Elem1>),
+    Osa(osa::BatchComparator<Elem1>),
+    DamerauLevenshtein(damerau_levenshtein::BatchComparator<Elem1>),
+    Jaro(jaro::BatchComparator<Elem1>),
+    JaroWinkler {
+        scorer: jaro_winkler::BatchComparator<Elem1>,
+        prefix_weight: f64,
+    },
+    Hamming {
+        scorer: hamming::BatchComparator<Elem1>,
+        pad: bool,
+    },
+    LcsSeq(lcs_seq::BatchComparator<Elem1>),
+    Prefix(prefix::BatchComparator<Elem1>),
+    Postfix(postfix::BatchComparator<Elem1>),
+    Ratio(fuzz::RatioBatchComparator<Elem1>),
+    PartialRatio(fuzz::PartialRatioBatchComparator<Elem1>),
+    TokenSortRatio(fuzz::TokenSortRatioBatchComparator<Elem1>),
+    PartialTokenSortRatio(fuzz::PartialTokenSortRatioBatchComparator<Elem1>),
+    TokenSetRatio(fuzz::TokenSetRatioBatchComparator<Elem1>),
+    PartialTokenSetRatio(fuzz::PartialTokenSetRatioBatchComparator<Elem1>),
+    TokenRatio(fuzz::TokenRatioBatchComparator<Elem1>),
+    PartialTokenRatio(fuzz::PartialTokenRatioBatchComparator<Elem1>),
+    WRatio(fuzz::WRatioBatchComparator<Elem1>),
+    QRatio(fuzz::QRatioBatchComparator<Elem1>),
+}
+
+impl<Elem1> BatchScorer<Elem1>
+where
+    Elem1: HashableChar + Copy,
+{
+    pub fn new<Iter1>(scorer: &ScorerKind, s1: Iter1) -> Self
+    where
+        Iter1: IntoIterator<Item = Elem1>,
+        Iter1::IntoIter: Clone,
+    {
+        match *scorer {
+            ScorerKind::Levenshtein { weights } => Self::Levenshtein {
+                scorer: levenshtein::BatchComparator::new(s1),
+                weights,
+            },
+            ScorerKind::Indel => Self::Indel(indel::BatchComparator::new(s1)),
+            ScorerKind::Osa => Self::Osa(osa::BatchComparator::new(s1)),
+            ScorerKind::DamerauLevenshtein => {
+                Self::DamerauLevenshtein(damerau_levenshtein::BatchComparator::new(s1))
+            }
+            ScorerKind::Jaro => Self::Jaro(jaro::BatchComparator::new(s1)),
+            ScorerKind::JaroWinkler { prefix_weight } => Self::JaroWinkler {
+                scorer: jaro_winkler::BatchComparator::new(s1),
+                prefix_weight,
+            },
+            ScorerKind::Hamming { pad } => Self::Hamming {
+                scorer: hamming::BatchComparator::new(s1),
+                pad,
+            },
+            ScorerKind::LcsSeq => Self::LcsSeq(lcs_seq::BatchComparator::new(s1)),
+            ScorerKind::Prefix => Self::Prefix(prefix::BatchComparator::new(s1)),
+            ScorerKind::Postfix => Self::Postfix(postfix::BatchComparator::new(s1)),
+            ScorerKind::Ratio => Self::Ratio(fuzz::RatioBatchComparator::new(s1)),
+            ScorerKind::PartialRatio => {
+                Self::PartialRatio(fuzz::PartialRatioBatchComparator::new(s1))
+            }
+            ScorerKind::TokenSortRatio => {
+                Self::TokenSortRatio(fuzz::TokenSortRatioBatchComparator::new(s1))
+            }
+            ScorerKind::PartialTokenSortRatio => {
+                Self::PartialTokenSortRatio(fuzz::PartialTokenSortRatioBatchComparator::new(s1))
+            }
+            ScorerKind::TokenSetRatio => {
+                Self::TokenSetRatio(fuzz::TokenSetRatioBatchComparator::new(s1))
+            }
+            ScorerKind::PartialTokenSetRatio => {
+                Self::PartialTokenSetRatio(fuzz::PartialTokenSetRatioBatchComparator::new(s1))
+            }
+            ScorerKind::TokenRatio => Self::TokenRatio(fuzz::TokenRatioBatchComparator::new(s1)),
+            ScorerKind::PartialTokenRatio => {
+                Self::PartialTokenRatio(fuzz::PartialTokenRatioBatchComparator::new(s1))
+            }
+            ScorerKind::WRatio => Self::WRatio(fuzz::WRatioBatchComparator::new(s1)),
+            ScorerKind::QRatio => Self::QRatio(fuzz::QRatioBatchComparator::new(s1)),
+        }
+    }
+
+    /// Normalized similarity calculated similar to [`ScorerKind::normalized_similarity`]
+    pub fn normalized_similarity<Iter2>(&self, s2: Iter2, score_cutoff: Option<f64>) -> Option<f64>
+    where
+        Iter2: IntoIterator,
+        Iter2::IntoIter: DoubleEndedIterator + Clone,
+        Elem1: PartialEq<Iter2::Item>,
+        Iter2::Item: PartialEq<Elem1> + HashableChar + Copy,
+    {
+        let cutoff = score_cutoff.unwrap_or(0.0);
+        let fuzz_args = fuzz::Args::default().score_cutoff(cutoff);
+        match self {
+            Self::Levenshtein { scorer, weights } => scorer.normalized_similarity_with_args(
+                s2,
+                &levenshtein::Args::default()
+                    .weights(weights)
+                    .score_cutoff(cutoff),
+            ),
+            Self::Indel(scorer) => scorer
+                .normalized_similarity_with_args(s2, &indel::Args::default().score_cutoff(cutoff)),
+            Self::Osa(scorer) => scorer
+                .normalized_similarity_with_args(s2, &osa::Args::default().score_cutoff(cutoff)),
+            Self::DamerauLevenshtein(scorer) => scorer.normalized_similarity_with_args(
+                s2,
+                &damerau_levenshtein::Args::default().score_cutoff(cutoff),
+            ),
+            Self::Jaro(scorer) => scorer
+                .normalized_similarity_with_args(s2, &jaro::Args::default().score_cutoff(cutoff)),
+            Self::JaroWinkler {
+                scorer,
+                prefix_weight,
+            } => scorer.normalized_similarity_with_args(
+                s2,
+                &jaro_winkler::Args::default()
+                    .prefix_weight(*prefix_weight)
+                    .score_cutoff(cutoff),
+            ),
+            Self::Hamming { scorer, pad: true } => scorer.normalized_similarity_with_args(
+                s2,
+                &hamming::Args::default().pad(true).score_cutoff(cutoff),
+            ),
+            Self::Hamming { scorer, pad: false } => scorer
+                .normalized_similarity_with_args(s2, &hamming::Args::default().score_cutoff(cutoff))
+                .ok()
+                .flatten(),
+            Self::LcsSeq(scorer) => scorer.normalized_similarity_with_args(
+                s2,
+                &lcs_seq::Args::default().score_cutoff(cutoff),
+            ),
+            Self::Prefix(scorer) => scorer
+                .normalized_similarity_with_args(s2, &prefix::Args::default().score_cutoff(cutoff)),
+            Self::Postfix(scorer) => scorer.normalized_similarity_with_args(
+                s2,
+                &postfix::Args::default().score_cutoff(cutoff),
+            ),
+            Self::Ratio(scorer) => scorer.similarity_with_args(s2, &fuzz_args),
+            Self::PartialRatio(scorer) => scorer.similarity_with_args(s2, &fuzz_args),
+            Self::TokenSortRatio(scorer) => scorer.similarity_with_args(s2, &fuzz_args),
+            Self::PartialTokenSortRatio(scorer) => scorer.similarity_with_args(s2, &fuzz_args),
+            Self::TokenSetRatio(scorer) => scorer.similarity_with_args(s2, &fuzz_args),
+            Self::PartialTokenSetRatio(scorer) => scorer.similarity_with_args(s2, &fuzz_args),
+            Self::TokenRatio(scorer) => scorer.similarity_with_args(s2, &fuzz_args),
+            Self::PartialTokenRatio(scorer) => scorer.similarity_with_args(s2, &fuzz_args),
+            Self::WRatio(scorer) => scorer.similarity_with_args(s2, &fuzz_args),
+            Self::QRatio(scorer) => scorer.similarity_with_args(s2, &fuzz_args),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(Ok(ScorerKind::Osa), "osa".parse());
+        assert_eq!(Ok(ScorerKind::Osa), "OSA".parse());
+        assert_eq!(Ok(ScorerKind::LcsSeq), "LCSseq".parse());
+        assert_eq!(
+            Ok(ScorerKind::JaroWinkler { prefix_weight: 0.1 }),
+            "jaro-winkler".parse()
+        );
+        assert_eq!(Ok(ScorerKind::WRatio), "WRatio".parse());
+        assert_eq!(Ok(ScorerKind::WRatio), "wratio".parse::<crate::Scorer>());
+        assert_eq!(
+            Err(ParseScorerError("unknown".to_string())),
+            "unknown".parse::<ScorerKind>()
+        );
+        assert_eq!(
+            "unknown scorer: unknown",
+            "unknown".parse::<ScorerKind>().unwrap_err().to_string()
+        );
+
+        for name in [
+            "levenshtein",
+            "damerau_levenshtein",
+            "hamming",
+            "partial_token_sort_ratio",
+            "qratio",
+        ] {
+            assert_eq!(name, name.parse::<ScorerKind>().unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn test_matches_modules() {
+        let names = [
+            "levenshtein",
+            "indel",
+            "osa",
+            "damerau_levenshtein",
+            "jaro",
+            "jaro_winkler",
+            "hamming",
+            "lcs_seq",
+            "prefix",
+            "postfix",
+            "ratio",
+            "partial_ratio",
+            "token_sort_ratio",
+            "partial_token_sort_ratio",
+            "token_set_ratio",
+            "partial_token_set_ratio",
+            "token_ratio",
+            "partial_token_ratio",
+            "wratio",
+            "qratio",
+        ];
+        let s1 = "new york mets";
+        let s2 = "new york meats";
+        for name in names {
+            let scorer: ScorerKind = name.parse().unwrap();
+            let score = scorer.normalized_similarity(s1.chars(), s2.chars(), None);
+            assert!(score.is_some());
+            assert_eq!(
+                score,
+                scorer
+                    .batch(s1.chars())
+                    .normalized_similarity(s2.chars(), None)
+            );
+            assert_eq!(
+                None,
+                scorer.normalized_similarity(s1.chars(), "xyz".chars(), Some(0.9))
+            );
+        }
+
+        assert_eq!(
+            Some(levenshtein::normalized_similarity(s1.chars(), s2.chars())),
+            ScorerKind::Levenshtein {
+                weights: WeightTable::default()
+            }
+            .normalized_similarity(s1.chars(), s2.chars(), None)
+        );
+        assert_eq!(
+            Some(fuzz::wratio(s1.chars(), s2.chars())),
+            ScorerKind::WRatio.normalized_similarity(s1.chars(), s2.chars(), None)
+        );
+    }
+
+    #[test]
+    fn test_hamming_without_padding() {
+        let scorer = ScorerKind::Hamming { pad: false };
+        assert_eq!(
+            None,
+            scorer.normalized_similarity("abc".chars(), "abcd".chars(), None)
+        );
+        assert_eq!(
+            None,
+            scorer
+                .batch("abc".chars())
+                .normalized_similarity("abcd".chars(), None)
+        );
+        assert_eq!(
+            Some(1.0),
+            scorer.normalized_similarity("abc".chars(), "abc".chars(), None)
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Utilities
+//!
+//! Helpers to preprocess sequences before comparing them. A processor can be passed
+//! to the `Args` of each scorer, which applies it to both sequences. The `BatchComparator`s
+//! store the first sequence when they are created, so when using them the sequences have to
+//! be processed beforehand, e.g. using [`default_process`].
+//!
+//! ```
+//! use rapidfuzz::fuzz;
+//! use rapidfuzz::utils;
+//!
+//! assert_eq!(
+//!     1.0,
+//!     fuzz::ratio_with_args(
+//!         "this is a test".chars(),
+//!         "This is a test!".chars(),
+//!         &fuzz::Args::default().processor(utils::DefaultProcessor)
+//!     )
+//! );
+//! ```
+
+use crate::common::Processor;
+
+fn process_chars<Iter>(s: Iter) -> Vec<char>
+where
+    Iter: Iterator<Item = char>,
+{
+    let mut processed = Vec::new();
+    for ch in s {
+        if ch.is_alphanumeric() {
+            processed.extend(ch.to_lowercase());
+        } else {
+            processed.push(' ');
+        }
+    }
+
+    let start = processed
+        .iter()
+        .position(|&ch| ch != ' ')
+        .unwrap_or(processed.len());
+    let end = processed
+        .iter()
+        .rposition(|&ch| ch != ' ')
+        .map_or(start, |pos| pos + 1);
+    processed.truncate(end);
+    processed.drain(..start);
+    processed
+}
+
+/// Preprocesses a string by
+/// - replacing all non alphanumeric characters with whitespace
+/// - converting all characters to lowercase
+/// - trimming whitespace at the start and the end
+///
+/// Both alphanumeric characters and lowercase conversion follow the Unicode definition.
+///
+/// # Example
+/// ```
+/// use rapidfuzz::utils;
+///
+/// assert_eq!("hello  world", utils::default_process(" Hello, World!"));
+/// ```
+pub fn default_process(s: &str) -> String {
+    process_chars(s.chars()).into_iter().collect()
+}
+
+/// [`Processor`] for sequences of `char`, which applies [`default_process`]
+#[derive(Default, Copy, Clone, Debug)]
+pub struct DefaultProcessor;
+
+impl<Iter> Processor<Iter> for DefaultProcessor
+where
+    Iter: Iterator<Item = char>,
+{
+    type Output = std::vec::IntoIter<char>;
+
+    fn process(&self, s: Iter) -> Self::Output {
+        process_chars(s).into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_process() {
+        assert_eq!("", default_process(""));
+        assert_eq!("", default_process(" !?. "));
+        assert_eq!("new york", default_process("  New-York!"));
+        assert_eq!("a  b", default_process("a, b"));
+        assert_eq!("äöü 123", default_process("ÄÖÜ 123"));
+        assert_eq!("straße", default_process("STRAßE"));
+        assert_eq!(
+            vec!['a', 'b', 'c'],
+            DefaultProcessor.process("ABC".chars()).collect::<Vec<_>>()
+        );
+    }
+}